@@ -0,0 +1,66 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use scoundrel_algorithm::{BaseGraph, LabeledGraph, Passability, SpatialGraph, a_star};
+use scoundrel_geometry::{Grid2D, Point};
+
+/// Wraps a grid and collects its adjacency into a `Vec` on every expansion, reproducing
+/// the cost of the previous `Vec`-returning `BaseGraph::adjacent_nodes`.
+struct AllocatingGrid<'a>(&'a Grid2D<Passability>);
+
+impl BaseGraph for AllocatingGrid<'_> {
+    type NodeHandle = Point;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        self.0.adjacent_nodes(point).collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl LabeledGraph<Passability> for AllocatingGrid<'_> {
+    fn get(&self, point: Self::NodeHandle) -> Option<Passability> {
+        self.0.get(point).copied()
+    }
+}
+
+impl SpatialGraph for AllocatingGrid<'_> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        self.0.distance(pt0, pt1)
+    }
+}
+
+/// Builds a grid of vertical walls with alternating gaps at the top and bottom, forcing
+/// the search to snake across the whole map.
+fn serpentine_grid(size: i32) -> Grid2D<Passability> {
+    let mut grid = Grid2D::new(size, size, Passability::Passable);
+    for x in (2..size - 1).step_by(4) {
+        let gap = if (x / 4) % 2 == 0 { size - 1 } else { 0 };
+        for y in 0..size {
+            if y != gap {
+                grid.set(Point::new(x, y), Passability::Impassable);
+            }
+        }
+    }
+    grid
+}
+
+fn bench_a_star(c: &mut Criterion) {
+    let grid = serpentine_grid(64);
+    let start = Point::new(0, 0);
+    let end = Point::new(63, 63);
+
+    let mut group = c.benchmark_group("a_star_serpentine_64");
+    group.bench_function("iterator", |b| {
+        b.iter(|| a_star(black_box(&grid), start, end))
+    });
+    group.bench_function("allocating", |b| {
+        let allocating = AllocatingGrid(&grid);
+        b.iter(|| a_star(black_box(&allocating), start, end))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_a_star);
+criterion_main!(benches);
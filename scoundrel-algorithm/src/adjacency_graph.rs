@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Add;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use scoundrel_geometry::{Rect, bsp};
+
+use crate::graph::{BaseGraph, LabeledGraph, SpatialGraph};
+
+/// A distance function between two nodes of an `AdjacencyGraph`.
+///
+/// This is used both as the cost of traversing an edge and as the heuristic estimate
+/// between arbitrary nodes, so for `a_star` to return optimal paths it should never
+/// overestimate the true path cost. Any `Fn(N, N) -> D` closure implements this trait.
+pub trait NodeDistance<N> {
+    type Distance: Copy + Ord + Add<Output = Self::Distance> + Default;
+
+    /// Returns the distance between two nodes.
+    fn distance(&self, a: N, b: N) -> Self::Distance;
+}
+
+impl<N, D, F> NodeDistance<N> for F
+where
+    D: Copy + Ord + Add<Output = D> + Default,
+    F: Fn(N, N) -> D,
+{
+    type Distance = D;
+
+    fn distance(&self, a: N, b: N) -> D {
+        self(a, b)
+    }
+}
+
+/// A distance function that treats every edge as having a cost of one.
+///
+/// The distance between a node and itself is zero, and between any two distinct nodes
+/// is one.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct UnitDistance;
+
+impl<N: Eq> NodeDistance<N> for UnitDistance {
+    type Distance = u32;
+
+    fn distance(&self, a: N, b: N) -> u32 {
+        if a == b { 0 } else { 1 }
+    }
+}
+
+/// The label and outgoing edges of a single node in an `AdjacencyGraph`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+struct AdjacencyEntry<N, L> {
+    label: L,
+    neighbors: Vec<N>,
+}
+
+/// A general-purpose graph stored as adjacency lists, with a label of type `L` on each node.
+///
+/// Nodes are identified by handles of type `N`, which can be any small copyable key such
+/// as a room index or a `Point`. Edges may be undirected (`add_edge`) or directed
+/// (`add_directed_edge`); an undirected edge is simply a pair of directed edges.
+///
+/// Distances between nodes are computed by the pluggable `D: NodeDistance<N>`, which
+/// defaults to `UnitDistance`. The distance function is not serialized; deserializing a
+/// graph produces `D::default()`.
+///
+/// # Examples
+///
+/// ```
+/// # use scoundrel_algorithm::{AdjacencyGraph, Passability, a_star};
+/// let mut graph = AdjacencyGraph::new();
+/// graph.add_node("hall", Passability::Passable);
+/// graph.add_node("vault", Passability::Passable);
+/// graph.add_node("cellar", Passability::Passable);
+/// graph.add_edge("hall", "cellar");
+/// graph.add_edge("cellar", "vault");
+///
+/// let path = a_star(&graph, "hall", "vault").unwrap();
+/// assert_eq!(path, vec!["hall", "cellar", "vault"]);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct AdjacencyGraph<N: Eq + Hash, L, D = UnitDistance> {
+    nodes: HashMap<N, AdjacencyEntry<N, L>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    metric: D,
+}
+
+impl<N: Copy + Eq + Hash, L, D: Default> Default for AdjacencyGraph<N, L, D> {
+    fn default() -> Self {
+        Self::with_distance(D::default())
+    }
+}
+
+impl<N: Copy + Eq + Hash, L> AdjacencyGraph<N, L, UnitDistance> {
+    /// Creates an empty graph in which every edge has unit cost.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N: Copy + Eq + Hash, L, D> AdjacencyGraph<N, L, D> {
+    /// Creates an empty graph that measures distances with the given function.
+    pub fn with_distance(metric: D) -> Self {
+        Self {
+            nodes: HashMap::new(),
+            metric,
+        }
+    }
+
+    /// Adds a node with the given label.
+    ///
+    /// If the node is already present its label is replaced and its edges are kept.
+    ///
+    /// # Returns
+    ///
+    /// The previous label of the node, if it was already present.
+    pub fn add_node(&mut self, node: N, label: L) -> Option<L> {
+        match self.nodes.get_mut(&node) {
+            Some(entry) => Some(std::mem::replace(&mut entry.label, label)),
+            None => {
+                self.nodes.insert(
+                    node,
+                    AdjacencyEntry {
+                        label,
+                        neighbors: vec![],
+                    },
+                );
+                None
+            }
+        }
+    }
+
+    /// Removes a node along with all edges into and out of it.
+    ///
+    /// # Returns
+    ///
+    /// The label of the removed node, or `None` if it was not present.
+    pub fn remove_node(&mut self, node: N) -> Option<L> {
+        let entry = self.nodes.remove(&node)?;
+        for other in self.nodes.values_mut() {
+            other.neighbors.retain(|n| *n != node);
+        }
+        Some(entry.label)
+    }
+
+    /// Adds an undirected edge between two nodes.
+    ///
+    /// # Returns
+    ///
+    /// `true` if both nodes exist, `false` otherwise. Adding an edge that already exists
+    /// has no effect.
+    pub fn add_edge(&mut self, a: N, b: N) -> bool {
+        if !self.contains_node(a) || !self.contains_node(b) {
+            return false;
+        }
+        self.add_directed_edge(a, b);
+        self.add_directed_edge(b, a);
+        true
+    }
+
+    /// Adds a directed edge from `from` to `to`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if both nodes exist, `false` otherwise. Adding an edge that already exists
+    /// has no effect.
+    pub fn add_directed_edge(&mut self, from: N, to: N) -> bool {
+        if !self.contains_node(to) {
+            return false;
+        }
+        match self.nodes.get_mut(&from) {
+            Some(entry) => {
+                if !entry.neighbors.contains(&to) {
+                    entry.neighbors.push(to);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the edges between two nodes in both directions.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an edge in either direction was removed.
+    pub fn remove_edge(&mut self, a: N, b: N) -> bool {
+        let removed_ab = self.remove_directed_edge(a, b);
+        let removed_ba = self.remove_directed_edge(b, a);
+        removed_ab || removed_ba
+    }
+
+    /// Removes the directed edge from `from` to `to`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the edge was present and removed.
+    pub fn remove_directed_edge(&mut self, from: N, to: N) -> bool {
+        match self.nodes.get_mut(&from) {
+            Some(entry) => {
+                let len = entry.neighbors.len();
+                entry.neighbors.retain(|n| *n != to);
+                entry.neighbors.len() != len
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the graph contains the given node.
+    pub fn contains_node(&self, node: N) -> bool {
+        self.nodes.contains_key(&node)
+    }
+
+    /// Returns `true` if there is an edge from `from` to `to`.
+    pub fn contains_edge(&self, from: N, to: N) -> bool {
+        self.nodes
+            .get(&from)
+            .is_some_and(|entry| entry.neighbors.contains(&to))
+    }
+
+    /// Returns a reference to the label of the given node, if present.
+    pub fn label(&self, node: N) -> Option<&L> {
+        self.nodes.get(&node).map(|entry| &entry.label)
+    }
+
+    /// Returns a mutable reference to the label of the given node, if present.
+    pub fn label_mut(&mut self, node: N) -> Option<&mut L> {
+        self.nodes.get_mut(&node).map(|entry| &mut entry.label)
+    }
+
+    /// Returns an iterator over all nodes in the graph, in arbitrary order.
+    pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    /// Returns an iterator over all directed edges in the graph as `(from, to)` pairs.
+    ///
+    /// An undirected edge appears once in each direction.
+    pub fn edges(&self) -> impl Iterator<Item = (N, N)> + '_ {
+        self.nodes
+            .iter()
+            .flat_map(|(from, entry)| entry.neighbors.iter().map(move |to| (*from, *to)))
+    }
+
+    /// Returns the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of directed edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.nodes.values().map(|entry| entry.neighbors.len()).sum()
+    }
+
+    /// Removes all nodes and edges from the graph.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+}
+
+impl<L: Copy, D> AdjacencyGraph<Rect, L, D> {
+    /// Builds a graph of the leaves of a BSP tree, keyed by their bounds.
+    ///
+    /// Each leaf becomes a node labeled with its contents, and leaves that share an edge
+    /// in the tree are connected. This is the usual starting point for connecting rooms:
+    /// run `minimum_spanning_tree` over the result to pick corridors, and `bridges` to find
+    /// where locked doors can be placed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tree` - The BSP tree to read leaves from.
+    /// * `metric` - The distance function between leaf bounds.
+    pub fn from_bsp_leaves(tree: &bsp::Tree<L>, metric: D) -> Self {
+        let mut graph = Self::with_distance(metric);
+        for (_, node) in tree.leaves() {
+            graph.add_node(node.bounds, node.contents);
+            let entry = graph.nodes.get_mut(&node.bounds).unwrap();
+            for edge in &node.edges {
+                let bounds = tree[edge.neighbor].bounds;
+                if !entry.neighbors.contains(&bounds) {
+                    entry.neighbors.push(bounds);
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<N: Copy + Eq + Hash, L, D> BaseGraph for AdjacencyGraph<N, L, D> {
+    type NodeHandle = N;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        self.nodes
+            .get(&point)
+            .into_iter()
+            .flat_map(|entry| entry.neighbors.iter().copied())
+    }
+}
+
+impl<N: Copy + Eq + Hash, L: Copy, D> LabeledGraph<L> for AdjacencyGraph<N, L, D> {
+    fn get(&self, point: Self::NodeHandle) -> Option<L> {
+        self.label(point).copied()
+    }
+}
+
+impl<N: Copy + Eq + Hash, L, D: NodeDistance<N>> SpatialGraph
+    for AdjacencyGraph<N, L, D>
+{
+    type Distance = D::Distance;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        self.metric.distance(pt0, pt1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scoundrel_geometry::Point;
+
+    use super::*;
+    use crate::{Passability, a_star};
+
+    fn create_test_graph() -> AdjacencyGraph<u32, i32> {
+        // 0 -- 1 -- 2
+        // |    |
+        // 3 -- 4
+        let mut graph = AdjacencyGraph::new();
+        for node in 0..5 {
+            graph.add_node(node, (node as i32 + 1) * 10);
+        }
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(0, 3);
+        graph.add_edge(1, 4);
+        graph.add_edge(3, 4);
+        graph
+    }
+
+    fn sorted_neighbors<G: BaseGraph<NodeHandle = u32>>(
+        graph: &G,
+        node: u32,
+    ) -> Vec<u32> {
+        let mut neighbors: Vec<_> = graph.adjacent_nodes(node).collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn test_add_nodes_and_edges() {
+        let graph = create_test_graph();
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 10);
+        assert_eq!(sorted_neighbors(&graph, 1), vec![0, 2, 4]);
+        assert_eq!(sorted_neighbors(&graph, 2), vec![1]);
+        assert_eq!(sorted_neighbors(&graph, 7), Vec::<u32>::new());
+        assert!(graph.contains_edge(0, 3));
+        assert!(graph.contains_edge(3, 0));
+        assert!(!graph.contains_edge(0, 2));
+    }
+
+    #[test]
+    fn test_add_edge_missing_node() {
+        let mut graph = create_test_graph();
+        assert!(!graph.add_edge(0, 9));
+        assert!(!graph.add_directed_edge(9, 0));
+        assert_eq!(graph.edge_count(), 10);
+    }
+
+    #[test]
+    fn test_add_duplicate_edge() {
+        let mut graph = create_test_graph();
+        assert!(graph.add_edge(0, 1));
+        assert_eq!(graph.edge_count(), 10);
+    }
+
+    #[test]
+    fn test_directed_edges() {
+        let mut graph = AdjacencyGraph::new();
+        graph.add_node('a', ());
+        graph.add_node('b', ());
+        assert!(graph.add_directed_edge('a', 'b'));
+        assert!(graph.contains_edge('a', 'b'));
+        assert!(!graph.contains_edge('b', 'a'));
+        assert_eq!(graph.adjacent_nodes('b').count(), 0);
+    }
+
+    #[test]
+    fn test_replace_label() {
+        let mut graph = create_test_graph();
+        assert_eq!(graph.add_node(2, 99), Some(30));
+        assert_eq!(graph.get(2), Some(99));
+        assert_eq!(sorted_neighbors(&graph, 2), vec![1]);
+
+        *graph.label_mut(2).unwrap() += 1;
+        assert_eq!(graph.label(2), Some(&100));
+    }
+
+    #[test]
+    fn test_remove_node() {
+        let mut graph = create_test_graph();
+        assert_eq!(graph.remove_node(1), Some(20));
+        assert_eq!(graph.remove_node(1), None);
+        assert!(!graph.contains_node(1));
+        assert_eq!(graph.get(1), None);
+        assert_eq!(sorted_neighbors(&graph, 0), vec![3]);
+        assert_eq!(sorted_neighbors(&graph, 2), Vec::<u32>::new());
+        assert_eq!(sorted_neighbors(&graph, 4), vec![3]);
+        assert_eq!(graph.edge_count(), 4);
+    }
+
+    #[test]
+    fn test_remove_edge() {
+        let mut graph = create_test_graph();
+        assert!(graph.remove_edge(0, 1));
+        assert!(!graph.remove_edge(0, 1));
+        assert!(!graph.contains_edge(1, 0));
+        assert_eq!(sorted_neighbors(&graph, 1), vec![2, 4]);
+
+        assert!(graph.remove_directed_edge(3, 4));
+        assert!(!graph.contains_edge(3, 4));
+        assert!(graph.contains_edge(4, 3));
+    }
+
+    #[test]
+    fn test_unit_distance() {
+        let graph = create_test_graph();
+        assert_eq!(graph.distance(0, 0), 0);
+        assert_eq!(graph.distance(0, 4), 1);
+    }
+
+    #[test]
+    fn test_custom_distance() {
+        let mut graph =
+            AdjacencyGraph::with_distance(|a: Point, b: Point| (b - a).sqr_magnitude());
+        graph.add_node(Point::new(0, 0), Passability::Passable);
+        graph.add_node(Point::new(3, 4), Passability::Passable);
+        assert_eq!(graph.distance(Point::new(0, 0), Point::new(3, 4)), 25);
+    }
+
+    #[test]
+    fn test_a_star_on_adjacency_graph() {
+        let mut graph = AdjacencyGraph::new();
+        for node in 0..5 {
+            graph.add_node(node, Passability::Passable);
+        }
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(0, 3);
+        graph.add_edge(3, 4);
+        graph.add_edge(4, 2);
+
+        assert_eq!(a_star(&graph, 0, 2), Some(vec![0, 1, 2]));
+
+        graph.add_node(1, Passability::Impassable);
+        assert_eq!(a_star(&graph, 0, 2), Some(vec![0, 3, 4, 2]));
+
+        graph.remove_edge(4, 2);
+        assert_eq!(a_star(&graph, 0, 2), None);
+    }
+
+    #[test]
+    fn test_from_bsp_leaves() {
+        use scoundrel_geometry::{Axis2D, bsp::HalfSpace};
+
+        use crate::minimum_spanning_tree;
+
+        // +-----+-----+
+        // |     |  c  |
+        // |  a  +-----+
+        // |     |  b  |
+        // +-----+-----+
+        let mut tree =
+            bsp::Tree::new(Rect::with_points(Point::new(0, 0), Point::new(10, 10)), 0);
+        let split_x = HalfSpace {
+            axis: Axis2D::X,
+            offset: 5,
+            positive: true,
+        };
+        let [right, _] = tree.split(tree.root(), split_x, |_, _| 1).unwrap();
+        let split_y = HalfSpace {
+            axis: Axis2D::Y,
+            offset: 5,
+            positive: true,
+        };
+        tree.split(right, split_y, |_, _| 2);
+
+        let graph = AdjacencyGraph::from_bsp_leaves(&tree, |a: Rect, b: Rect| {
+            (b.center() - a.center()).sqr_magnitude()
+        });
+        let a = Rect::with_points(Point::new(0, 0), Point::new(5, 10));
+        let b = Rect::with_points(Point::new(5, 0), Point::new(10, 5));
+        let c = Rect::with_points(Point::new(5, 5), Point::new(10, 10));
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.get(a), Some(1));
+        assert_eq!(graph.get(b), Some(2));
+        assert!(graph.contains_edge(a, b) && graph.contains_edge(b, a));
+        assert!(graph.contains_edge(a, c) && graph.contains_edge(c, a));
+        assert!(graph.contains_edge(b, c) && graph.contains_edge(c, b));
+        assert_eq!(minimum_spanning_tree(&graph, a).len(), 2);
+    }
+
+    #[cfg(feature = "serde_json")]
+    mod serde_tests {
+        use super::*;
+
+        #[test]
+        fn test_serde_roundtrip() {
+            let graph = create_test_graph();
+            let json = serde_json::to_string(&graph).unwrap();
+            let restored: AdjacencyGraph<u32, i32> = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored.node_count(), graph.node_count());
+            assert_eq!(restored.edge_count(), graph.edge_count());
+            for node in graph.nodes() {
+                assert_eq!(restored.get(node), graph.get(node));
+                assert_eq!(
+                    sorted_neighbors(&restored, node),
+                    sorted_neighbors(&graph, node)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use scoundrel_geometry::bsp::{HalfSpace, NodeId, Tree};
+use scoundrel_geometry::{Axis2D, Grid2D, OrthoLine, Point, Rect};
+use scoundrel_util::SeededRng;
+
+use crate::Passability;
+use crate::spanning_tree::DisjointSet;
+
+/// Parameters controlling the layout produced by `BspDungeon::generate`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BspDungeonConfig {
+    /// The smallest width or height a leaf may be split down to.
+    pub min_leaf_size: i32,
+    /// Leaves wider or taller than this are always split further.
+    ///
+    /// Must be at least twice `min_leaf_size`, so that every oversized leaf can be split.
+    pub max_leaf_size: i32,
+    /// The smallest fraction of a leaf's length at which it may be split.
+    pub min_split_ratio: f32,
+    /// The largest fraction of a leaf's length at which it may be split.
+    pub max_split_ratio: f32,
+    /// Leaves whose longer side exceeds their shorter side by this factor are always cut
+    /// across the longer side; others are cut along a random axis.
+    pub aspect_ratio: f32,
+    /// The smallest width or height of a room.
+    pub min_room_size: i32,
+    /// The number of wall tiles kept between each room and the edges of its leaf, so
+    /// rooms in neighboring leaves never merge.
+    pub room_margin: i32,
+    /// The probability of adding a corridor between neighboring leaves that are already
+    /// connected, creating loops.
+    pub loop_chance: f32,
+}
+
+impl Default for BspDungeonConfig {
+    fn default() -> Self {
+        BspDungeonConfig {
+            min_leaf_size: 8,
+            max_leaf_size: 20,
+            min_split_ratio: 0.35,
+            max_split_ratio: 0.65,
+            aspect_ratio: 1.25,
+            min_room_size: 3,
+            room_margin: 1,
+            loop_chance: 0.1,
+        }
+    }
+}
+
+/// A corridor connecting the rooms of two neighboring leaves of a `BspDungeon`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Corridor {
+    /// The leaf the corridor starts in.
+    pub from: NodeId,
+    /// The leaf the corridor ends in.
+    pub to: NodeId,
+    /// The tiles of the corridor, from the center of the first room to the center of the
+    /// second.
+    pub path: Vec<Point>,
+}
+
+/// A dungeon generated by recursive binary space partitioning.
+///
+/// The dungeon's bounds are split recursively until every leaf is at most
+/// `max_leaf_size` on each side, a room is carved into each leaf, and corridors join rooms
+/// across the `HalfEdge`s shared by neighboring leaves. Corridors form a random spanning
+/// tree over the leaves, plus occasional extra connections that create loops, so every
+/// room is reachable from every other.
+///
+/// Generation is driven by a `SeededRng`, so the same bounds, configuration and seed
+/// always produce the same dungeon.
+pub struct BspDungeon {
+    /// The partition of the dungeon, with each leaf holding its room.
+    pub tree: Tree<Option<Rect>>,
+    /// The corridors joining the rooms.
+    pub corridors: Vec<Corridor>,
+}
+
+impl BspDungeon {
+    /// Generates a dungeon covering `bounds`.
+    ///
+    /// Leaves are only smaller than `min_leaf_size` when `bounds` itself is. A leaf too
+    /// small to hold a room of `min_room_size` and its margins is left without a room, so
+    /// a dungeon with bounds that small has no rooms or corridors at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is inconsistent: if `max_leaf_size` is less than twice
+    /// `min_leaf_size`, if a minimal leaf cannot hold a minimal room and its margins, or
+    /// if the split ratios do not satisfy `0 < min_split_ratio <= max_split_ratio < 1`.
+    pub fn generate(bounds: Rect, config: &BspDungeonConfig, seed: u64) -> BspDungeon {
+        assert!(
+            config.max_leaf_size >= 2 * config.min_leaf_size,
+            "max leaf size must be at least twice the min leaf size"
+        );
+        assert!(
+            config.min_room_size > 0
+                && config.min_leaf_size >= config.min_room_size + 2 * config.room_margin,
+            "a leaf of the min size must fit a room of the min size and its margins"
+        );
+        assert!(
+            0.0 < config.min_split_ratio
+                && config.min_split_ratio <= config.max_split_ratio
+                && config.max_split_ratio < 1.0,
+            "split ratios must lie strictly between 0 and 1"
+        );
+
+        let mut rng = SeededRng::new(seed);
+        let mut dungeon = BspDungeon {
+            tree: Tree::new(bounds, None),
+            corridors: vec![],
+        };
+        dungeon.subdivide(config, &mut rng);
+        dungeon.place_rooms(config, &mut rng);
+        dungeon.connect_leaves(config, &mut rng);
+        dungeon
+    }
+
+    /// Returns an iterator over every leaf that holds a room, and its room.
+    pub fn rooms(&self) -> impl Iterator<Item = (NodeId, Rect)> + '_ {
+        self.tree
+            .leaves()
+            .filter_map(|(id, node)| node.contents.map(|room| (id, room)))
+    }
+
+    /// Renders the dungeon into a grid, with rooms and corridors passable and everything
+    /// else impassable.
+    ///
+    /// The grid covers the dungeon's bounds: cell `(0, 0)` corresponds to the minimum
+    /// corner of the bounds.
+    pub fn to_grid(&self) -> Grid2D<Passability> {
+        let bounds = self.tree[self.tree.root()].bounds;
+        let size = bounds.size();
+        let mut grid = Grid2D::new(size.x, size.y, Passability::Impassable);
+        for (_, room) in self.rooms() {
+            room.for_each(|pt| {
+                grid.set(pt - bounds.min, Passability::Passable);
+            });
+        }
+        for corridor in &self.corridors {
+            for pt in &corridor.path {
+                grid.set(*pt - bounds.min, Passability::Passable);
+            }
+        }
+        grid
+    }
+
+    fn subdivide(&mut self, config: &BspDungeonConfig, rng: &mut SeededRng) {
+        let mut pending = vec![self.tree.root()];
+        while let Some(id) = pending.pop() {
+            let bounds = self.tree[id].bounds;
+            let size = bounds.size();
+            if size.x <= config.max_leaf_size && size.y <= config.max_leaf_size {
+                continue;
+            }
+            let Some(axis) = choose_axis(size.x, size.y, config, rng) else {
+                continue;
+            };
+            let length = size[axis];
+            let ratio = config.min_split_ratio
+                + rng.next_f32() * (config.max_split_ratio - config.min_split_ratio);
+            let cut = ((length as f32 * ratio).round() as i32)
+                .clamp(config.min_leaf_size, length - config.min_leaf_size);
+            let half_space = HalfSpace {
+                axis,
+                offset: bounds.min[axis] + cut,
+                positive: true,
+            };
+            if let Some(children) = self.tree.split(id, half_space, |_, _| None) {
+                pending.extend(children);
+            }
+        }
+    }
+
+    fn place_rooms(&mut self, config: &BspDungeonConfig, rng: &mut SeededRng) {
+        let leaves: Vec<(NodeId, Rect)> = self
+            .tree
+            .leaves()
+            .map(|(id, node)| (id, node.bounds))
+            .collect();
+        for (id, bounds) in leaves {
+            let space = bounds.size() - Point::new(2, 2) * config.room_margin;
+            if space.x < config.min_room_size || space.y < config.min_room_size {
+                continue;
+            }
+            let size = Point::new(
+                rng.range(config.min_room_size..space.x + 1),
+                rng.range(config.min_room_size..space.y + 1),
+            );
+            let min = bounds.min
+                + Point::new(config.room_margin, config.room_margin)
+                + Point::new(
+                    rng.range(0..space.x - size.x + 1),
+                    rng.range(0..space.y - size.y + 1),
+                );
+            *self.tree.contents_mut(id).unwrap() = Some(Rect::with_size(min, size));
+        }
+    }
+
+    fn connect_leaves(&mut self, config: &BspDungeonConfig, rng: &mut SeededRng) {
+        let mut candidates: Vec<(NodeId, NodeId, OrthoLine)> = self
+            .tree
+            .leaves()
+            .flat_map(|(id, node)| {
+                node.edges
+                    .iter()
+                    .filter(move |edge| id < edge.neighbor)
+                    .map(move |edge| (id, edge.neighbor, edge.line))
+            })
+            .collect();
+        rng.shuffle(&mut candidates);
+
+        // Kruskal's algorithm over randomly ordered edges gives a random spanning tree
+        let mut components = DisjointSet::new();
+        for (from, to, line) in candidates {
+            if components.find(from) == components.find(to)
+                && !rng.chance(config.loop_chance)
+            {
+                continue;
+            }
+            // only join the components once the corridor actually exists, so a failed
+            // corridor leaves the edge to a later candidate
+            if let Some(corridor) = self.corridor(from, to, line, rng) {
+                components.union(from, to);
+                self.corridors.push(corridor);
+            }
+        }
+    }
+
+    /// Lays out a corridor between the rooms of two neighboring leaves, crossing the
+    /// boundary between them at a random point along the shared edge `line`.
+    fn corridor(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        line: OrthoLine,
+        rng: &mut SeededRng,
+    ) -> Option<Corridor> {
+        let other = self.tree[to]
+            .edges
+            .iter()
+            .find(|edge| edge.neighbor == from)?
+            .line;
+        let axis = line.axis;
+        let lo = line.start[axis].max(other.start[axis]);
+        let hi = line.end()[axis].min(other.end()[axis]);
+        if hi < lo {
+            return None;
+        }
+        let crossing = rng.range(lo..hi + 1);
+        let (mut door_from, mut door_to) = (line.start, other.start);
+        door_from[axis] = crossing;
+        door_to[axis] = crossing;
+
+        let room_center = |id: NodeId| self.tree[id].contents.map(|room| room.center());
+        let mut path = l_path(room_center(from)?, door_from, rng);
+        path.extend(l_path(door_to, room_center(to)?, rng));
+        Some(Corridor { from, to, path })
+    }
+}
+
+/// Picks the axis to cut a leaf of the given size across, or `None` if it is too small
+/// to cut at all.
+fn choose_axis(
+    width: i32,
+    height: i32,
+    config: &BspDungeonConfig,
+    rng: &mut SeededRng,
+) -> Option<Axis2D> {
+    let can_split = |length: i32| length >= 2 * config.min_leaf_size;
+    let preferred = if width as f32 > height as f32 * config.aspect_ratio {
+        Axis2D::X
+    } else if height as f32 > width as f32 * config.aspect_ratio {
+        Axis2D::Y
+    } else if rng.chance(0.5) {
+        Axis2D::X
+    } else {
+        Axis2D::Y
+    };
+    let length = |axis| match axis {
+        Axis2D::X => width,
+        Axis2D::Y => height,
+    };
+    [preferred, preferred.opposite()]
+        .into_iter()
+        .find(|axis| can_split(length(*axis)))
+}
+
+/// Returns the tiles of an L-shaped path from `start` to `end`, inclusive, turning
+/// either horizontally or vertically first at random.
+fn l_path(start: Point, end: Point, rng: &mut SeededRng) -> Vec<Point> {
+    let corner = if rng.chance(0.5) {
+        Point::new(end.x, start.y)
+    } else {
+        Point::new(start.x, end.y)
+    };
+    let mut path = vec![start];
+    for target in [corner, end] {
+        let mut pt = *path.last().unwrap();
+        while pt != target {
+            pt += Point::new((target.x - pt.x).signum(), (target.y - pt.y).signum());
+            path.push(pt);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BaseGraph, a_star};
+
+    fn bounds(width: i32, height: i32) -> Rect {
+        Rect::with_size(Point::new(0, 0), Point::new(width, height))
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let config = BspDungeonConfig::default();
+        let a = BspDungeon::generate(bounds(80, 50), &config, 7);
+        let b = BspDungeon::generate(bounds(80, 50), &config, 7);
+        let c = BspDungeon::generate(bounds(80, 50), &config, 8);
+        assert_eq!(a.rooms().collect::<Vec<_>>(), b.rooms().collect::<Vec<_>>());
+        assert_eq!(a.corridors, b.corridors);
+        assert_eq!(a.to_grid().data, b.to_grid().data);
+        assert_ne!(a.to_grid().data, c.to_grid().data);
+    }
+
+    #[test]
+    fn test_leaves_and_rooms_respect_config() {
+        let config = BspDungeonConfig::default();
+        for seed in 0..20 {
+            let dungeon = BspDungeon::generate(bounds(90, 60), &config, seed);
+            assert!(dungeon.tree.leaves().count() > 4);
+            for (_, leaf) in dungeon.tree.leaves() {
+                let size = leaf.bounds.size();
+                assert!(size.x >= config.min_leaf_size && size.x <= config.max_leaf_size);
+                assert!(size.y >= config.min_leaf_size && size.y <= config.max_leaf_size);
+
+                let room = leaf.contents.unwrap();
+                let inner = Rect::with_points(
+                    leaf.bounds.min + Point::new(1, 1),
+                    leaf.bounds.max - Point::new(1, 1),
+                );
+                assert!(inner.contains_bounds(&room));
+                assert!(room.size().x >= config.min_room_size);
+                assert!(room.size().y >= config.min_room_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_aspect_ratio_picks_long_axis() {
+        let config = BspDungeonConfig {
+            min_leaf_size: 5,
+            max_leaf_size: 12,
+            ..Default::default()
+        };
+        let dungeon = BspDungeon::generate(bounds(100, 10), &config, 3);
+        // every cut goes across the corridor-like strip, never along it
+        for (_, leaf) in dungeon.tree.leaves() {
+            assert_eq!(leaf.bounds.size().y, 10);
+        }
+        assert!(dungeon.tree.leaves().count() >= 9);
+    }
+
+    #[test]
+    fn test_rooms_are_connected() {
+        let config = BspDungeonConfig {
+            loop_chance: 0.5,
+            ..Default::default()
+        };
+        for seed in 0..10 {
+            let dungeon = BspDungeon::generate(bounds(70, 45), &config, seed);
+            let grid = dungeon.to_grid();
+            let rooms: Vec<Rect> = dungeon.rooms().map(|(_, room)| room).collect();
+            assert!(dungeon.corridors.len() >= rooms.len() - 1);
+            for room in &rooms[1..] {
+                assert!(a_star(&grid, rooms[0].center(), room.center()).is_some());
+            }
+
+            // corridors only join neighboring leaves, and stay inside them
+            for corridor in &dungeon.corridors {
+                assert!(
+                    dungeon
+                        .tree
+                        .neighbors(corridor.from)
+                        .any(|n| n == corridor.to)
+                );
+                let (from, to) = (
+                    dungeon.tree[corridor.from].bounds,
+                    dungeon.tree[corridor.to].bounds,
+                );
+                assert!(
+                    corridor
+                        .path
+                        .iter()
+                        .all(|pt| from.contains(*pt) || to.contains(*pt))
+                );
+                assert!(
+                    corridor
+                        .path
+                        .windows(2)
+                        .all(|step| grid.adjacent_nodes(step[0]).any(|n| n == step[1]))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_offset_bounds() {
+        let area = Rect::with_size(Point::new(-30, 12), Point::new(40, 40));
+        let dungeon = BspDungeon::generate(area, &BspDungeonConfig::default(), 1);
+        let grid = dungeon.to_grid();
+        assert_eq!((grid.width(), grid.height()), (40, 40));
+        for (_, room) in dungeon.rooms() {
+            assert!(area.contains_bounds(&room));
+            assert_eq!(grid.get(room.min - area.min), Some(&Passability::Passable));
+        }
+    }
+
+    #[test]
+    fn test_bounds_too_small_for_rooms() {
+        let config = BspDungeonConfig::default();
+        for area in [bounds(4, 4), bounds(0, 0), bounds(100, 4)] {
+            let dungeon = BspDungeon::generate(area, &config, 0);
+            assert_eq!(dungeon.rooms().count(), 0);
+            assert!(dungeon.corridors.is_empty());
+            let grid = dungeon.to_grid();
+            assert!(grid.data.iter().all(|p| *p == Passability::Impassable));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inconsistent_config() {
+        let config = BspDungeonConfig {
+            min_leaf_size: 12,
+            max_leaf_size: 20,
+            ..Default::default()
+        };
+        BspDungeon::generate(bounds(50, 50), &config, 0);
+    }
+}
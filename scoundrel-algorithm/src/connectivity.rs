@@ -0,0 +1,231 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::BaseGraph;
+
+/// Articulation points and bridges of a connected component, found in a single pass.
+struct CutStructure<N> {
+    articulation_points: HashSet<N>,
+    bridges: Vec<(N, N)>,
+}
+
+/// Runs Tarjan's lowlink search over the component containing `root`.
+///
+/// The search is iterative so that large grids do not overflow the stack.
+fn find_cut_structure<G: BaseGraph>(
+    graph: &G,
+    root: G::NodeHandle,
+) -> CutStructure<G::NodeHandle> {
+    let mut discovery = HashMap::new();
+    let mut low = HashMap::new();
+    let mut articulation_points = HashSet::new();
+    let mut bridges = vec![];
+    let mut root_children = 0;
+
+    discovery.insert(root, 0);
+    low.insert(root, 0);
+    let mut time = 1;
+    let mut stack = vec![(root, None, graph.adjacent_nodes(root))];
+
+    while let Some((node, parent, neighbors)) = stack.last_mut() {
+        let (node, parent) = (*node, *parent);
+        if let Some(next) = neighbors.next() {
+            if Some(next) == parent {
+                continue;
+            }
+            if let Some(&next_discovery) = discovery.get(&next) {
+                let node_low = low.get_mut(&node).unwrap();
+                *node_low = (*node_low).min(next_discovery);
+            } else {
+                discovery.insert(next, time);
+                low.insert(next, time);
+                time += 1;
+                if node == root {
+                    root_children += 1;
+                }
+                stack.push((next, Some(node), graph.adjacent_nodes(next)));
+            }
+        } else {
+            stack.pop();
+            if let Some(parent) = parent {
+                let node_low = low[&node];
+                let parent_low = low.get_mut(&parent).unwrap();
+                *parent_low = (*parent_low).min(node_low);
+                if node_low > discovery[&parent] {
+                    bridges.push((parent, node));
+                }
+                if parent != root && node_low >= discovery[&parent] {
+                    articulation_points.insert(parent);
+                }
+            }
+        }
+    }
+
+    if root_children > 1 {
+        articulation_points.insert(root);
+    }
+    CutStructure {
+        articulation_points,
+        bridges,
+    }
+}
+
+/// Finds the articulation points of the component containing `root`.
+///
+/// An articulation point is a node whose removal splits its component into two or more
+/// pieces; on a dungeon map these are chokepoints that every route between some pair of
+/// areas must pass through. The graph is treated as undirected, so every edge should be
+/// reported by `adjacent_nodes` from both of its endpoints.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to search.
+/// * `root` - Any node of the component to search.
+pub fn articulation_points<G: BaseGraph>(
+    graph: &G,
+    root: G::NodeHandle,
+) -> HashSet<G::NodeHandle> {
+    find_cut_structure(graph, root).articulation_points
+}
+
+/// Finds the bridges of the component containing `root`.
+///
+/// A bridge is an edge whose removal splits its component into two pieces, which makes
+/// it a natural place for a locked door. The graph is treated as undirected, so every edge
+/// should be reported by `adjacent_nodes` from both of its endpoints.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to search.
+/// * `root` - Any node of the component to search.
+///
+/// # Returns
+///
+/// Each bridge as a `(parent, child)` pair, where `child` is on the side of the bridge
+/// that does not contain `root`.
+pub fn bridges<G: BaseGraph>(
+    graph: &G,
+    root: G::NodeHandle,
+) -> Vec<(G::NodeHandle, G::NodeHandle)> {
+    find_cut_structure(graph, root).bridges
+}
+
+#[cfg(test)]
+mod tests {
+    use scoundrel_geometry::{Grid2D, Point};
+
+    use super::*;
+    use crate::{AdjacencyGraph, ComposableGraph};
+
+    fn graph_from_edges(nodes: u32, edges: &[(u32, u32)]) -> AdjacencyGraph<u32, ()> {
+        let mut graph = AdjacencyGraph::new();
+        for node in 0..nodes {
+            graph.add_node(node, ());
+        }
+        for (a, b) in edges {
+            graph.add_edge(*a, *b);
+        }
+        graph
+    }
+
+    fn component_size<G: BaseGraph>(graph: &G, root: G::NodeHandle) -> usize {
+        let mut seen = HashSet::from([root]);
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            for next in graph.adjacent_nodes(node) {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    fn brute_force_articulation_points<G: BaseGraph>(
+        graph: &G,
+        nodes: &[G::NodeHandle],
+    ) -> HashSet<G::NodeHandle> {
+        let full = component_size(graph, nodes[0]);
+        nodes
+            .iter()
+            .copied()
+            .filter(|removed| {
+                let view = graph.mask(|n| n != *removed);
+                let start = nodes.iter().find(|n| *n != removed).unwrap();
+                component_size(&view, *start) < full - 1
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_two_rooms_joined_by_corridor() {
+        // triangle 0-1-2, corridor 2-3-4, triangle 4-5-6
+        let graph = graph_from_edges(
+            7,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 6),
+                (6, 4),
+            ],
+        );
+        let points = articulation_points(&graph, 0);
+        assert_eq!(points, HashSet::from([2, 3, 4]));
+
+        let mut found = bridges(&graph, 0);
+        found.sort();
+        assert_eq!(found, vec![(2, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn test_cycle_has_no_cuts() {
+        let graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert!(articulation_points(&graph, 0).is_empty());
+        assert!(bridges(&graph, 0).is_empty());
+    }
+
+    #[test]
+    fn test_root_articulation_point() {
+        // star centered on the root
+        let graph = graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(articulation_points(&graph, 0), HashSet::from([0]));
+        assert_eq!(bridges(&graph, 0).len(), 3);
+
+        // same star, searched from a leaf
+        assert_eq!(articulation_points(&graph, 1), HashSet::from([0]));
+    }
+
+    #[test]
+    fn test_single_node() {
+        let graph = graph_from_edges(1, &[]);
+        assert!(articulation_points(&graph, 0).is_empty());
+        assert!(bridges(&graph, 0).is_empty());
+    }
+
+    #[test]
+    fn test_matches_brute_force_on_grid() {
+        // two 3x3 rooms joined by a one tile wide corridor
+        let mut grid = Grid2D::new(9, 3, false);
+        for pt in grid.rect().contained_points() {
+            let in_room = pt.x < 3 || pt.x >= 6;
+            grid.set(pt, in_room || pt.y == 1);
+        }
+        let floor = grid.mask(|pt| grid.get(pt) == Some(&true));
+        let nodes: Vec<Point> = grid
+            .iter_coords()
+            .filter(|pt| grid.get(*pt) == Some(&true))
+            .collect();
+
+        let expected = brute_force_articulation_points(&floor, &nodes);
+        assert_eq!(articulation_points(&floor, nodes[0]), expected);
+        // the room tiles beside each corridor mouth are bypassed diagonally
+        assert_eq!(
+            expected,
+            HashSet::from([Point::new(3, 1), Point::new(4, 1), Point::new(5, 1)])
+        );
+        assert_eq!(bridges(&floor, nodes[0]).len(), 2);
+    }
+}
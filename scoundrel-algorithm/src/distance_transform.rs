@@ -0,0 +1,367 @@
+use scoundrel_geometry::{Grid2D, Point};
+
+/// Returns the index into `sites` of the site nearest to each column of a row.
+///
+/// Each site is a column `q` with a squared vertical distance `g` to its nearest feature,
+/// so its squared distance to column `x` is the parabola `(x - q)^2 + g`. This computes
+/// the lower envelope of those parabolas as described by Felzenszwalb and Huttenlocher.
+fn row_envelope(sites: &[(i32, i64)], width: i32) -> Vec<Option<usize>> {
+    if sites.is_empty() {
+        return vec![None; width as usize];
+    }
+    let intersect = |a: usize, b: usize| {
+        let (qa, ga) = sites[a];
+        let (qb, gb) = sites[b];
+        let (qa, qb) = (qa as i64, qb as i64);
+        ((ga + qa * qa) - (gb + qb * qb)) as f64 / (2 * (qa - qb)) as f64
+    };
+
+    // `hull[k]` is the lowest parabola from `starts[k]` until `starts[k + 1]`
+    let mut hull = vec![0];
+    let mut starts = vec![f64::NEG_INFINITY];
+    for idx in 1..sites.len() {
+        let mut s = intersect(idx, *hull.last().unwrap());
+        while s <= *starts.last().unwrap() {
+            hull.pop();
+            starts.pop();
+            s = intersect(idx, *hull.last().unwrap());
+        }
+        hull.push(idx);
+        starts.push(s);
+    }
+
+    let mut k = 0;
+    (0..width)
+        .map(|x| {
+            while k + 1 < hull.len() && starts[k + 1] < x as f64 {
+                k += 1;
+            }
+            Some(hull[k])
+        })
+        .collect()
+}
+
+/// Finds the nearest feature cell to every cell of a grid, by Euclidean distance.
+///
+/// Feature cells are those set to `true`, and are their own nearest feature. Cells are
+/// `None` only if the grid has no feature cells at all. Where several features are
+/// equally near, any one of them may be returned.
+///
+/// # Arguments
+///
+/// * `features` - The grid of feature cells, such as walls.
+pub fn nearest_feature_transform(features: &Grid2D<bool>) -> Grid2D<Option<Point>> {
+    let (width, height) = (features.width(), features.height());
+
+    // nearest feature row within each column
+    let mut column_nearest: Grid2D<Option<i32>> = Grid2D::new(width, height, None);
+    for x in 0..width {
+        let mut last = None;
+        for y in 0..height {
+            if features.get(Point::new(x, y)) == Some(&true) {
+                last = Some(y);
+            }
+            column_nearest.set(Point::new(x, y), last);
+        }
+        let mut last = None;
+        for y in (0..height).rev() {
+            let pt = Point::new(x, y);
+            if features.get(pt) == Some(&true) {
+                last = Some(y);
+            }
+            let above = *column_nearest.get(pt).unwrap();
+            let nearest = match (above, last) {
+                (Some(a), Some(b)) => Some(if y - a <= b - y { a } else { b }),
+                (a, b) => a.or(b),
+            };
+            column_nearest.set(pt, nearest);
+        }
+    }
+
+    let mut result = Grid2D::new(width, height, None);
+    let mut sites = Vec::with_capacity(width as usize);
+    for y in 0..height {
+        sites.clear();
+        sites.extend((0..width).filter_map(|x| {
+            let fy = (*column_nearest.get(Point::new(x, y)).unwrap())?;
+            let dy = (fy - y) as i64;
+            Some((x, dy * dy))
+        }));
+        for (x, site) in row_envelope(&sites, width).into_iter().enumerate() {
+            let nearest = site.map(|idx| {
+                let fx = sites[idx].0;
+                Point::new(fx, column_nearest.get(Point::new(fx, y)).unwrap().unwrap())
+            });
+            result.set(Point::new(x as i32, y), nearest);
+        }
+    }
+    result
+}
+
+/// Computes the exact squared Euclidean distance from every cell to the nearest feature
+/// cell.
+///
+/// Feature cells are those set to `true`. If there are no feature cells, every cell is
+/// `i32::MAX`.
+pub fn squared_euclidean_distance_transform(features: &Grid2D<bool>) -> Grid2D<i32> {
+    let nearest = nearest_feature_transform(features);
+    nearest.map_coords(|pt| match nearest.get(pt).unwrap() {
+        Some(feature) => (*feature - pt).sqr_magnitude(),
+        None => i32::MAX,
+    })
+}
+
+/// Computes the exact Euclidean distance from every cell to the nearest feature cell.
+///
+/// Feature cells are those set to `true`. If there are no feature cells, every cell is
+/// infinite.
+///
+/// # Examples
+///
+/// ```
+/// # use scoundrel_algorithm::euclidean_distance_transform;
+/// # use scoundrel_geometry::{Grid2D, Point};
+/// let walls = Grid2D::from_sparse_points(5, 5, false, vec![Point::new(0, 0)], true);
+/// let distance = euclidean_distance_transform(&walls);
+/// assert_eq!(distance.get(Point::new(3, 4)), Some(&5.0));
+/// ```
+pub fn euclidean_distance_transform(features: &Grid2D<bool>) -> Grid2D<f32> {
+    squared_euclidean_distance_transform(features).map(|d| match *d {
+        i32::MAX => f32::INFINITY,
+        d => (d as f32).sqrt(),
+    })
+}
+
+/// Runs a two-pass chamfer distance transform using the given unit-cost neighbor offsets.
+///
+/// `forward` holds the offsets of neighbors that precede a cell in row-major order; the
+/// backward pass uses their negations.
+fn chamfer_transform(features: &Grid2D<bool>, forward: &[Point]) -> Grid2D<i32> {
+    let relax = |result: &mut Grid2D<i32>, pt: Point, sign: i32| {
+        let best = forward
+            .iter()
+            .filter_map(|o| result.get(pt + *o * sign))
+            .map(|d| d.saturating_add(1))
+            .fold(*result.get(pt).unwrap(), i32::min);
+        result.set(pt, best);
+    };
+
+    let mut result = features.map(|f| if *f { 0 } else { i32::MAX });
+    for pt in features.iter_coords() {
+        relax(&mut result, pt, 1);
+    }
+    let coords: Vec<Point> = features.iter_coords().collect();
+    for pt in coords.into_iter().rev() {
+        relax(&mut result, pt, -1);
+    }
+    result
+}
+
+/// Computes the Chebyshev distance from every cell to the nearest feature cell.
+///
+/// This is the number of king's moves to the nearest cell set to `true`. If there are no
+/// feature cells, every cell is `i32::MAX`.
+pub fn chebyshev_distance_transform(features: &Grid2D<bool>) -> Grid2D<i32> {
+    chamfer_transform(
+        features,
+        &[
+            Point::new(-1, -1),
+            Point::new(0, -1),
+            Point::new(1, -1),
+            Point::new(-1, 0),
+        ],
+    )
+}
+
+/// Computes the Manhattan distance from every cell to the nearest feature cell.
+///
+/// If there are no feature cells, every cell is `i32::MAX`.
+pub fn manhattan_distance_transform(features: &Grid2D<bool>) -> Grid2D<i32> {
+    chamfer_transform(features, &[Point::new(0, -1), Point::new(-1, 0)])
+}
+
+/// Extracts the medial axis of the space between feature cells.
+///
+/// A cell is on the medial axis if it is not a feature and its nearest feature differs
+/// substantially from that of an orthogonal neighbor, meaning it lies on the ridge where
+/// the closest walls change. On open map areas this traces the center lines of corridors
+/// and the skeletons of rooms, which makes it useful for finding room centers and
+/// routing. The skeleton is not pruned, so small irregularities in walls produce short
+/// spurs.
+///
+/// # Arguments
+///
+/// * `features` - The grid of feature cells, such as walls.
+pub fn medial_axis(features: &Grid2D<bool>) -> Grid2D<bool> {
+    let nearest = nearest_feature_transform(features);
+    let mut result = Grid2D::new(features.width(), features.height(), false);
+    for pt in features.iter_coords() {
+        if features.get(pt) == Some(&true) {
+            continue;
+        }
+        let Some(fp) = *nearest.get(pt).unwrap() else {
+            continue;
+        };
+        for q in [pt + Point::new(1, 0), pt + Point::new(0, 1)] {
+            if features.get(q) != Some(&false) {
+                continue;
+            }
+            let fq = nearest.get(q).unwrap().unwrap();
+            // features at most diagonally adjacent belong to the same wall
+            if (fp - fq).sqr_magnitude() <= 2 {
+                continue;
+            }
+            let (dp, dq) = ((fp - pt).sqr_magnitude(), (fq - q).sqr_magnitude());
+            if dp >= dq {
+                result.set(pt, true);
+            }
+            if dq >= dp {
+                result.set(q, true);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scoundrel_util::SeededRng;
+
+    /// Returns a grid with roughly `density` percent of its cells set, the same for every
+    /// run with a given seed.
+    fn random_grid(width: i32, height: i32, seed: u32, density: u32) -> Grid2D<bool> {
+        let mut rng = SeededRng::new(seed as u64);
+        Grid2D::new(width, height, false)
+            .map_coords(|_| rng.range(0..100) < density as i32)
+    }
+
+    fn brute_force<F: Fn(Point) -> i32>(
+        features: &Grid2D<bool>,
+        metric: F,
+    ) -> Grid2D<i32> {
+        let walls: Vec<Point> = features
+            .iter_coords()
+            .filter(|pt| features.get(*pt) == Some(&true))
+            .collect();
+        features.map_coords(|pt| {
+            walls
+                .iter()
+                .map(|w| metric(*w - pt))
+                .min()
+                .unwrap_or(i32::MAX)
+        })
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for (seed, density) in [(1, 2), (7, 10), (42, 30), (1234, 60)] {
+            let features = random_grid(23, 17, seed, density);
+            assert_eq!(
+                squared_euclidean_distance_transform(&features).data,
+                brute_force(&features, |d| d.sqr_magnitude()).data
+            );
+            assert_eq!(
+                chebyshev_distance_transform(&features).data,
+                brute_force(&features, |d| d.x.abs().max(d.y.abs())).data
+            );
+            assert_eq!(
+                manhattan_distance_transform(&features).data,
+                brute_force(&features, |d| d.x.abs() + d.y.abs()).data
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_feature_is_nearest() {
+        let features = random_grid(19, 21, 99, 5);
+        let nearest = nearest_feature_transform(&features);
+        let distance = squared_euclidean_distance_transform(&features);
+        for pt in features.iter_coords() {
+            let feature = nearest.get(pt).unwrap().unwrap();
+            assert_eq!(features.get(feature), Some(&true));
+            assert_eq!((feature - pt).sqr_magnitude(), *distance.get(pt).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_no_features() {
+        let features = Grid2D::new(4, 3, false);
+        assert!(
+            euclidean_distance_transform(&features)
+                .data
+                .iter()
+                .all(|d| d.is_infinite())
+        );
+        assert!(
+            chebyshev_distance_transform(&features)
+                .data
+                .iter()
+                .all(|d| *d == i32::MAX)
+        );
+        assert!(
+            nearest_feature_transform(&features)
+                .data
+                .iter()
+                .all(Option::is_none)
+        );
+    }
+
+    #[test]
+    fn test_euclidean_values() {
+        let features =
+            Grid2D::from_sparse_points(6, 6, false, vec![Point::new(1, 1)], true);
+        let distance = euclidean_distance_transform(&features);
+        assert_eq!(distance.get(Point::new(1, 1)), Some(&0.0));
+        assert_eq!(distance.get(Point::new(4, 5)), Some(&5.0));
+        assert_eq!(distance.get(Point::new(2, 2)), Some(&2f32.sqrt()));
+    }
+
+    /// Builds a grid from rows of text, where `#` marks a feature.
+    fn from_rows(rows: &[&str]) -> Grid2D<bool> {
+        Grid2D::from_iter(
+            rows.iter().flat_map(|r| r.chars().map(|c| c == '#')),
+            rows[0].len() as i32,
+            rows.len() as i32,
+        )
+    }
+
+    #[test]
+    fn test_medial_axis_corridor() {
+        let features = from_rows(&[
+            "##########",
+            "..........",
+            "..........",
+            "..........",
+            "##########",
+        ]);
+        let axis = medial_axis(&features);
+        for x in 0..10 {
+            assert_eq!(axis.get(Point::new(x, 2)), Some(&true));
+            assert_eq!(axis.get(Point::new(x, 1)), Some(&false));
+            assert_eq!(axis.get(Point::new(x, 3)), Some(&false));
+        }
+    }
+
+    #[test]
+    fn test_medial_axis_room() {
+        let features = from_rows(&[
+            "#########",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#########",
+        ]);
+        let axis = medial_axis(&features);
+        // the center of the room and the ridge towards each corner are on the axis
+        assert_eq!(axis.get(Point::new(4, 3)), Some(&true));
+        assert_eq!(axis.get(Point::new(1, 1)), Some(&true));
+        assert_eq!(axis.get(Point::new(7, 5)), Some(&true));
+        // cells hugging the middle of a wall are not
+        assert_eq!(axis.get(Point::new(4, 1)), Some(&false));
+        assert_eq!(axis.get(Point::new(1, 3)), Some(&false));
+        assert_eq!(axis.get(Point::new(0, 0)), Some(&false));
+    }
+}
@@ -0,0 +1,494 @@
+use std::collections::{BinaryHeap, HashSet};
+
+use scoundrel_geometry::{
+    EdgePolicy, GradientMode, Grid2D, MooreNeighbor, Point, Vector2,
+};
+use scoundrel_util::MinHeapEntry;
+use scoundrel_util::numeric::NonNaN32;
+
+use crate::Passability;
+
+/// A field of directions leading every reachable cell of a grid towards the nearest of a
+/// set of goals.
+///
+/// Computing the field once lets any number of agents share it: each agent simply steps
+/// in the direction stored at its cell, instead of running `a_star` individually. The
+/// field is built from a grid of movement costs, where each cell holds the cost of
+/// entering it or `None` if it is impassable. Diagonal steps cost `SQRT_2` times as much.
+///
+/// When the goals or costs change, `set_goals` and `set_cost` repair only the part of the
+/// field that depended on what changed.
+#[derive(Clone)]
+pub struct FlowField {
+    costs: Grid2D<Option<f32>>,
+    goals: HashSet<Point>,
+    distances: Grid2D<f32>,
+    directions: Grid2D<Option<MooreNeighbor>>,
+}
+
+impl FlowField {
+    /// Creates a flow field over a grid of entry costs, with no goals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any cost is negative or NaN.
+    pub fn from_costs(costs: Grid2D<Option<f32>>) -> FlowField {
+        assert!(
+            costs.data.iter().flatten().all(|c| *c >= 0.0),
+            "costs must be non-negative"
+        );
+        let (width, height) = (costs.width(), costs.height());
+        FlowField {
+            costs,
+            goals: HashSet::new(),
+            distances: Grid2D::new(width, height, f32::INFINITY),
+            directions: Grid2D::new(width, height, None),
+        }
+    }
+
+    /// Creates a flow field over a passability grid, where every passable cell costs one
+    /// to enter.
+    pub fn new(passability: &Grid2D<Passability>) -> FlowField {
+        FlowField::from_costs(passability.map(|p| match p {
+            Passability::Passable => Some(1.0),
+            Passability::Impassable => None,
+        }))
+    }
+
+    /// Returns the current goals.
+    pub fn goals(&self) -> impl Iterator<Item = Point> + '_ {
+        self.goals.iter().copied()
+    }
+
+    /// Returns the grid of directions, where each reachable cell that is not a goal holds
+    /// the direction of its next step towards the nearest goal.
+    pub fn directions(&self) -> &Grid2D<Option<MooreNeighbor>> {
+        &self.directions
+    }
+
+    /// Returns the direction of the next step from `pt` towards the nearest goal.
+    ///
+    /// Returns `None` for goals, unreachable cells and points outside the grid.
+    pub fn direction(&self, pt: Point) -> Option<MooreNeighbor> {
+        self.directions.get(pt).copied().flatten()
+    }
+
+    /// Returns the total cost of travelling from `pt` to the nearest goal, or `None` if no
+    /// goal is reachable.
+    pub fn distance(&self, pt: Point) -> Option<f32> {
+        self.distances.get(pt).copied().filter(|d| d.is_finite())
+    }
+
+    /// Returns the cost of entering `pt`, or `None` if it is impassable or outside the
+    /// grid.
+    pub fn cost(&self, pt: Point) -> Option<f32> {
+        self.costs.get(pt).copied().flatten()
+    }
+
+    /// Replaces the set of goals and updates the field.
+    ///
+    /// Only cells that were routed to a removed goal are recomputed from scratch; the
+    /// rest of the field is kept and improved where a new goal is closer. Goals outside
+    /// the grid or on impassable cells are ignored.
+    pub fn set_goals<I: IntoIterator<Item = Point>>(&mut self, goals: I) {
+        let goals: HashSet<Point> = goals
+            .into_iter()
+            .filter(|pt| self.cost(*pt).is_some())
+            .collect();
+        let removed: Vec<Point> = self.goals.difference(&goals).copied().collect();
+        let added: Vec<Point> = goals.difference(&self.goals).copied().collect();
+        self.goals = goals;
+
+        let mut frontier = self.invalidate(&removed);
+        for goal in added {
+            self.distances.set(goal, 0.0);
+            self.directions.set(goal, None);
+            frontier.push(MinHeapEntry {
+                value: goal,
+                priority: NonNaN32::new(0.0),
+            });
+        }
+        self.propagate(frontier);
+    }
+
+    /// Changes the cost of entering a cell and updates the field.
+    ///
+    /// Setting the cost to `None` makes the cell impassable; if the cell was a goal, it
+    /// stops being one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cost` is negative or NaN.
+    pub fn set_cost(&mut self, pt: Point, cost: Option<f32>) {
+        assert!(cost.is_none_or(|c| c >= 0.0), "costs must be non-negative");
+        let Some(old) = self.costs.get(pt).copied() else {
+            return;
+        };
+        self.costs.set(pt, cost);
+        if cost.is_none() {
+            self.goals.remove(&pt);
+        }
+
+        let mut frontier = if cost.is_none() || cost > old {
+            // everything routed through this cell may now have a better route elsewhere
+            let mut invalid = vec![pt];
+            invalid.extend(
+                MooreNeighbor::ALL
+                    .iter()
+                    .map(|n| pt + n.offset())
+                    .filter(|nb| self.direction_target(*nb) == Some(pt)),
+            );
+            self.invalidate(&invalid)
+        } else {
+            BinaryHeap::new()
+        };
+        if self.goals.contains(&pt) {
+            self.distances.set(pt, 0.0);
+            self.directions.set(pt, None);
+        } else if cost.is_some() {
+            // the cell may now be reachable, or reachable more cheaply
+            self.seed_from_neighbors(pt, &mut frontier);
+        }
+        if self.distance(pt).is_some() {
+            frontier.push(MinHeapEntry {
+                value: pt,
+                priority: NonNaN32::new(*self.distances.get(pt).unwrap()),
+            });
+        }
+        self.propagate(frontier);
+    }
+
+    /// Returns the cell that the direction stored at `pt` leads to.
+    fn direction_target(&self, pt: Point) -> Option<Point> {
+        self.direction(pt).map(|n| pt + n.offset())
+    }
+
+    /// Returns the cost of stepping from `from` into the adjacent cell `to`.
+    fn step_cost(&self, from: Point, to: Point) -> Option<f32> {
+        let n = MooreNeighbor::ALL
+            .into_iter()
+            .find(|n| from + n.offset() == to)?;
+        Some(self.cost(to)? * n.offset_magnitude())
+    }
+
+    /// Clears the distance and direction of every cell routed through `roots`, returning
+    /// a frontier seeded from the surviving cells around them.
+    fn invalidate(
+        &mut self,
+        roots: &[Point],
+    ) -> BinaryHeap<MinHeapEntry<Point, NonNaN32>> {
+        let mut invalid = vec![];
+        let mut stack: Vec<Point> = roots.to_vec();
+        while let Some(pt) = stack.pop() {
+            if self.goals.contains(&pt)
+                || !self.distances.get(pt).is_some_and(|d| d.is_finite())
+            {
+                continue;
+            }
+            self.distances.set(pt, f32::INFINITY);
+            invalid.push(pt);
+            for n in MooreNeighbor::ALL {
+                let child = pt + n.offset();
+                if self.direction_target(child) == Some(pt) {
+                    stack.push(child);
+                }
+            }
+            self.directions.set(pt, None);
+        }
+
+        let mut frontier = BinaryHeap::new();
+        for pt in invalid {
+            self.seed_from_neighbors(pt, &mut frontier);
+        }
+        frontier
+    }
+
+    /// Points `pt` at its cheapest neighbor with a known distance, if that improves it,
+    /// and queues it for propagation.
+    fn seed_from_neighbors(
+        &mut self,
+        pt: Point,
+        frontier: &mut BinaryHeap<MinHeapEntry<Point, NonNaN32>>,
+    ) {
+        if self.cost(pt).is_none() {
+            return;
+        }
+        for n in MooreNeighbor::ALL {
+            let next = pt + n.offset();
+            let (Some(dist), Some(step)) =
+                (self.distance(next), self.step_cost(pt, next))
+            else {
+                continue;
+            };
+            if dist + step < *self.distances.get(pt).unwrap() {
+                self.distances.set(pt, dist + step);
+                self.directions.set(pt, Some(n));
+                frontier.push(MinHeapEntry {
+                    value: pt,
+                    priority: NonNaN32::new(dist + step),
+                });
+            }
+        }
+    }
+
+    /// Runs Dijkstra's algorithm outwards from the frontier, lowering distances wherever
+    /// a cheaper route to a goal is found.
+    fn propagate(&mut self, mut frontier: BinaryHeap<MinHeapEntry<Point, NonNaN32>>) {
+        while let Some(MinHeapEntry {
+            value: current,
+            priority,
+        }) = frontier.pop()
+        {
+            let dist: f32 = priority.into();
+            if dist > *self.distances.get(current).unwrap() {
+                // stale entry, a cheaper route has already been found
+                continue;
+            }
+            for n in MooreNeighbor::ALL {
+                let prev = current + n.offset();
+                let Some(step) = self.step_cost(prev, current) else {
+                    continue;
+                };
+                if self.cost(prev).is_none() || self.goals.contains(&prev) {
+                    continue;
+                }
+                let new_dist = dist + step;
+                if new_dist < *self.distances.get(prev).unwrap() {
+                    self.distances.set(prev, new_dist);
+                    self.directions.set(prev, Some(n.opposite()));
+                    frontier.push(MinHeapEntry {
+                        value: prev,
+                        priority: NonNaN32::new(new_dist),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Computes a smoothed unit direction for every reachable cell from the gradient of
+    /// the distance field.
+    ///
+    /// The discrete directions of `directions` only point at the eight neighbors, which
+    /// makes agents move in visible zigzags. Following the negated gradient of the
+    /// distance field instead gives continuous headings. To keep agents from being pushed
+    /// into walls, impassable cells take the distance of their closest reachable neighbor
+    /// plus one step. Goals, unreachable cells and cells where the gradient vanishes fall
+    /// back to the discrete direction, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The difference scheme used to compute the gradient.
+    pub fn smoothed_directions(
+        &self,
+        mode: GradientMode,
+    ) -> Grid2D<Option<Vector2<f32>>> {
+        let padded = self.distances.map_coords(|pt| match self.distance(pt) {
+            Some(dist) => dist,
+            None => {
+                MooreNeighbor::ALL
+                    .iter()
+                    .filter_map(|n| self.distance(pt + n.offset()))
+                    .fold(f32::INFINITY, f32::min)
+                    + 1.0
+            }
+        });
+        self.distances.map_coords(|pt| {
+            let fallback = self.direction(pt).map(|n| {
+                let offset = n.offset().map(|c| c as f32);
+                offset / offset.magnitude()
+            });
+            if self.distance(pt).is_none() || self.goals.contains(&pt) {
+                return fallback;
+            }
+            let gradient = padded.gradient_with(pt, mode, EdgePolicy::Clamp)?;
+            let length = gradient.magnitude();
+            if length.is_finite() && length > 1e-6 {
+                Some(gradient / -length)
+            } else {
+                fallback
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&str]) -> Grid2D<Passability> {
+        Grid2D::from_iter(
+            rows.iter().flat_map(|r| {
+                r.chars().map(|c| match c {
+                    '#' => Passability::Impassable,
+                    _ => Passability::Passable,
+                })
+            }),
+            rows[0].len() as i32,
+            rows.len() as i32,
+        )
+    }
+
+    /// Follows the field from `start`, returning the cells visited.
+    fn follow(field: &FlowField, start: Point) -> Vec<Point> {
+        let mut path = vec![start];
+        let mut pt = start;
+        while let Some(n) = field.direction(pt) {
+            pt += n.offset();
+            path.push(pt);
+            assert!(path.len() < 1000, "flow field contains a cycle");
+        }
+        path
+    }
+
+    fn assert_matches_full_rebuild(field: &FlowField) {
+        let mut fresh = FlowField::from_costs(field.costs.clone());
+        fresh.set_goals(field.goals());
+        for pt in field.costs.iter_coords() {
+            let (a, b) = (field.distance(pt), fresh.distance(pt));
+            assert!(
+                a == b || (a.unwrap() - b.unwrap()).abs() < 1e-4,
+                "{pt:?}: {a:?} != {b:?}"
+            );
+            // every direction must lead to a neighbor that is one step cheaper
+            if let Some(next) = field.direction_target(pt) {
+                let step = field.step_cost(pt, next).unwrap();
+                let expected = field.distance(next).unwrap() + step;
+                assert!((field.distance(pt).unwrap() - expected).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flow_field_reaches_goal() {
+        let grid = from_rows(&[
+            "..........",
+            ".########.",
+            ".#......#.",
+            ".#.####.#.",
+            "...#..#...",
+        ]);
+        let mut field = FlowField::new(&grid);
+        field.set_goals([Point::new(4, 2)]);
+
+        assert_eq!(field.direction(Point::new(4, 2)), None);
+        assert_eq!(field.distance(Point::new(4, 2)), Some(0.0));
+        assert_eq!(field.distance(Point::new(1, 1)), None);
+
+        for start in [Point::new(0, 0), Point::new(9, 4), Point::new(2, 3)] {
+            let path = follow(&field, start);
+            assert_eq!(path.last(), Some(&Point::new(4, 2)));
+            for pt in &path {
+                assert_eq!(grid.get(*pt), Some(&Passability::Passable));
+            }
+        }
+        // the pocket boxed in by walls cannot reach the goal
+        assert_eq!(field.direction(Point::new(4, 4)), None);
+        assert_eq!(field.distance(Point::new(4, 4)), None);
+    }
+
+    #[test]
+    fn test_flow_field_multiple_goals() {
+        let grid = from_rows(&["..........."]);
+        let mut field = FlowField::new(&grid);
+        field.set_goals([Point::new(0, 0), Point::new(10, 0)]);
+        assert_eq!(field.direction(Point::new(3, 0)), Some(MooreNeighbor::Left));
+        assert_eq!(
+            field.direction(Point::new(7, 0)),
+            Some(MooreNeighbor::Right)
+        );
+        assert_eq!(field.distance(Point::new(4, 0)), Some(4.0));
+    }
+
+    #[test]
+    fn test_flow_field_costs() {
+        // crossing the swamp in the middle row costs more than walking around it
+        let mut costs = Grid2D::new(5, 3, Some(1.0));
+        for x in 1..4 {
+            costs.set(Point::new(x, 1), Some(10.0));
+        }
+        let mut field = FlowField::from_costs(costs);
+        field.set_goals([Point::new(4, 1)]);
+        let path = follow(&field, Point::new(0, 1));
+        assert!(!path.contains(&Point::new(2, 1)));
+    }
+
+    #[test]
+    fn test_moving_goal_matches_rebuild() {
+        let grid = from_rows(&[
+            "............",
+            "..####..#...",
+            "..#.....#...",
+            "..#..####...",
+            "............",
+        ]);
+        let mut field = FlowField::new(&grid);
+        field.set_goals([Point::new(0, 0), Point::new(11, 4)]);
+        assert_matches_full_rebuild(&field);
+
+        for goal in [
+            Point::new(3, 2),
+            Point::new(4, 2),
+            Point::new(7, 4),
+            Point::new(0, 0),
+        ] {
+            field.set_goals([goal, Point::new(11, 4)]);
+            assert_matches_full_rebuild(&field);
+        }
+        field.set_goals([]);
+        assert!(field.directions().data.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_set_cost_matches_rebuild() {
+        let grid = from_rows(&["........", "........", "........", "........"]);
+        let mut field = FlowField::new(&grid);
+        field.set_goals([Point::new(7, 0)]);
+
+        // build a wall across the map, then knock a hole in it
+        for y in 0..4 {
+            field.set_cost(Point::new(4, y), None);
+            assert_matches_full_rebuild(&field);
+        }
+        assert_eq!(field.distance(Point::new(0, 0)), None);
+
+        field.set_cost(Point::new(4, 3), Some(1.0));
+        assert_matches_full_rebuild(&field);
+        assert_eq!(
+            follow(&field, Point::new(0, 0)).last(),
+            Some(&Point::new(7, 0))
+        );
+
+        field.set_cost(Point::new(4, 3), Some(5.0));
+        assert_matches_full_rebuild(&field);
+        field.set_cost(Point::new(5, 1), Some(0.5));
+        assert_matches_full_rebuild(&field);
+
+        // removing the goal cell itself
+        field.set_cost(Point::new(7, 0), None);
+        assert_eq!(field.goals().count(), 0);
+        assert_matches_full_rebuild(&field);
+    }
+
+    #[test]
+    fn test_smoothed_directions() {
+        let grid = from_rows(&["..........", "..........", "..........", ".........."]);
+        let mut field = FlowField::new(&grid);
+        field.set_goals([Point::new(9, 0)]);
+
+        let smoothed = field.smoothed_directions(GradientMode::Central);
+        for pt in grid.iter_coords() {
+            match smoothed.get(pt).unwrap() {
+                None => assert_eq!(pt, Point::new(9, 0)),
+                Some(dir) => {
+                    assert!((dir.magnitude() - 1.0).abs() < 1e-4);
+                    // always heads right and never away from the goal row
+                    assert!(dir.x > 0.0 || pt.x == 9);
+                    assert!(dir.y <= 1e-4);
+                }
+            }
+        }
+        // far from the goal the heading is between the discrete directions
+        let dir = smoothed.get(Point::new(0, 3)).unwrap().unwrap();
+        assert!(dir.y < 0.0 && dir.y > -std::f32::consts::FRAC_1_SQRT_2);
+    }
+}
@@ -2,15 +2,24 @@ use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::Add;
 
-use scoundrel_geometry::{Grid2D, MooreNeighbor, Point};
+use scoundrel_geometry::{
+    BitGrid, ChunkedGrid, Grid2D, Grid2DView, Grid2DViewMut, Grid3D, Hex, HexGrid,
+    MooreNeighbor, Neighborhood3D, Point, Point3,
+};
 
 /// A `BaseGraph` represents a graph data structure where nodes are identified by `NodeHandle`s.
 /// This trait provides a method for accessing the adjacent nodes of a given node in the graph.
 pub trait BaseGraph {
     type NodeHandle: Copy + Eq + Hash;
 
-    /// Returns a vector of all nodes that are adjacent to the given node.
-    fn adjacent_nodes(&self, point: Self::NodeHandle) -> Vec<Self::NodeHandle>;
+    /// Returns an iterator over all nodes that are adjacent to the given node.
+    ///
+    /// Implementations should avoid allocating here, as this is called once per node
+    /// expansion by search algorithms such as `a_star`.
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle>;
 }
 
 /// A `LabeledGraph` is a `BaseGraph` that also associates a label of type `Label` with each node.
@@ -49,7 +58,10 @@ pub struct GraphFunctorView<'a, Graph: ?Sized, T, Tp, F> {
 impl<'a, Graph: BaseGraph, T, Tp, F> BaseGraph for GraphFunctorView<'a, Graph, T, Tp, F> {
     type NodeHandle = Graph::NodeHandle;
 
-    fn adjacent_nodes(&self, point: Self::NodeHandle) -> Vec<Self::NodeHandle> {
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
         self.graph.adjacent_nodes(point)
     }
 }
@@ -62,6 +74,16 @@ impl<'a, Graph: LabeledGraph<T>, T: Copy, Tp: Copy, F: Fn(T) -> Tp> LabeledGraph
     }
 }
 
+impl<'a, Graph: SpatialGraph, T, Tp, F> SpatialGraph
+    for GraphFunctorView<'a, Graph, T, Tp, F>
+{
+    type Distance = Graph::Distance;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        self.graph.distance(pt0, pt1)
+    }
+}
+
 /// `TransformableGraph` is a trait that provides a method for creating a `GraphFunctorView` that applies
 /// a given functor to each node or edge label as it is accessed. The functor must be a function that
 /// takes a label of the original type and returns a label of the new type.
@@ -122,15 +144,215 @@ where
 {
 }
 
+/// A view of a graph with every node rejected by a predicate removed.
+///
+/// Masked nodes are never returned from `adjacent_nodes`, and `get` returns `None` for
+/// them, so `a_star` treats them as impassable and every `cast_light_2d` variant,
+/// including the bevel decisions of `cast_light_2d_beveled`, treats them as opaque.
+/// This is useful for temporarily blocking tiles, such as those occupied by monsters,
+/// without modifying the underlying map.
+pub struct GraphMaskView<'a, Graph: ?Sized, P> {
+    /// Reference to the original graph
+    graph: &'a Graph,
+
+    /// Predicate returning `true` for nodes that should remain in the graph
+    predicate: P,
+}
+
+impl<'a, Graph: BaseGraph, P: Fn(Graph::NodeHandle) -> bool> BaseGraph
+    for GraphMaskView<'a, Graph, P>
+{
+    type NodeHandle = Graph::NodeHandle;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        self.graph
+            .adjacent_nodes(point)
+            .filter(|pt| (self.predicate)(*pt))
+    }
+}
+
+impl<'a, Graph: LabeledGraph<T>, T: Copy, P: Fn(Graph::NodeHandle) -> bool>
+    LabeledGraph<T> for GraphMaskView<'a, Graph, P>
+{
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        if (self.predicate)(point) {
+            self.graph.get(point)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, Graph: SpatialGraph, P: Fn(Graph::NodeHandle) -> bool> SpatialGraph
+    for GraphMaskView<'a, Graph, P>
+{
+    type Distance = Graph::Distance;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        self.graph.distance(pt0, pt1)
+    }
+}
+
+/// A view of a labeled graph with some labels replaced by those from a second source.
+///
+/// The overlay function is consulted for each node of the original graph; where it
+/// returns `Some(label)` that label is used, and elsewhere the original label shows
+/// through. The overlay cannot add nodes that are absent from the original graph.
+pub struct GraphOverlayView<'a, Graph: ?Sized, F> {
+    /// Reference to the original graph
+    graph: &'a Graph,
+
+    /// Function returning the overriding label for a node, if any
+    overlay: F,
+}
+
+impl<'a, Graph: BaseGraph, F> BaseGraph for GraphOverlayView<'a, Graph, F> {
+    type NodeHandle = Graph::NodeHandle;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        self.graph.adjacent_nodes(point)
+    }
+}
+
+impl<'a, Graph: LabeledGraph<T>, T: Copy, F: Fn(Graph::NodeHandle) -> Option<T>>
+    LabeledGraph<T> for GraphOverlayView<'a, Graph, F>
+{
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.graph
+            .get(point)
+            .map(|label| (self.overlay)(point).unwrap_or(label))
+    }
+}
+
+impl<'a, Graph: SpatialGraph, F> SpatialGraph for GraphOverlayView<'a, Graph, F> {
+    type Distance = Graph::Distance;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        self.graph.distance(pt0, pt1)
+    }
+}
+
+/// A view pairing the labels of two graphs that share the same node handles.
+///
+/// Adjacency and distances are taken from the first graph. A node is only present in the
+/// view if both graphs have a label for it, in which case `get` returns both labels as a
+/// tuple. Combine with `apply` to reduce the pair to a single label, e.g. to derive
+/// `Passability` from a terrain layer and an occupancy layer.
+pub struct GraphZipView<'a, GraphA: ?Sized, GraphB: ?Sized> {
+    /// Reference to the graph providing structure and the first label
+    first: &'a GraphA,
+
+    /// Reference to the graph providing the second label
+    second: &'a GraphB,
+}
+
+impl<'a, GraphA: BaseGraph, GraphB: ?Sized> BaseGraph
+    for GraphZipView<'a, GraphA, GraphB>
+{
+    type NodeHandle = GraphA::NodeHandle;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        self.first.adjacent_nodes(point)
+    }
+}
+
+impl<'a, GraphA, GraphB, A, B> LabeledGraph<(A, B)> for GraphZipView<'a, GraphA, GraphB>
+where
+    GraphA: LabeledGraph<A>,
+    GraphB: LabeledGraph<B, NodeHandle = GraphA::NodeHandle> + ?Sized,
+    A: Copy,
+    B: Copy,
+{
+    fn get(&self, point: Self::NodeHandle) -> Option<(A, B)> {
+        Some((self.first.get(point)?, self.second.get(point)?))
+    }
+}
+
+impl<'a, GraphA: SpatialGraph, GraphB: ?Sized> SpatialGraph
+    for GraphZipView<'a, GraphA, GraphB>
+{
+    type Distance = GraphA::Distance;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        self.first.distance(pt0, pt1)
+    }
+}
+
+/// `ComposableGraph` provides methods for building views that combine a graph with other
+/// sources of information: masking out nodes, overlaying labels and zipping with a second
+/// graph. Like `TransformableGraph::apply`, none of these copy or modify the original graph.
+pub trait ComposableGraph: BaseGraph {
+    /// Returns a view containing only the nodes for which `predicate` returns `true`.
+    fn mask<P: Fn(Self::NodeHandle) -> bool>(
+        &self,
+        predicate: P,
+    ) -> GraphMaskView<'_, Self, P>;
+
+    /// Returns a view whose labels are replaced wherever `overlay` returns `Some`.
+    fn overlay<T, F: Fn(Self::NodeHandle) -> Option<T>>(
+        &self,
+        overlay: F,
+    ) -> GraphOverlayView<'_, Self, F>;
+
+    /// Returns a view labeling each node with the pair of labels from this graph and `other`.
+    fn zip<'a, Other: BaseGraph<NodeHandle = Self::NodeHandle> + ?Sized>(
+        &'a self,
+        other: &'a Other,
+    ) -> GraphZipView<'a, Self, Other>;
+}
+
+impl<Graph: BaseGraph> ComposableGraph for Graph {
+    fn mask<P: Fn(Self::NodeHandle) -> bool>(
+        &self,
+        predicate: P,
+    ) -> GraphMaskView<'_, Self, P> {
+        GraphMaskView {
+            graph: self,
+            predicate,
+        }
+    }
+
+    fn overlay<T, F: Fn(Self::NodeHandle) -> Option<T>>(
+        &self,
+        overlay: F,
+    ) -> GraphOverlayView<'_, Self, F> {
+        GraphOverlayView {
+            graph: self,
+            overlay,
+        }
+    }
+
+    fn zip<'a, Other: BaseGraph<NodeHandle = Self::NodeHandle> + ?Sized>(
+        &'a self,
+        other: &'a Other,
+    ) -> GraphZipView<'a, Self, Other> {
+        GraphZipView {
+            first: self,
+            second: other,
+        }
+    }
+}
+
 impl<T> BaseGraph for Grid2D<T> {
     type NodeHandle = Point;
 
-    fn adjacent_nodes(&self, point: Self::NodeHandle) -> Vec<Self::NodeHandle> {
-        MooreNeighbor::all()
-            .iter()
-            .map(|n| point + n.offset())
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        MooreNeighbor::ALL
+            .into_iter()
+            .map(move |n| point + n.offset())
             .filter(|pt| self.index(*pt).is_some())
-            .collect()
     }
 }
 impl<T: Copy> SpatialGraph for Grid2D<T> {
@@ -147,11 +369,176 @@ impl<T: Copy> LabeledGraph<T> for Grid2D<T> {
     }
 }
 
+impl<T> BaseGraph for Grid2DView<'_, T> {
+    type NodeHandle = Point;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        let rect = self.rect();
+        MooreNeighbor::ALL
+            .into_iter()
+            .map(move |n| point + n.offset())
+            .filter(move |pt| rect.contains(*pt))
+    }
+}
+impl<T: Copy> SpatialGraph for Grid2DView<'_, T> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        (pt1 - pt0).sqr_magnitude()
+    }
+}
+
+impl<T: Copy> LabeledGraph<T> for Grid2DView<'_, T> {
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.get(point).cloned()
+    }
+}
+
+impl<T> BaseGraph for Grid2DViewMut<'_, T> {
+    type NodeHandle = Point;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        let rect = self.rect();
+        MooreNeighbor::ALL
+            .into_iter()
+            .map(move |n| point + n.offset())
+            .filter(move |pt| rect.contains(*pt))
+    }
+}
+impl<T: Copy> SpatialGraph for Grid2DViewMut<'_, T> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        (pt1 - pt0).sqr_magnitude()
+    }
+}
+
+impl<T: Copy> LabeledGraph<T> for Grid2DViewMut<'_, T> {
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.get(point).cloned()
+    }
+}
+
+impl<T> BaseGraph for ChunkedGrid<T> {
+    type NodeHandle = Point;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        MooreNeighbor::ALL
+            .into_iter()
+            .map(move |n| point + n.offset())
+            .filter(|pt| self.get(*pt).is_some())
+    }
+}
+impl<T: Copy> SpatialGraph for ChunkedGrid<T> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        (pt1 - pt0).sqr_magnitude()
+    }
+}
+
+impl<T: Copy> LabeledGraph<T> for ChunkedGrid<T> {
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.get(point).cloned()
+    }
+}
+
+impl BaseGraph for BitGrid {
+    type NodeHandle = Point;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        MooreNeighbor::ALL
+            .into_iter()
+            .map(move |n| point + n.offset())
+            .filter(|pt| self.get(*pt).is_some())
+    }
+}
+impl SpatialGraph for BitGrid {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        (pt1 - pt0).sqr_magnitude()
+    }
+}
+
+impl LabeledGraph<bool> for BitGrid {
+    fn get(&self, point: Self::NodeHandle) -> Option<bool> {
+        self.get(point)
+    }
+}
+
+impl<T> BaseGraph for HexGrid<T> {
+    type NodeHandle = Hex;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        point.neighbors().filter(|hex| self.contains(*hex))
+    }
+}
+impl<T> SpatialGraph for HexGrid<T> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        pt0.distance(pt1)
+    }
+}
+
+impl<T: Copy> LabeledGraph<T> for HexGrid<T> {
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.get(point).cloned()
+    }
+}
+
+impl<T> BaseGraph for Grid3D<T> {
+    type NodeHandle = Point3;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        Neighborhood3D::Moore
+            .offsets()
+            .iter()
+            .map(move |offset| point + *offset)
+            .filter(|pt| self.contains(*pt))
+    }
+}
+impl<T> SpatialGraph for Grid3D<T> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        (pt1 - pt0).sqr_magnitude()
+    }
+}
+
+impl<T: Copy> LabeledGraph<T> for Grid3D<T> {
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.get(point).cloned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
+
+    use scoundrel_geometry::{Rect, TileBin};
 
     use super::*;
+    use crate::{Opacity, Passability, a_star, cast_light_2d, cast_light_2d_beveled};
 
     // A simple graph implementation for testing
     struct TestGraph {
@@ -187,8 +574,11 @@ mod tests {
     impl BaseGraph for TestGraph {
         type NodeHandle = u32;
 
-        fn adjacent_nodes(&self, node: Self::NodeHandle) -> Vec<Self::NodeHandle> {
-            self.adjacency.get(&node).cloned().unwrap_or_default()
+        fn adjacent_nodes(
+            &self,
+            node: Self::NodeHandle,
+        ) -> impl Iterator<Item = Self::NodeHandle> {
+            self.adjacency.get(&node).into_iter().flatten().copied()
         }
     }
 
@@ -217,7 +607,7 @@ mod tests {
 
         // Test center point adjacency
         let center = Point::new(1, 1);
-        let adj = grid.adjacent_nodes(center);
+        let adj: Vec<_> = grid.adjacent_nodes(center).collect();
 
         // Should have 8 neighbors (Moore neighborhood)
         assert_eq!(adj.len(), 8);
@@ -234,8 +624,7 @@ mod tests {
 
         // Test edge adjacency (should have fewer neighbors)
         let edge = Point::new(0, 1);
-        let edge_adj = grid.adjacent_nodes(edge);
-        assert_eq!(edge_adj.len(), 5); // Only 5 neighbors for edge point
+        assert_eq!(grid.adjacent_nodes(edge).count(), 5); // Only 5 neighbors for edge point
     }
 
     #[test]
@@ -276,9 +665,9 @@ mod tests {
         let graph = TestGraph::new();
 
         // Test adjacency
-        assert_eq!(graph.adjacent_nodes(0), vec![1, 3]);
-        assert_eq!(graph.adjacent_nodes(1), vec![0, 2, 4]);
-        assert_eq!(graph.adjacent_nodes(5), Vec::<u32>::new()); // Non-existent node
+        assert_eq!(graph.adjacent_nodes(0).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(graph.adjacent_nodes(1).collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(graph.adjacent_nodes(5).next(), None); // Non-existent node
 
         // Test labels
         assert_eq!(graph.get(0), Some(10));
@@ -298,8 +687,8 @@ mod tests {
         let view = graph.apply(|x| x * 2);
 
         // Adjacency should be unchanged
-        assert_eq!(view.adjacent_nodes(0), vec![1, 3]);
-        assert_eq!(view.adjacent_nodes(1), vec![0, 2, 4]);
+        assert_eq!(view.adjacent_nodes(0).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(view.adjacent_nodes(1).collect::<Vec<_>>(), vec![0, 2, 4]);
 
         // Labels should be doubled
         assert_eq!(view.get(0), Some(20)); // 10 * 2
@@ -307,6 +696,275 @@ mod tests {
         assert_eq!(view.get(5), None); // Non-existent node
     }
 
+    #[test]
+    fn test_graph_functor_view_pathfinding() {
+        let terrain = Grid2D::from_sparse_points(
+            5,
+            5,
+            0,
+            vec![Point::new(2, 0), Point::new(2, 1), Point::new(2, 2)],
+            1,
+        );
+        let view = terrain.apply(|t| {
+            if t == 0 {
+                Passability::Passable
+            } else {
+                Passability::Impassable
+            }
+        });
+        let path = a_star(&view, Point::new(0, 0), Point::new(4, 0)).unwrap();
+        assert!(path.iter().all(|pt| terrain.get(*pt) == Some(&0)));
+    }
+
+    #[test]
+    fn test_graph_mask_view() {
+        let graph = TestGraph::new();
+        let view = graph.mask(|node| node != 1);
+
+        assert_eq!(view.adjacent_nodes(0).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(view.adjacent_nodes(4).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(view.get(0), Some(10));
+        assert_eq!(view.get(1), None);
+        assert_eq!(view.distance(0, 4), graph.distance(0, 4));
+    }
+
+    #[test]
+    fn test_graph_mask_view_blocks_occupied_tiles() {
+        let grid = Grid2D::new(3, 3, Passability::Passable);
+        let mut monsters = TileBin::default();
+        for y in 0..2 {
+            monsters.insert(y, Point::new(1, y)).unwrap();
+        }
+
+        let view = grid.mask(|pt| monsters.values_at(pt).next().is_none());
+        let path = a_star(&view, Point::new(0, 0), Point::new(2, 0)).unwrap();
+        assert!(path.contains(&Point::new(1, 2)));
+
+        monsters.insert(2, Point::new(1, 2)).unwrap();
+        let view = grid.mask(|pt| monsters.values_at(pt).next().is_none());
+        assert!(a_star(&view, Point::new(0, 0), Point::new(2, 0)).is_none());
+    }
+
+    #[test]
+    fn test_graph_mask_view_field_of_view() {
+        let mut walls = Grid2D::new(9, 9, Opacity::Transparent);
+        let crates = [Point::new(5, 4), Point::new(5, 5), Point::new(3, 6)];
+        let view = walls.mask(|pt| !crates.contains(&pt));
+        let mut masked = HashSet::new();
+        cast_light_2d_beveled(&view, Point::new(2, 4), 10, |pt| {
+            masked.insert(pt);
+        });
+
+        // masked tiles shape the beveled corners exactly like opaque ones
+        for pt in crates {
+            walls.set(pt, Opacity::Opaque);
+        }
+        let mut opaque = HashSet::new();
+        cast_light_2d_beveled(&walls, Point::new(2, 4), 10, |pt| {
+            opaque.insert(pt);
+        });
+        assert_eq!(masked, opaque);
+    }
+
+    #[test]
+    fn test_graph_overlay_view() {
+        let graph = TestGraph::new();
+        let view = graph.overlay(|node| if node % 2 == 0 { Some(-1) } else { None });
+
+        assert_eq!(view.adjacent_nodes(1).collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(view.get(0), Some(-1));
+        assert_eq!(view.get(1), Some(20));
+        assert_eq!(view.get(2), Some(-1));
+        // the overlay cannot introduce nodes absent from the original graph
+        assert_eq!(view.get(6), None);
+    }
+
+    #[test]
+    fn test_graph_overlay_view_from_tile_bin() {
+        let grid = Grid2D::new(5, 1, Passability::Passable);
+        let mut monsters = TileBin::default();
+        monsters.insert("goblin", Point::new(2, 0)).unwrap();
+
+        let view = grid.overlay(|pt| {
+            monsters
+                .values_at(pt)
+                .next()
+                .map(|_| Passability::Impassable)
+        });
+        assert_eq!(view.get(Point::new(1, 0)), Some(Passability::Passable));
+        assert_eq!(view.get(Point::new(2, 0)), Some(Passability::Impassable));
+        assert!(a_star(&view, Point::new(0, 0), Point::new(4, 0)).is_none());
+    }
+
+    #[test]
+    fn test_graph_overlay_view_field_of_view() {
+        let grid = Grid2D::new(7, 1, Opacity::Transparent);
+        let smoke = [Point::new(3, 0)];
+        let view = grid.overlay(|pt| smoke.contains(&pt).then_some(Opacity::Opaque));
+
+        let mut visible = vec![];
+        cast_light_2d(&view, Point::new(0, 0), 10, |pt| visible.push(pt));
+        assert!(visible.contains(&Point::new(3, 0)));
+        assert!(!visible.contains(&Point::new(4, 0)));
+    }
+
+    #[test]
+    fn test_graph_zip_view() {
+        let terrain = Grid2D::from_sparse_points(
+            3,
+            3,
+            Passability::Passable,
+            vec![Point::new(1, 0)],
+            Passability::Impassable,
+        );
+        let occupied =
+            Grid2D::from_sparse_points(4, 3, false, vec![Point::new(1, 1)], true);
+
+        let zipped = terrain.zip(&occupied);
+        assert_eq!(
+            zipped.get(Point::new(0, 0)),
+            Some((Passability::Passable, false))
+        );
+        assert_eq!(
+            zipped.get(Point::new(1, 1)),
+            Some((Passability::Passable, true))
+        );
+        // present in the second graph only
+        assert_eq!(zipped.get(Point::new(3, 0)), None::<(Passability, bool)>);
+
+        let view = zipped.apply(|(passability, occupied)| {
+            if occupied {
+                Passability::Impassable
+            } else {
+                passability
+            }
+        });
+        let path = a_star(&view, Point::new(0, 0), Point::new(2, 0)).unwrap();
+        assert!(!path.contains(&Point::new(1, 0)));
+        assert!(!path.contains(&Point::new(1, 1)));
+        assert!(path.contains(&Point::new(1, 2)));
+    }
+
+    #[test]
+    fn test_grid_view_a_star() {
+        // the direct route through the window is blocked, and the detour around the wall
+        // lies outside it
+        let mut grid = Grid2D::new(7, 5, Passability::Passable);
+        for y in 0..4 {
+            grid.set(Point::new(3, y), Passability::Impassable);
+        }
+        let full = a_star(&grid, Point::new(1, 1), Point::new(5, 1)).unwrap();
+        assert!(full.contains(&Point::new(3, 4)));
+
+        let window = grid.sub_grid(Rect::with_points(Point::new(0, 0), Point::new(7, 4)));
+        assert_eq!(window.adjacent_nodes(Point::new(1, 3)).count(), 5);
+        assert!(a_star(&window, Point::new(1, 1), Point::new(5, 1)).is_none());
+
+        let mut window =
+            grid.sub_grid_mut(Rect::with_points(Point::new(0, 0), Point::new(7, 4)));
+        window.set(Point::new(3, 2), Passability::Passable);
+        let path = a_star(&window, Point::new(1, 1), Point::new(5, 1)).unwrap();
+        assert!(path.contains(&Point::new(3, 2)));
+    }
+
+    #[test]
+    fn test_grid_view_field_of_view() {
+        let grid = Grid2D::new(9, 9, Opacity::Transparent);
+        let window = grid.sub_grid(Rect::with_points(Point::new(2, 2), Point::new(7, 7)));
+
+        let mut visible = vec![];
+        cast_light_2d(&window, Point::new(4, 4), 10, |pt| visible.push(pt));
+        assert!(visible.contains(&Point::new(2, 2)));
+        assert!(visible.contains(&Point::new(6, 6)));
+        // cells outside the window block sight like walls at the edge of a grid
+        assert!(visible.contains(&Point::new(1, 4)));
+        assert!(!visible.contains(&Point::new(0, 4)));
+    }
+
+    #[test]
+    fn test_chunked_grid_a_star() {
+        let mut grid = ChunkedGrid::new(4, Passability::Passable);
+        for chunk in [Point::new(-1, -1), Point::new(0, -1), Point::new(1, -1)] {
+            grid.load_chunk(chunk);
+        }
+        // a wall along a chunk boundary with a gap in the negative chunk
+        for y in -4..-1 {
+            grid.set(Point::new(0, y), Passability::Impassable);
+        }
+        let path = a_star(&grid, Point::new(-3, -3), Point::new(6, -3)).unwrap();
+        assert_eq!(path.first(), Some(&Point::new(-3, -3)));
+        assert_eq!(path.last(), Some(&Point::new(6, -3)));
+        assert!(path.contains(&Point::new(0, -1)));
+
+        // unloaded chunks are not traversable
+        assert!(a_star(&grid, Point::new(0, -3), Point::new(0, 3)).is_none());
+    }
+
+    #[test]
+    fn test_chunked_grid_field_of_view() {
+        let mut grid = ChunkedGrid::new(4, Opacity::Transparent);
+        for x in -2..2 {
+            grid.load_chunk(Point::new(x, 0));
+        }
+        grid.set(Point::new(3, 1), Opacity::Opaque);
+
+        let mut visible = vec![];
+        cast_light_2d(&grid, Point::new(-1, 1), 10, |pt| visible.push(pt));
+        assert!(visible.contains(&Point::new(-8, 1)));
+        assert!(visible.contains(&Point::new(3, 1)));
+        assert!(!visible.contains(&Point::new(4, 1)));
+        // the edge of the loaded area blocks sight
+        assert!(visible.contains(&Point::new(-1, -1)));
+        assert!(!visible.contains(&Point::new(-1, -2)));
+    }
+
+    #[test]
+    fn test_bit_grid_a_star() {
+        let mut walls = BitGrid::new(5, 5);
+        for y in 0..4 {
+            walls.set(Point::new(2, y), true);
+        }
+        let view = walls.apply(|wall| {
+            if wall {
+                Passability::Impassable
+            } else {
+                Passability::Passable
+            }
+        });
+        let path = a_star(&view, Point::new(0, 0), Point::new(4, 0)).unwrap();
+        assert!(path.contains(&Point::new(2, 4)));
+
+        walls.set(Point::new(2, 4), true);
+        let view = walls.apply(|wall| {
+            if wall {
+                Passability::Impassable
+            } else {
+                Passability::Passable
+            }
+        });
+        assert!(a_star(&view, Point::new(0, 0), Point::new(4, 0)).is_none());
+    }
+
+    #[test]
+    fn test_hex_grid_a_star() {
+        let mut grid = HexGrid::new(6, 5, Passability::Passable);
+        for r in 0..4 {
+            grid.set(Hex::from_offset(Point::new(3, r)), Passability::Impassable);
+        }
+        let start = Hex::from_offset(Point::new(0, 0));
+        let end = Hex::from_offset(Point::new(5, 0));
+        let path = a_star(&grid, start, end).unwrap();
+        assert!(path.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert!(path.contains(&Hex::from_offset(Point::new(3, 4))));
+        assert!(
+            path.iter()
+                .all(|hex| grid.get(*hex) == Some(&Passability::Passable))
+        );
+
+        grid.set(Hex::from_offset(Point::new(3, 4)), Passability::Impassable);
+        assert!(a_star(&grid, start, end).is_none());
+    }
+
     #[test]
     fn test_labeled_spatial_graph() {
         // Grid2D implements both SpatialGraph and LabeledGraph, so it should
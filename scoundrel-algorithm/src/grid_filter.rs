@@ -0,0 +1,413 @@
+use scoundrel_geometry::{Grid2D, MooreNeighbor, Point, Rect};
+
+/// A set of offsets describing which cells around a center cell are its neighbors.
+///
+/// The center cell itself is not part of a window. `Window::moore` matches the offsets of
+/// `MooreNeighbor`; the other constructors describe larger or sparser neighborhoods.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Window {
+    offsets: Vec<Point>,
+}
+
+impl Window {
+    /// Creates a window from arbitrary offsets. Any zero offset is discarded.
+    pub fn from_offsets<I: IntoIterator<Item = Point>>(offsets: I) -> Window {
+        Window {
+            offsets: offsets
+                .into_iter()
+                .filter(|o| *o != Point::zero())
+                .collect(),
+        }
+    }
+
+    /// Returns the eight cells surrounding the center, in `MooreNeighbor` order.
+    pub fn moore() -> Window {
+        Window::from_offsets(MooreNeighbor::ALL.map(|n| n.offset()))
+    }
+
+    /// Returns the four cells orthogonally adjacent to the center.
+    pub fn von_neumann() -> Window {
+        Window::from_offsets(
+            [
+                MooreNeighbor::Up,
+                MooreNeighbor::Right,
+                MooreNeighbor::Down,
+                MooreNeighbor::Left,
+            ]
+            .map(|n| n.offset()),
+        )
+    }
+
+    /// Returns every cell within a Chebyshev distance of `radius` from the center.
+    pub fn square(radius: i32) -> Window {
+        let size = Point::new(radius * 2 + 1, radius * 2 + 1);
+        let rect = Rect::with_size(Point::new(-radius, -radius), size);
+        Window::from_offsets(rect.contained_points())
+    }
+
+    /// Returns every cell within a Euclidean distance of `radius` from the center.
+    pub fn circle(radius: i32) -> Window {
+        let mut window = Window::square(radius);
+        window
+            .offsets
+            .retain(|o| o.sqr_magnitude() <= radius * radius);
+        window
+    }
+
+    /// Returns the offsets in the window.
+    pub fn offsets(&self) -> &[Point] {
+        &self.offsets
+    }
+}
+
+/// A grid of convolution weights with a designated center cell.
+#[derive(Clone)]
+pub struct Kernel {
+    weights: Grid2D<f32>,
+    center: Point,
+}
+
+impl Kernel {
+    /// Creates a kernel from a grid of weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights` - The weight of each cell of the kernel.
+    /// * `center` - The cell of `weights` aligned with the cell being computed.
+    pub fn new(weights: Grid2D<f32>, center: Point) -> Kernel {
+        Kernel { weights, center }
+    }
+
+    /// Returns a square kernel of equal weights summing to one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative.
+    pub fn box_filter(radius: i32) -> Kernel {
+        assert!(radius >= 0, "box filter radius must not be negative");
+        let size = radius * 2 + 1;
+        let weight = 1.0 / (size * size) as f32;
+        Kernel::new(Grid2D::new(size, size, weight), Point::new(radius, radius))
+    }
+
+    /// Returns a normalized Gaussian kernel with the given standard deviation.
+    ///
+    /// The kernel extends three standard deviations from its center.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sigma` is not positive.
+    pub fn gaussian(sigma: f32) -> Kernel {
+        assert!(sigma > 0.0, "gaussian standard deviation must be positive");
+        let radius = (sigma * 3.0).ceil().max(1.0) as i32;
+        let size = radius * 2 + 1;
+        let center = Point::new(radius, radius);
+        let mut weights = Grid2D::new(size, size, 0.0).map_coords(|pt| {
+            let d = (pt - center).sqr_magnitude() as f32;
+            (-d / (2.0 * sigma * sigma)).exp()
+        });
+        let total: f32 = weights.data.iter().sum();
+        weights.data.iter_mut().for_each(|w| *w /= total);
+        Kernel::new(weights, center)
+    }
+
+    /// Returns the weight grid of the kernel.
+    pub fn weights(&self) -> &Grid2D<f32> {
+        &self.weights
+    }
+
+    /// Returns the cell of the kernel aligned with the cell being computed.
+    pub fn center(&self) -> Point {
+        self.center
+    }
+}
+
+/// Returns the value of the grid at `pt`, clamping coordinates to the edge of the grid.
+fn clamped<T: Copy>(grid: &Grid2D<T>, pt: Point) -> T {
+    let max = grid.size() - Point::new(1, 1);
+    let pt = Point::new(pt.x.clamp(0, max.x), pt.y.clamp(0, max.y));
+    *grid.get(pt).unwrap()
+}
+
+/// Convolves a grid with a kernel.
+///
+/// Cells beyond the edge of the grid take the value of the nearest edge cell.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to filter.
+/// * `kernel` - The weights to apply around each cell.
+pub fn convolve(grid: &Grid2D<f32>, kernel: &Kernel) -> Grid2D<f32> {
+    let weights = kernel.weights();
+    grid.map_coords(|pt| {
+        weights
+            .iter_coords()
+            .zip(weights.data.iter())
+            .map(|(k, w)| w * clamped(grid, pt + k - kernel.center()))
+            .sum()
+    })
+}
+
+/// Blurs a grid by averaging each cell with its neighbors within `radius`.
+///
+/// # Panics
+///
+/// Panics if `radius` is negative.
+pub fn box_blur(grid: &Grid2D<f32>, radius: i32) -> Grid2D<f32> {
+    convolve(grid, &Kernel::box_filter(radius))
+}
+
+/// Blurs a grid with a Gaussian kernel of the given standard deviation.
+///
+/// # Panics
+///
+/// Panics if `sigma` is not positive.
+pub fn gaussian_blur(grid: &Grid2D<f32>, sigma: f32) -> Grid2D<f32> {
+    convolve(grid, &Kernel::gaussian(sigma))
+}
+
+/// Replaces each cell with the result of `reduce` over it and its in-bounds neighbors.
+fn morphology<T: Copy, F: Fn(T, T) -> T>(
+    grid: &Grid2D<T>,
+    window: &Window,
+    reduce: F,
+) -> Grid2D<T> {
+    grid.map_coords(|pt| {
+        window
+            .offsets()
+            .iter()
+            .filter_map(|o| grid.get(pt + *o))
+            .fold(*grid.get(pt).unwrap(), |acc, v| reduce(acc, *v))
+    })
+}
+
+/// Replaces each cell with the greatest value among it and its neighbors in `window`.
+///
+/// On a boolean grid this grows every `true` region by the shape of the window. Neighbors
+/// outside the grid are ignored.
+pub fn dilate<T: Copy + Ord>(grid: &Grid2D<T>, window: &Window) -> Grid2D<T> {
+    morphology(grid, window, Ord::max)
+}
+
+/// Replaces each cell with the least value among it and its neighbors in `window`.
+///
+/// On a boolean grid this shrinks every `true` region by the shape of the window.
+/// Neighbors outside the grid are ignored.
+pub fn erode<T: Copy + Ord>(grid: &Grid2D<T>, window: &Window) -> Grid2D<T> {
+    morphology(grid, window, Ord::min)
+}
+
+/// Erodes and then dilates a grid, removing features smaller than the window.
+pub fn open<T: Copy + Ord>(grid: &Grid2D<T>, window: &Window) -> Grid2D<T> {
+    dilate(&erode(grid, window), window)
+}
+
+/// Dilates and then erodes a grid, filling gaps smaller than the window.
+pub fn close<T: Copy + Ord>(grid: &Grid2D<T>, window: &Window) -> Grid2D<T> {
+    erode(&dilate(grid, window), window)
+}
+
+/// Advances a cellular automaton by one generation.
+///
+/// For each cell, `rule` is called with the cell's current value and the values of its
+/// neighbors in `window`, and returns the cell's next value. All cells are updated
+/// simultaneously.
+///
+/// # Arguments
+///
+/// * `grid` - The current generation.
+/// * `window` - The neighborhood passed to `rule`.
+/// * `outside` - The value of neighbors beyond the edge of the grid, or `None` to leave
+///   them out of the neighborhood.
+/// * `rule` - Computes a cell's next value from its value and its neighbors' values.
+///
+/// # Examples
+///
+/// A step of the classic cave generation rule, treating the edge of the map as wall:
+///
+/// ```
+/// # use scoundrel_algorithm::{Window, step_automaton};
+/// # use scoundrel_geometry::{Grid2D, Point};
+/// let walls = Grid2D::from_sparse_points(3, 3, false, vec![Point::new(1, 1)], true);
+/// let next = step_automaton(&walls, &Window::moore(), Some(true), |_, neighbors| {
+///     neighbors.iter().filter(|wall| **wall).count() >= 5
+/// });
+/// assert_eq!(next.get(Point::new(0, 0)), Some(&true));
+/// assert_eq!(next.get(Point::new(1, 1)), Some(&false));
+/// ```
+pub fn step_automaton<T, U, F>(
+    grid: &Grid2D<T>,
+    window: &Window,
+    outside: Option<T>,
+    mut rule: F,
+) -> Grid2D<U>
+where
+    T: Copy,
+    U: Copy,
+    F: FnMut(T, &[T]) -> U,
+{
+    let mut neighbors = Vec::with_capacity(window.offsets().len());
+    grid.map_coords(|pt| {
+        neighbors.clear();
+        neighbors.extend(
+            window
+                .offsets()
+                .iter()
+                .filter_map(|o| grid.get(pt + *o).copied().or(outside)),
+        );
+        rule(*grid.get(pt).unwrap(), &neighbors)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&str]) -> Grid2D<bool> {
+        Grid2D::from_iter(
+            rows.iter().flat_map(|r| r.chars().map(|c| c == '#')),
+            rows[0].len() as i32,
+            rows.len() as i32,
+        )
+    }
+
+    fn to_rows(grid: &Grid2D<bool>) -> Vec<String> {
+        grid.rows()
+            .map(|r| r.iter().map(|v| if *v { '#' } else { '.' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(Window::moore().offsets().len(), 8);
+        assert_eq!(Window::von_neumann().offsets().len(), 4);
+        assert_eq!(Window::square(2).offsets().len(), 24);
+        assert_eq!(Window::circle(1).offsets().len(), 4);
+        assert!(!Window::square(1).offsets().contains(&Point::zero()));
+        assert_eq!(
+            Window::from_offsets([Point::zero(), Point::new(2, 0)]).offsets(),
+            &[Point::new(2, 0)]
+        );
+    }
+
+    #[test]
+    fn test_dilate_erode() {
+        let grid = from_rows(&[".....", ".....", "..#..", ".....", "....."]);
+        let plus = dilate(&grid, &Window::von_neumann());
+        assert_eq!(
+            to_rows(&plus),
+            vec![".....", "..#..", ".###.", "..#..", "....."]
+        );
+        assert_eq!(erode(&plus, &Window::von_neumann()).data, grid.data);
+        assert_eq!(
+            to_rows(&dilate(&grid, &Window::moore())),
+            vec![".....", ".###.", ".###.", ".###.", "....."]
+        );
+    }
+
+    #[test]
+    fn test_open_close() {
+        let grid = from_rows(&["#.....", "..###.", "..###.", "..###.", "......"]);
+        // opening removes the speck but keeps the block
+        assert_eq!(
+            to_rows(&open(&grid, &Window::moore())),
+            vec!["......", "..###.", "..###.", "..###.", "......"]
+        );
+
+        let holed = from_rows(&["#####", "#####", "##.##", "#####", "#####"]);
+        assert!(close(&holed, &Window::moore()).data.iter().all(|v| *v));
+    }
+
+    #[test]
+    fn test_grayscale_morphology() {
+        let grid = Grid2D::from_iter([1, 5, 2, 3].into_iter(), 4, 1);
+        let window = Window::from_offsets([Point::new(-1, 0), Point::new(1, 0)]);
+        assert_eq!(dilate(&grid, &window).data, vec![5, 5, 5, 3]);
+        assert_eq!(erode(&grid, &window).data, vec![1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_step_automaton() {
+        // game of life blinker
+        let grid = from_rows(&[".....", "..#..", "..#..", "..#..", "....."]);
+        let life = |alive: bool, neighbors: &[bool]| {
+            let count = neighbors.iter().filter(|n| **n).count();
+            count == 3 || (alive && count == 2)
+        };
+        let next = step_automaton(&grid, &Window::moore(), None, life);
+        assert_eq!(
+            to_rows(&next),
+            vec![".....", ".....", ".###.", ".....", "....."]
+        );
+        assert_eq!(
+            step_automaton(&next, &Window::moore(), None, life).data,
+            grid.data
+        );
+    }
+
+    #[test]
+    fn test_step_automaton_outside() {
+        let grid = Grid2D::new(3, 3, 0);
+        let counts = step_automaton(&grid, &Window::moore(), None, |_, n| n.len());
+        assert_eq!(counts.get(Point::new(0, 0)), Some(&3));
+        assert_eq!(counts.get(Point::new(1, 1)), Some(&8));
+
+        let counts = step_automaton(&grid, &Window::moore(), Some(1), |_, n| {
+            n.iter().sum::<i32>()
+        });
+        assert_eq!(counts.get(Point::new(0, 0)), Some(&5));
+        assert_eq!(counts.get(Point::new(1, 0)), Some(&3));
+        assert_eq!(counts.get(Point::new(1, 1)), Some(&0));
+    }
+
+    #[test]
+    fn test_convolve() {
+        let grid = Grid2D::from_iter((0..9).map(|v| v as f32), 3, 3);
+        let shift = Kernel::new(
+            Grid2D::from_iter([0.0, 0.0, 1.0].into_iter(), 3, 1),
+            Point::new(1, 0),
+        );
+        // each cell takes the value of its right neighbor, clamped at the edge
+        assert_eq!(
+            convolve(&grid, &shift).data,
+            vec![1.0, 2.0, 2.0, 4.0, 5.0, 5.0, 7.0, 8.0, 8.0]
+        );
+    }
+
+    #[test]
+    fn test_blur() {
+        let flat = Grid2D::new(6, 6, 2.5);
+        for blurred in [box_blur(&flat, 2), gaussian_blur(&flat, 1.0)] {
+            assert!(blurred.data.iter().all(|v| (v - 2.5).abs() < 1e-5));
+        }
+
+        let spike = Grid2D::from_sparse_points(7, 7, 0.0, vec![Point::new(3, 3)], 9.0);
+        let blurred = box_blur(&spike, 1);
+        assert!((blurred.get(Point::new(3, 3)).unwrap() - 1.0).abs() < 1e-5);
+        assert!((blurred.get(Point::new(2, 4)).unwrap() - 1.0).abs() < 1e-5);
+        assert_eq!(blurred.get(Point::new(1, 3)), Some(&0.0));
+
+        let kernel = Kernel::gaussian(1.0);
+        let total: f32 = kernel.weights().data.iter().sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        let gaussian = gaussian_blur(&spike, 1.0);
+        let center = gaussian.get(Point::new(3, 3)).unwrap();
+        assert!(center > gaussian.get(Point::new(3, 4)).unwrap());
+        assert!(
+            gaussian.get(Point::new(3, 4)).unwrap()
+                > gaussian.get(Point::new(4, 4)).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gaussian_rejects_zero_sigma() {
+        Kernel::gaussian(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_box_filter_rejects_negative_radius() {
+        Kernel::box_filter(-1);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use scoundrel_geometry::{Grid3D, Neighborhood3D, Point3};
+
+use crate::graph::{BaseGraph, LabeledGraph, SpatialGraph};
+
+/// A graph over a `Grid3D` with a configurable neighborhood and extra links between cells.
+///
+/// Links model stairs, ladders, pits and other connections that the neighborhood alone
+/// does not provide. A typical multi-level dungeon uses `Neighborhood3D::Planar`, so that
+/// movement stays on a single z-level except through explicit links.
+pub struct LevelGraph<'a, T> {
+    grid: &'a Grid3D<T>,
+    neighborhood: Neighborhood3D,
+    links: HashMap<Point3, Vec<Point3>>,
+}
+
+impl<'a, T> LevelGraph<'a, T> {
+    /// Creates a graph over the grid with no links.
+    pub fn new(grid: &'a Grid3D<T>, neighborhood: Neighborhood3D) -> Self {
+        LevelGraph {
+            grid,
+            neighborhood,
+            links: HashMap::new(),
+        }
+    }
+
+    /// Returns the underlying grid.
+    pub fn grid(&self) -> &'a Grid3D<T> {
+        self.grid
+    }
+
+    /// Connects two cells in both directions, such as the ends of a staircase.
+    pub fn add_link(&mut self, a: Point3, b: Point3) {
+        self.add_one_way_link(a, b);
+        self.add_one_way_link(b, a);
+    }
+
+    /// Connects `from` to `to` in one direction only, such as a trapdoor.
+    pub fn add_one_way_link(&mut self, from: Point3, to: Point3) {
+        let targets = self.links.entry(from).or_default();
+        if !targets.contains(&to) {
+            targets.push(to);
+        }
+    }
+
+    /// Removes the links between two cells in both directions.
+    pub fn remove_link(&mut self, a: Point3, b: Point3) {
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(targets) = self.links.get_mut(&from) {
+                targets.retain(|pt| *pt != to);
+                if targets.is_empty() {
+                    self.links.remove(&from);
+                }
+            }
+        }
+    }
+
+    /// Returns the cells linked from `pt`.
+    pub fn links(&self, pt: Point3) -> &[Point3] {
+        self.links.get(&pt).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+impl<T> BaseGraph for LevelGraph<'_, T> {
+    type NodeHandle = Point3;
+
+    fn adjacent_nodes(
+        &self,
+        point: Self::NodeHandle,
+    ) -> impl Iterator<Item = Self::NodeHandle> {
+        self.neighborhood
+            .offsets()
+            .iter()
+            .map(move |offset| point + *offset)
+            .chain(self.links(point).iter().copied())
+            .filter(|pt| self.grid.contains(*pt))
+    }
+}
+
+impl<T> SpatialGraph for LevelGraph<'_, T> {
+    type Distance = i32;
+
+    fn distance(&self, pt0: Self::NodeHandle, pt1: Self::NodeHandle) -> Self::Distance {
+        (pt1 - pt0).sqr_magnitude()
+    }
+}
+
+impl<T: Copy> LabeledGraph<T> for LevelGraph<'_, T> {
+    fn get(&self, point: Self::NodeHandle) -> Option<T> {
+        self.grid.get(point).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scoundrel_geometry::Grid2D;
+
+    use super::*;
+    use crate::{Passability, a_star};
+
+    fn floor(walls: &[(i32, i32)]) -> Grid2D<Passability> {
+        let mut grid = Grid2D::new(5, 5, Passability::Passable);
+        for (x, y) in walls {
+            grid.set((*x, *y).into(), Passability::Impassable);
+        }
+        grid
+    }
+
+    #[test]
+    fn test_stairs_between_levels() {
+        // the two halves of the ground floor are only connected through the floor above
+        let ground = floor(&[(2, 0), (2, 1), (2, 2), (2, 3), (2, 4)]);
+        let upper = floor(&[]);
+        let grid = Grid3D::from_levels(&[ground, upper]);
+        let (start, end) = (Point3::new(0, 0, 0), Point3::new(4, 0, 0));
+
+        let mut graph = LevelGraph::new(&grid, Neighborhood3D::Planar);
+        assert!(a_star(&graph, start, end).is_none());
+
+        graph.add_link(Point3::new(1, 4, 0), Point3::new(1, 4, 1));
+        assert!(a_star(&graph, start, end).is_none());
+
+        graph.add_link(Point3::new(3, 4, 0), Point3::new(3, 4, 1));
+        let path = a_star(&graph, start, end).unwrap();
+        assert!(path.contains(&Point3::new(1, 4, 1)));
+        assert!(path.contains(&Point3::new(3, 4, 1)));
+        for step in path.windows(2) {
+            let offset = step[1] - step[0];
+            assert!(offset.z == 0 || (offset.x == 0 && offset.y == 0));
+        }
+
+        graph.remove_link(Point3::new(3, 4, 1), Point3::new(3, 4, 0));
+        assert!(graph.links(Point3::new(3, 4, 0)).is_empty());
+        assert!(a_star(&graph, start, end).is_none());
+    }
+
+    #[test]
+    fn test_one_way_link() {
+        let grid = Grid3D::from_levels(&[floor(&[]), floor(&[])]);
+        let mut graph = LevelGraph::new(&grid, Neighborhood3D::Planar);
+        let (top, bottom) = (Point3::new(2, 2, 1), Point3::new(2, 2, 0));
+        graph.add_one_way_link(top, bottom);
+        assert!(a_star(&graph, Point3::new(0, 0, 1), Point3::new(4, 4, 0)).is_some());
+        assert!(a_star(&graph, Point3::new(0, 0, 0), Point3::new(4, 4, 1)).is_none());
+    }
+
+    #[test]
+    fn test_neighborhoods() {
+        let grid = Grid3D::new(3, 3, 3, Passability::Passable);
+        let center = Point3::new(1, 1, 1);
+        for (neighborhood, count) in [
+            (Neighborhood3D::VonNeumann, 6),
+            (Neighborhood3D::Moore, 26),
+            (Neighborhood3D::Planar, 8),
+        ] {
+            let graph = LevelGraph::new(&grid, neighborhood);
+            assert_eq!(graph.adjacent_nodes(center).count(), count);
+        }
+        let graph = LevelGraph::new(&grid, Neighborhood3D::Moore);
+        assert_eq!(graph.adjacent_nodes(Point3::new(0, 0, 0)).count(), 7);
+
+        let path = a_star(&graph, Point3::new(0, 0, 0), Point3::new(2, 2, 2)).unwrap();
+        assert_eq!(path.len(), 3);
+    }
+}
@@ -1,16 +1,44 @@
 mod a_star;
+mod adjacency_graph;
 mod bresenham;
+mod bsp_dungeon;
+mod connectivity;
+mod distance_transform;
+mod flow_field;
 mod graph;
+mod grid_filter;
+mod level_graph;
 mod shadow_cast_2d;
+mod shadow_cast_hex;
+mod spanning_tree;
 
 extern crate scoundrel_geometry;
 
 pub use a_star::{Passability, a_star};
+pub use adjacency_graph::{AdjacencyGraph, NodeDistance, UnitDistance};
 pub use bresenham::Bresenham;
+pub use bsp_dungeon::{BspDungeon, BspDungeonConfig, Corridor};
+pub use connectivity::{articulation_points, bridges};
+pub use distance_transform::{
+    chebyshev_distance_transform, euclidean_distance_transform,
+    manhattan_distance_transform, medial_axis, nearest_feature_transform,
+    squared_euclidean_distance_transform,
+};
+pub use flow_field::FlowField;
 pub use graph::{
-    BaseGraph, LabeledGraph, LabeledSpatialGraph, SpatialGraph, TransformableGraph,
+    BaseGraph, ComposableGraph, GraphFunctorView, GraphMaskView, GraphOverlayView,
+    GraphZipView, LabeledGraph, LabeledSpatialGraph, SpatialGraph, TransformableGraph,
+};
+pub use grid_filter::{
+    Kernel, Window, box_blur, close, convolve, dilate, erode, gaussian_blur, open,
+    step_automaton,
 };
+pub use level_graph::LevelGraph;
 pub use shadow_cast_2d::{
     DiamondTileShape, Opacity, Slope, SquareTileShape, TileShape, cast_light_2d,
     cast_light_2d_beveled, cast_light_2d_diamond,
 };
+pub use shadow_cast_hex::cast_light_hex;
+pub use spanning_tree::{
+    ShortestPathTree, minimum_spanning_forest, minimum_spanning_tree, shortest_path_tree,
+};
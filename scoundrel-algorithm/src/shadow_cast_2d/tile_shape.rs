@@ -74,10 +74,13 @@ impl<'a, M: LabeledSpatialGraph<Opacity, NodeHandle = Point>>
         }
     }
 
-    /// Maps a point from octant 0 coordinates to world coordinates and checks if it blocks light
+    /// Maps a point from octant 0 coordinates to world coordinates and checks if it blocks light.
+    ///
+    /// Tiles without an opacity, such as masked or out-of-bounds tiles, block light, matching
+    /// how the shadowcasting loop itself treats them.
     fn blocks_light(&self, x: i32, y: i32) -> bool {
         let map_pt = self.origin + self.transform * Point::new(y, x);
-        self.map.get(map_pt) == Some(Opacity::Opaque)
+        self.map.get(map_pt) != Some(Opacity::Transparent)
     }
 }
 
@@ -109,14 +112,14 @@ impl<'a, M: LabeledSpatialGraph<Opacity, NodeHandle = Point>> TileShape
             // We know the current tile is a wall, so we need to check if the tile above is clear
             if !self.blocks_light(x, y + 1) {
                 // Beveled corner - use top center (g in diagram)
-                return Slope::new(2 * y + 1, 2 * x);
+                Slope::new(2 * y + 1, 2 * x)
             } else {
                 // Non-beveled corner - use top left (a in diagram)
-                return Slope::new(2 * y + 1, 2 * x - 1);
+                Slope::new(2 * y + 1, 2 * x - 1)
             }
         } else {
             // For floor tiles, just use the top-left corner (a in diagram)
-            return Slope::new(2 * y + 1, 2 * x - 1);
+            Slope::new(2 * y + 1, 2 * x - 1)
         }
     }
 
@@ -133,14 +136,14 @@ impl<'a, M: LabeledSpatialGraph<Opacity, NodeHandle = Point>> TileShape
             // We know current tile is a wall, we can check if the tile to the right is clear
             if !self.blocks_light(x + 1, y) {
                 // Beveled corner - use bottom center (h in diagram)
-                return Slope::new(2 * y - 1, 2 * x);
+                Slope::new(2 * y - 1, 2 * x)
             } else {
                 // Non-beveled corner - use bottom right (d in diagram)
-                return Slope::new(2 * y - 1, 2 * x + 1);
+                Slope::new(2 * y - 1, 2 * x + 1)
             }
         } else {
             // For floor tiles, use the bottom-right corner (d in diagram)
-            return Slope::new(2 * y - 1, 2 * x + 1);
+            Slope::new(2 * y - 1, 2 * x + 1)
         }
     }
 
@@ -157,10 +160,10 @@ impl<'a, M: LabeledSpatialGraph<Opacity, NodeHandle = Point>> TileShape
         // We know the tile at (x,y) was a wall and we're now in a clear tile, so check to the right
         if !self.blocks_light(x + 1, y) {
             // Beveled - use bottom center (h in diagram)
-            return Slope::new(2 * y, 2 * x);
+            Slope::new(2 * y, 2 * x)
         } else {
             // Not beveled - use bottom right (d in diagram)
-            return Slope::new(2 * y, 2 * x + 1);
+            Slope::new(2 * y, 2 * x + 1)
         }
     }
 }
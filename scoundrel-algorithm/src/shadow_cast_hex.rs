@@ -0,0 +1,151 @@
+use scoundrel_geometry::Hex;
+
+use crate::{LabeledGraph, Opacity};
+
+/// Casts light in all directions from the given origin hex.
+///
+/// The map is scanned ring by ring outwards from the origin. Each ring of radius `k` is
+/// split into `6k` equal angular sectors, one per hex, and every opaque hex that is lit
+/// casts a shadow over its sector for all further rings. A hex is lit if its center is
+/// not strictly inside any shadow, so hexes bordering a shadow remain visible.
+///
+/// Hexes outside the map block light and are not reported.
+///
+/// # Arguments
+///
+/// * `map` - The map to cast light on.
+/// * `origin` - The origin hex to cast light from.
+/// * `range` - The maximum number of steps from the origin that can be lit.
+/// * `callback` - A callback function to call for each lit hex.
+pub fn cast_light_hex<M: LabeledGraph<Opacity, NodeHandle = Hex>, F: FnMut(Hex)>(
+    map: &M,
+    origin: Hex,
+    range: i32,
+    mut callback: F,
+) {
+    if map.get(origin).is_none() {
+        return;
+    }
+    callback(origin);
+
+    // shadows as fractions of a full turn, measured clockwise from the north-west corner
+    // of each ring. The first sector of a ring straddles zero, so its shadow is also
+    // recorded one full turn later. Fractions are kept exact so that hexes centered on a
+    // shadow boundary are treated consistently.
+    let mut shadows: Vec<(Turn, Turn)> = vec![];
+    for radius in 1..=range {
+        // sector boundaries fall on odd multiples of half a sector
+        let denominator = 12 * radius as i64;
+        let mut new_shadows = vec![];
+        for (idx, hex) in origin.ring(radius).into_iter().enumerate() {
+            let center = 2 * idx as i64;
+            let in_shadow = shadows.iter().any(|&(start, end)| {
+                start.lt(Turn(center, denominator)) && Turn(center, denominator).lt(end)
+            });
+            if in_shadow {
+                continue;
+            }
+            let opacity = map.get(hex);
+            if opacity.is_some() {
+                callback(hex);
+            }
+            if opacity != Some(Opacity::Transparent) {
+                new_shadows
+                    .push((Turn(center - 1, denominator), Turn(center + 1, denominator)));
+                if idx == 0 {
+                    new_shadows.push((
+                        Turn(denominator - 1, denominator),
+                        Turn(denominator + 1, denominator),
+                    ));
+                }
+            }
+        }
+        if !new_shadows.is_empty() {
+            shadows.extend(new_shadows);
+            shadows = merge_shadows(shadows);
+        }
+    }
+}
+
+/// An exact fraction of a full turn, as a numerator over a positive denominator.
+#[derive(Copy, Clone)]
+struct Turn(i64, i64);
+
+impl Turn {
+    fn lt(self, other: Turn) -> bool {
+        self.0 * other.1 < other.0 * self.1
+    }
+}
+
+/// Sorts shadows and joins any that overlap or touch, so that hexes centered on the
+/// boundary between two adjacent shadows are not lit.
+fn merge_shadows(mut shadows: Vec<(Turn, Turn)>) -> Vec<(Turn, Turn)> {
+    shadows.sort_by(|a, b| (a.0.0 * b.0.1).cmp(&(b.0.0 * a.0.1)));
+    let mut merged: Vec<(Turn, Turn)> = Vec::with_capacity(shadows.len());
+    for (start, end) in shadows {
+        match merged.last_mut() {
+            Some(last) if !last.1.lt(start) => {
+                if last.1.lt(end) {
+                    last.1 = end;
+                }
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use scoundrel_geometry::{HexGrid, HexNeighbor, Point};
+
+    use super::*;
+
+    fn compute_fov(map: &HexGrid<Opacity>, origin: Hex, range: i32) -> HashSet<Hex> {
+        let mut visible = HashSet::new();
+        cast_light_hex(map, origin, range, |hex| {
+            assert!(visible.insert(hex), "{hex:?} reported twice");
+        });
+        visible
+    }
+
+    #[test]
+    fn test_cast_light_hex_open() {
+        let map = HexGrid::new(15, 15, Opacity::Transparent);
+        let origin = Hex::from_offset(Point::new(7, 7));
+        let visible = compute_fov(&map, origin, 4);
+        assert_eq!(visible, origin.spiral(4).into_iter().collect());
+    }
+
+    #[test]
+    fn test_cast_light_hex_wall() {
+        let mut map = HexGrid::new(15, 15, Opacity::Transparent);
+        let origin = Hex::from_offset(Point::new(7, 7));
+        let wall = origin.neighbor(HexNeighbor::East);
+        map.set(wall, Opacity::Opaque);
+        let visible = compute_fov(&map, origin, 5);
+
+        assert!(visible.contains(&wall));
+        for steps in 2..=5 {
+            let behind = origin + HexNeighbor::East.offset() * steps;
+            assert!(!visible.contains(&behind), "{behind:?} should be in shadow");
+        }
+        // hexes off to the side of the wall stay visible
+        assert!(visible.contains(&wall.neighbor(HexNeighbor::NorthEast)));
+        assert!(visible.contains(&wall.neighbor(HexNeighbor::SouthEast)));
+        assert!(visible.contains(&(origin + HexNeighbor::West.offset() * 5)));
+    }
+
+    #[test]
+    fn test_cast_light_hex_enclosed() {
+        let mut map = HexGrid::new(15, 15, Opacity::Transparent);
+        let origin = Hex::from_offset(Point::new(7, 7));
+        for hex in origin.ring(2) {
+            map.set(hex, Opacity::Opaque);
+        }
+        let visible = compute_fov(&map, origin, 6);
+        assert_eq!(visible, origin.spiral(2).into_iter().collect());
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use scoundrel_util::MinHeapEntry;
+
+use crate::graph::SpatialGraph;
+
+/// Computes a minimum spanning tree of the component containing `root` using Prim's
+/// algorithm.
+///
+/// The weight of each edge is the graph's `distance` between its endpoints. The graph is
+/// treated as undirected, so every edge should be reported by `adjacent_nodes` from both
+/// of its endpoints.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to span.
+/// * `root` - Any node of the component to span.
+///
+/// # Returns
+///
+/// The edges of the tree as `(parent, child)` pairs, in the order they were added.
+pub fn minimum_spanning_tree<G: SpatialGraph>(
+    graph: &G,
+    root: G::NodeHandle,
+) -> Vec<(G::NodeHandle, G::NodeHandle)> {
+    let mut visited = HashSet::new();
+    let mut frontier = BinaryHeap::new();
+    let mut edges = vec![];
+
+    visited.insert(root);
+    for neighbor in graph.adjacent_nodes(root) {
+        frontier.push(MinHeapEntry {
+            value: (root, neighbor),
+            priority: graph.distance(root, neighbor),
+        });
+    }
+
+    while let Some(MinHeapEntry {
+        value: (from, to), ..
+    }) = frontier.pop()
+    {
+        if !visited.insert(to) {
+            continue;
+        }
+        edges.push((from, to));
+        for neighbor in graph.adjacent_nodes(to) {
+            if !visited.contains(&neighbor) {
+                frontier.push(MinHeapEntry {
+                    value: (to, neighbor),
+                    priority: graph.distance(to, neighbor),
+                });
+            }
+        }
+    }
+    edges
+}
+
+/// A minimal union-find structure over arbitrary hashable keys.
+pub(crate) struct DisjointSet<N> {
+    parents: HashMap<N, N>,
+}
+
+impl<N: Copy + Eq + Hash> DisjointSet<N> {
+    /// Creates a structure in which every key is its own set.
+    pub(crate) fn new() -> Self {
+        DisjointSet {
+            parents: HashMap::new(),
+        }
+    }
+
+    /// Returns the representative of the set containing `node`.
+    pub(crate) fn find(&mut self, node: N) -> N {
+        let mut root = node;
+        while let Some(&parent) = self.parents.get(&root) {
+            if parent == root {
+                break;
+            }
+            root = parent;
+        }
+        // path compression
+        let mut cur = node;
+        while cur != root {
+            let next = self.parents.insert(cur, root).unwrap_or(root);
+            cur = next;
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning false if they were already one.
+    pub(crate) fn union(&mut self, a: N, b: N) -> bool {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return false;
+        }
+        self.parents.insert(root_a, root_b);
+        true
+    }
+}
+
+/// Computes a minimum spanning forest over the given nodes using Kruskal's algorithm.
+///
+/// Unlike `minimum_spanning_tree`, the nodes do not need to be connected: each connected
+/// component yields its own tree. Only edges between nodes in `nodes` are considered.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to span.
+/// * `nodes` - The nodes to include in the forest.
+///
+/// # Returns
+///
+/// The edges of the forest in order of increasing weight.
+pub fn minimum_spanning_forest<G: SpatialGraph, I: IntoIterator<Item = G::NodeHandle>>(
+    graph: &G,
+    nodes: I,
+) -> Vec<(G::NodeHandle, G::NodeHandle)> {
+    let nodes: Vec<_> = nodes.into_iter().collect();
+    let included: HashSet<_> = nodes.iter().copied().collect();
+
+    let mut candidates = vec![];
+    for &node in &nodes {
+        for neighbor in graph.adjacent_nodes(node) {
+            if included.contains(&neighbor) {
+                candidates.push((graph.distance(node, neighbor), node, neighbor));
+            }
+        }
+    }
+    candidates.sort_by_key(|(weight, _, _)| *weight);
+
+    let mut components = DisjointSet::new();
+    candidates
+        .into_iter()
+        .filter(|(_, a, b)| components.union(*a, *b))
+        .map(|(_, a, b)| (a, b))
+        .collect()
+}
+
+/// A tree of shortest paths from a single root node to every node reachable from it.
+///
+/// Produced by `shortest_path_tree`.
+pub struct ShortestPathTree<N, D> {
+    root: N,
+    parents: HashMap<N, N>,
+    costs: HashMap<N, D>,
+}
+
+impl<N: Copy + Eq + Hash, D: Copy> ShortestPathTree<N, D> {
+    /// Returns the root node of the tree.
+    pub fn root(&self) -> N {
+        self.root
+    }
+
+    /// Returns the total cost of the shortest path from the root to `node`, or `None` if
+    /// `node` is unreachable.
+    pub fn cost(&self, node: N) -> Option<D> {
+        self.costs.get(&node).copied()
+    }
+
+    /// Returns the node preceding `node` on its shortest path from the root.
+    ///
+    /// Returns `None` for the root itself and for unreachable nodes.
+    pub fn parent(&self, node: N) -> Option<N> {
+        self.parents.get(&node).copied()
+    }
+
+    /// Returns the shortest path from the root to `node`, including both endpoints, or
+    /// `None` if `node` is unreachable.
+    pub fn path_to(&self, node: N) -> Option<Vec<N>> {
+        if !self.costs.contains_key(&node) {
+            return None;
+        }
+        let mut path = vec![node];
+        let mut cur = node;
+        while let Some(pred) = self.parents.get(&cur) {
+            cur = *pred;
+            path.push(cur);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns an iterator over all reachable nodes, including the root.
+    pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+        self.costs.keys().copied()
+    }
+
+    /// Returns an iterator over the edges of the tree as `(parent, child)` pairs.
+    pub fn edges(&self) -> impl Iterator<Item = (N, N)> + '_ {
+        self.parents.iter().map(|(child, parent)| (*parent, *child))
+    }
+}
+
+/// Computes the tree of shortest paths from `root` to every reachable node using
+/// Dijkstra's algorithm.
+///
+/// Edge costs are the graph's `distance` between adjacent nodes. To exclude impassable
+/// nodes, run this on a view such as `GraphMaskView`.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to search.
+/// * `root` - The node to compute shortest paths from.
+pub fn shortest_path_tree<G: SpatialGraph>(
+    graph: &G,
+    root: G::NodeHandle,
+) -> ShortestPathTree<G::NodeHandle, G::Distance> {
+    let mut parents = HashMap::new();
+    let mut costs = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+
+    costs.insert(root, G::Distance::default());
+    frontier.push(MinHeapEntry {
+        value: root,
+        priority: G::Distance::default(),
+    });
+
+    while let Some(MinHeapEntry {
+        value: current,
+        priority,
+    }) = frontier.pop()
+    {
+        if costs.get(&current).is_some_and(|cost| *cost < priority) {
+            // stale entry, a cheaper path has already been found
+            continue;
+        }
+        for candidate in graph.adjacent_nodes(current) {
+            let new_cost = priority + graph.distance(current, candidate);
+            if costs.get(&candidate).is_none_or(|cost| *cost > new_cost) {
+                costs.insert(candidate, new_cost);
+                parents.insert(candidate, current);
+                frontier.push(MinHeapEntry {
+                    value: candidate,
+                    priority: new_cost,
+                });
+            }
+        }
+    }
+
+    ShortestPathTree {
+        root,
+        parents,
+        costs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scoundrel_geometry::{Grid2D, Point};
+
+    use super::*;
+    use crate::AdjacencyGraph;
+
+    /// A weighted graph where the edge weight is stored in the distance function.
+    ///
+    /// ```text
+    ///   0 --1-- 1 --2-- 2
+    ///   |       |       |
+    ///   4       3       1
+    ///   |       |       |
+    ///   3 --5-- 4 --1-- 5
+    /// ```
+    fn create_weighted_graph() -> AdjacencyGraph<u32, (), impl Fn(u32, u32) -> u32> {
+        let weights = [
+            ((0, 1), 1),
+            ((1, 2), 2),
+            ((0, 3), 4),
+            ((1, 4), 3),
+            ((2, 5), 1),
+            ((3, 4), 5),
+            ((4, 5), 1),
+        ];
+        let mut graph = AdjacencyGraph::with_distance(move |a: u32, b: u32| {
+            if a == b {
+                return 0;
+            }
+            let key = (a.min(b), a.max(b));
+            weights
+                .iter()
+                .find(|(edge, _)| *edge == key)
+                .map_or(1, |(_, w)| *w)
+        });
+        for node in 0..6 {
+            graph.add_node(node, ());
+        }
+        for ((a, b), _) in weights {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    fn total_weight<G: SpatialGraph<Distance = u32>>(
+        graph: &G,
+        edges: &[(G::NodeHandle, G::NodeHandle)],
+    ) -> u32 {
+        edges.iter().map(|(a, b)| graph.distance(*a, *b)).sum()
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let graph = create_weighted_graph();
+        let tree = minimum_spanning_tree(&graph, 0);
+        assert_eq!(tree.len(), 5);
+        assert_eq!(total_weight(&graph, &tree), 9);
+        assert!(!tree.iter().any(|e| *e == (3, 4) || *e == (4, 3)));
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_matches_tree() {
+        let graph = create_weighted_graph();
+        let forest = minimum_spanning_forest(&graph, 0..6);
+        assert_eq!(forest.len(), 5);
+        assert_eq!(total_weight(&graph, &forest), 9);
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_disconnected() {
+        let mut graph = AdjacencyGraph::new();
+        for node in 0..6 {
+            graph.add_node(node, ());
+        }
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 0);
+        graph.add_edge(3, 4);
+
+        let forest = minimum_spanning_forest(&graph, 0..6);
+        assert_eq!(forest.len(), 3);
+        let tree = minimum_spanning_tree(&graph, 0);
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_subset() {
+        let graph = create_weighted_graph();
+        let forest = minimum_spanning_forest(&graph, [0, 1, 3]);
+        assert_eq!(forest.len(), 2);
+        assert_eq!(total_weight(&graph, &forest), 5);
+    }
+
+    #[test]
+    fn test_shortest_path_tree() {
+        let graph = create_weighted_graph();
+        let tree = shortest_path_tree(&graph, 0);
+
+        assert_eq!(tree.root(), 0);
+        assert_eq!(tree.cost(0), Some(0));
+        assert_eq!(tree.cost(4), Some(4));
+        assert_eq!(tree.cost(5), Some(4));
+        assert_eq!(tree.cost(3), Some(4));
+        assert_eq!(tree.parent(0), None);
+        assert_eq!(tree.path_to(5), Some(vec![0, 1, 2, 5]));
+        assert_eq!(tree.path_to(3), Some(vec![0, 3]));
+        assert_eq!(tree.nodes().count(), 6);
+        assert_eq!(tree.edges().count(), 5);
+    }
+
+    #[test]
+    fn test_shortest_path_tree_unreachable() {
+        let mut graph = AdjacencyGraph::new();
+        graph.add_node(0, ());
+        graph.add_node(1, ());
+        let tree = shortest_path_tree(&graph, 0);
+        assert_eq!(tree.cost(1), None);
+        assert_eq!(tree.path_to(1), None);
+        assert_eq!(tree.path_to(0), Some(vec![0]));
+    }
+
+    #[test]
+    fn test_shortest_path_tree_grid() {
+        let grid = Grid2D::new(4, 4, ());
+        let tree = shortest_path_tree(&grid, Point::new(0, 0));
+        assert_eq!(tree.nodes().count(), 16);
+        // diagonal steps cost 2 on a grid using squared distance
+        assert_eq!(tree.cost(Point::new(3, 3)), Some(6));
+        let path = tree.path_to(Point::new(3, 3)).unwrap();
+        assert_eq!(path.first(), Some(&Point::new(0, 0)));
+        assert_eq!(path.last(), Some(&Point::new(3, 3)));
+    }
+}
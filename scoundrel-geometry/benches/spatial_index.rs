@@ -0,0 +1,177 @@
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use scoundrel_geometry::quadtree::{QuadTree, RegionQuadTree, build_quadtree};
+use scoundrel_geometry::{Point, RTree, Rect};
+use scoundrel_util::SeededRng;
+
+const MAP_SIZE: i32 = 256;
+
+/// Generates `count` pseudo-random rectangles of up to `max_size` tiles on each side,
+/// scattered over the map.
+fn scattered_rects(count: usize, max_size: i32) -> Vec<(usize, Rect)> {
+    let mut rng = SeededRng::new(0x2545_f491);
+    let mut next = move |bound: i32| rng.range(0..bound);
+    (0..count)
+        .map(|id| {
+            let size = Point::new(1 + next(max_size), 1 + next(max_size));
+            let min = Point::new(next(MAP_SIZE - size.x), next(MAP_SIZE - size.y));
+            (id, Rect::with_size(min, size))
+        })
+        .collect()
+}
+
+fn query_rects() -> Vec<Rect> {
+    scattered_rects(64, 24)
+        .into_iter()
+        .map(|(_, r)| r)
+        .collect()
+}
+
+/// Compares indices over single-tile items, where every index type applies.
+fn bench_points(c: &mut Criterion) {
+    let bounds = Rect::with_size(Point::new(0, 0), Point::new(MAP_SIZE, MAP_SIZE));
+    let queries = query_rects();
+    let mut group = c.benchmark_group("spatial_index_points");
+    for count in [256, 4096] {
+        let items = scattered_rects(count, 1);
+        let points: Vec<(usize, Point)> =
+            items.iter().map(|(id, r)| (*id, r.min)).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("build/quadtree", count),
+            &count,
+            |b, _| b.iter(|| build_quadtree(black_box(points.clone()), bounds, 8)),
+        );
+        group.bench_with_input(BenchmarkId::new("build/rtree", count), &count, |b, _| {
+            b.iter(|| RTree::new(black_box(items.clone()), 8))
+        });
+
+        let static_tree = build_quadtree(points.clone(), bounds, 8);
+        let mut dynamic_tree = QuadTree::new(bounds, 8);
+        for (id, pt) in &points {
+            dynamic_tree.insert(*id, *pt).unwrap();
+        }
+        let rtree = RTree::new(items.clone(), 8);
+
+        group.bench_with_input(
+            BenchmarkId::new("query/quadtree", count),
+            &count,
+            |b, _| {
+                b.iter(|| {
+                    let mut found = 0;
+                    for query in &queries {
+                        static_tree.query_rect(*query, &mut |_| found += 1);
+                    }
+                    found
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("query/dynamic_quadtree", count),
+            &count,
+            |b, _| {
+                b.iter(|| {
+                    let mut found = 0;
+                    for query in &queries {
+                        dynamic_tree.query_rect(*query, |_| found += 1);
+                    }
+                    found
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("query/rtree", count), &count, |b, _| {
+            b.iter(|| {
+                let mut found = 0;
+                for query in &queries {
+                    rtree.query_rect(*query, |_| found += 1);
+                }
+                found
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("nearest/quadtree", count),
+            &count,
+            |b, _| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|q| static_tree.nearest(q.min, None).map(|(d, _)| d))
+                        .sum::<i32>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("nearest/rtree", count),
+            &count,
+            |b, _| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|q| rtree.nearest(q.min).map(|(d, _)| d))
+                        .sum::<i32>()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Compares indices over items spanning several tiles.
+fn bench_regions(c: &mut Criterion) {
+    let bounds = Rect::with_size(Point::new(0, 0), Point::new(MAP_SIZE, MAP_SIZE));
+    let queries = query_rects();
+    let mut group = c.benchmark_group("spatial_index_regions");
+    for count in [256, 4096] {
+        let items = scattered_rects(count, 12);
+
+        group.bench_with_input(
+            BenchmarkId::new("build/region_quadtree", count),
+            &count,
+            |b, _| {
+                b.iter(|| {
+                    let mut tree = RegionQuadTree::new(bounds, 8);
+                    for (id, rect) in black_box(&items) {
+                        tree.insert(*id, *rect);
+                    }
+                    tree
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("build/rtree", count), &count, |b, _| {
+            b.iter(|| RTree::new(black_box(items.clone()), 8))
+        });
+
+        let mut region_tree = RegionQuadTree::new(bounds, 8);
+        for (id, rect) in &items {
+            region_tree.insert(*id, *rect);
+        }
+        let rtree = RTree::new(items.clone(), 8);
+
+        group.bench_with_input(
+            BenchmarkId::new("query/region_quadtree", count),
+            &count,
+            |b, _| {
+                b.iter(|| {
+                    let mut found = 0;
+                    for query in &queries {
+                        region_tree.query_rect(*query, |_| found += 1);
+                    }
+                    found
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("query/rtree", count), &count, |b, _| {
+            b.iter(|| {
+                let mut found = 0;
+                for query in &queries {
+                    rtree.query_rect(*query, |_| found += 1);
+                }
+                found
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_points, bench_regions);
+criterion_main!(benches);
@@ -0,0 +1,452 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{Grid2D, Point, Rect};
+
+const WORD_BITS: i32 = u64::BITS as i32;
+
+/// A 2D grid of booleans packed into bits.
+///
+/// Each row is stored as a run of `u64` words, so set operations and morphology work on
+/// 64 cells at a time. This is a compact replacement for `Grid2D<bool>` in visibility,
+/// explored and passability masks.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: i32,
+    height: i32,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// Creates a new grid of the specified dimensions with every cell unset.
+    pub fn new(width: i32, height: i32) -> BitGrid {
+        let words_per_row = (width.max(0) as usize).div_ceil(WORD_BITS as usize);
+        BitGrid {
+            words: vec![0; words_per_row * height.max(0) as usize],
+            width,
+            height,
+            words_per_row,
+        }
+    }
+
+    /// Creates a new grid of the specified dimensions with every cell set to `value`.
+    pub fn filled(width: i32, height: i32, value: bool) -> BitGrid {
+        let mut grid = BitGrid::new(width, height);
+        grid.fill(value);
+        grid
+    }
+
+    /// Returns a `Rect` representing the bounds of the grid.
+    pub fn rect(&self) -> Rect {
+        Rect::with_size(Point::zero(), self.size())
+    }
+
+    /// Returns the size of the grid.
+    pub fn size(&self) -> Point {
+        Point::new(self.width, self.height)
+    }
+
+    /// Returns the width of the grid.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Returns the height of the grid.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Returns the word index and bit mask of the given point, if it is within the grid.
+    fn locate(&self, pt: Point) -> Option<(usize, u64)> {
+        if pt.x < 0 || pt.y < 0 || pt.x >= self.width || pt.y >= self.height {
+            return None;
+        }
+        let word = pt.y as usize * self.words_per_row + (pt.x / WORD_BITS) as usize;
+        Some((word, 1 << (pt.x % WORD_BITS)))
+    }
+
+    /// Returns the value at the given point, or `None` if it is outside the grid.
+    pub fn get(&self, pt: Point) -> Option<bool> {
+        let (word, mask) = self.locate(pt)?;
+        Some(self.words[word] & mask != 0)
+    }
+
+    /// Sets the value at the specified position.
+    ///
+    /// # Returns
+    /// `true` if the position is within the grid and the value was set, `false` otherwise.
+    pub fn set(&mut self, pt: Point, value: bool) -> bool {
+        match self.locate(pt) {
+            Some((word, mask)) => {
+                if value {
+                    self.words[word] |= mask;
+                } else {
+                    self.words[word] &= !mask;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets every cell in the grid to the given value.
+    pub fn fill(&mut self, value: bool) {
+        self.words.fill(if value { u64::MAX } else { 0 });
+        self.clear_padding();
+    }
+
+    /// Returns the mask of valid bits in the last word of each row.
+    fn last_word_mask(&self) -> u64 {
+        match self.width % WORD_BITS {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// Clears the unused bits past the end of each row, which every operation relies on
+    /// staying zero.
+    fn clear_padding(&mut self) {
+        if self.words_per_row == 0 {
+            return;
+        }
+        let mask = self.last_word_mask();
+        for row in self.words.chunks_mut(self.words_per_row) {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+
+    /// Returns the number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if any cell is set.
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|w| *w != 0)
+    }
+
+    /// Returns an iterator over the coordinates of every set cell, in row-major order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = Point> + '_ {
+        let words_per_row = self.words_per_row;
+        self.words.iter().enumerate().flat_map(move |(idx, word)| {
+            let y = (idx / words_per_row) as i32;
+            let x0 = (idx % words_per_row) as i32 * WORD_BITS;
+            let mut bits = *word;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as i32;
+                bits &= bits - 1;
+                Some(Point::new(x0 + bit, y))
+            })
+        })
+    }
+
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert_eq!(self.size(), other.size(), "bit grids must be the same size");
+    }
+
+    /// Sets every cell that is set in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids are not the same size.
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// Clears every cell that is not set in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids are not the same size.
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    /// Clears every cell that is set in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids are not the same size.
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+
+    /// Flips the value of every cell.
+    pub fn invert(&mut self) {
+        for word in &mut self.words {
+            *word = !*word;
+        }
+        self.clear_padding();
+    }
+
+    /// Returns a grid of the cells set in either grid.
+    pub fn union(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    /// Returns a grid of the cells set in both grids.
+    pub fn intersection(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    /// Returns a grid of the cells set in this grid but not in `other`.
+    pub fn difference(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.difference_with(other);
+        result
+    }
+
+    /// Returns a grid where each cell is set if it or any of its eight neighbors is set.
+    pub fn dilate(&self) -> BitGrid {
+        let wpr = self.words_per_row;
+        if wpr == 0 {
+            return self.clone();
+        }
+        // spread each row sideways first, then combine each row with its neighbors
+        let mut spread = Vec::with_capacity(self.words.len());
+        for row in self.words.chunks(wpr) {
+            for (idx, word) in row.iter().enumerate() {
+                let from_left = idx.checked_sub(1).map_or(0, |prev| row[prev] >> 63);
+                let from_right = row.get(idx + 1).map_or(0, |next| next << 63);
+                spread.push(word | (word << 1) | from_left | (word >> 1) | from_right);
+            }
+        }
+
+        let mut result = BitGrid {
+            words: spread.clone(),
+            ..*self
+        };
+        for (y, row) in result.words.chunks_mut(wpr).enumerate() {
+            if y > 0 {
+                let above = &spread[(y - 1) * wpr..y * wpr];
+                row.iter_mut().zip(above).for_each(|(w, a)| *w |= a);
+            }
+            if let Some(below) = spread.get((y + 1) * wpr..(y + 2) * wpr) {
+                row.iter_mut().zip(below).for_each(|(w, b)| *w |= b);
+            }
+        }
+        result.clear_padding();
+        result
+    }
+
+    /// Returns a grid where each cell is set if it and all of its eight neighbors are set.
+    ///
+    /// Neighbors outside the grid are ignored, so cells along the edge of the grid are not
+    /// eroded merely for being on the edge.
+    pub fn erode(&self) -> BitGrid {
+        let mut inverted = self.clone();
+        inverted.invert();
+        let mut result = inverted.dilate();
+        result.invert();
+        result
+    }
+
+    /// Converts the grid into a `Grid2D<bool>`.
+    pub fn to_grid(&self) -> Grid2D<bool> {
+        Grid2D::new(self.width, self.height, false).map_coords(|pt| self.get(pt).unwrap())
+    }
+}
+
+impl From<&Grid2D<bool>> for BitGrid {
+    fn from(grid: &Grid2D<bool>) -> Self {
+        let mut result = BitGrid::new(grid.width(), grid.height());
+        for pt in grid.iter_coords() {
+            if grid.get(pt) == Some(&true) {
+                result.set(pt, true);
+            }
+        }
+        result
+    }
+}
+
+/// The serialized form of a `BitGrid`, checked for consistency before use.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct BitGridData {
+    words: Vec<u64>,
+    width: i32,
+    height: i32,
+    words_per_row: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for BitGrid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = BitGridData::deserialize(deserializer)?;
+        let expected = BitGrid::new(data.width, data.height);
+        if data.words_per_row != expected.words_per_row
+            || data.words.len() != expected.words.len()
+        {
+            return Err(serde::de::Error::custom(
+                "word count does not match the grid dimensions",
+            ));
+        }
+        let grid = BitGrid {
+            words: data.words,
+            ..expected
+        };
+        let mut cleared = grid.clone();
+        cleared.clear_padding();
+        if cleared.words != grid.words {
+            return Err(serde::de::Error::custom(
+                "bits are set past the end of a row",
+            ));
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&str]) -> BitGrid {
+        let mut grid = BitGrid::new(rows[0].len() as i32, rows.len() as i32);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set(Point::new(x as i32, y as i32), c == '#');
+            }
+        }
+        grid
+    }
+
+    fn to_rows(grid: &BitGrid) -> Vec<String> {
+        (0..grid.height())
+            .map(|y| {
+                (0..grid.width())
+                    .map(|x| match grid.get(Point::new(x, y)).unwrap() {
+                        true => '#',
+                        false => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut grid = BitGrid::new(70, 3);
+        assert!(grid.set(Point::new(0, 0), true));
+        assert!(grid.set(Point::new(69, 2), true));
+        assert!(grid.set(Point::new(64, 1), true));
+        assert!(!grid.set(Point::new(70, 0), true));
+        assert!(!grid.set(Point::new(0, -1), true));
+
+        assert_eq!(grid.get(Point::new(0, 0)), Some(true));
+        assert_eq!(grid.get(Point::new(1, 0)), Some(false));
+        assert_eq!(grid.get(Point::new(69, 2)), Some(true));
+        assert_eq!(grid.get(Point::new(70, 2)), None);
+        assert_eq!(grid.count_ones(), 3);
+
+        grid.set(Point::new(64, 1), false);
+        assert_eq!(grid.count_ones(), 2);
+    }
+
+    #[test]
+    fn test_fill_and_invert() {
+        let mut grid = BitGrid::filled(65, 2, true);
+        assert_eq!(grid.count_ones(), 130);
+        grid.set(Point::new(3, 1), false);
+        grid.invert();
+        assert_eq!(grid.count_ones(), 1);
+        assert_eq!(grid.get(Point::new(3, 1)), Some(true));
+        grid.fill(false);
+        assert!(!grid.any());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = from_rows(&["##..", "#..."]);
+        let b = from_rows(&[".##.", "#..#"]);
+        assert_eq!(to_rows(&a.union(&b)), vec!["###.", "#..#"]);
+        assert_eq!(to_rows(&a.intersection(&b)), vec![".#..", "#..."]);
+        assert_eq!(to_rows(&a.difference(&b)), vec!["#...", "...."]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_operations_require_same_size() {
+        BitGrid::new(3, 3).union_with(&BitGrid::new(4, 3));
+    }
+
+    #[test]
+    fn test_iter_ones() {
+        let mut grid = BitGrid::new(130, 2);
+        let points = [
+            Point::new(1, 0),
+            Point::new(63, 0),
+            Point::new(64, 0),
+            Point::new(129, 0),
+            Point::new(0, 1),
+        ];
+        for pt in points {
+            grid.set(pt, true);
+        }
+        assert_eq!(grid.iter_ones().collect::<Vec<_>>(), points.to_vec());
+    }
+
+    #[test]
+    fn test_dilate_erode() {
+        let grid = from_rows(&[".....", ".....", "..#..", ".....", "....."]);
+        let dilated = grid.dilate();
+        assert_eq!(
+            to_rows(&dilated),
+            vec![".....", ".###.", ".###.", ".###.", "....."]
+        );
+        assert_eq!(dilated.erode(), grid);
+
+        // the edge of the grid does not erode
+        let full = BitGrid::filled(3, 3, true);
+        assert_eq!(full.erode(), full);
+    }
+
+    #[test]
+    fn test_dilate_across_words() {
+        let mut grid = BitGrid::new(128, 3);
+        grid.set(Point::new(63, 1), true);
+        grid.set(Point::new(127, 0), true);
+        let dilated = grid.dilate();
+        for x in 62..=64 {
+            for y in 0..3 {
+                assert_eq!(dilated.get(Point::new(x, y)), Some(true));
+            }
+        }
+        assert_eq!(dilated.get(Point::new(61, 1)), Some(false));
+        assert_eq!(dilated.get(Point::new(65, 1)), Some(false));
+        assert_eq!(dilated.get(Point::new(126, 1)), Some(true));
+        // nothing spills into padding or wraps into the next row
+        assert_eq!(dilated.count_ones(), 9 + 4);
+    }
+
+    #[test]
+    fn test_grid_roundtrip() {
+        let grid = Grid2D::from_sparse_points(
+            5,
+            4,
+            false,
+            vec![Point::new(1, 2), Point::new(4, 3)],
+            true,
+        );
+        let bits = BitGrid::from(&grid);
+        assert_eq!(bits.count_ones(), 2);
+        assert_eq!(bits.to_grid().data, grid.data);
+    }
+}
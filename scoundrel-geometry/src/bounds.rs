@@ -221,6 +221,27 @@ impl<T: Copy + Ord + ops::Add<T, Output = T>> Bounds<T> {
 }
 
 impl<T: Copy + Ord> Bounds<T> {
+    /// Returns the overlapping region of this bounding box and another.
+    ///
+    /// Returns `None` if the overlap is empty.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Vector2::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Vector2::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        if max.x <= min.x || max.y <= min.y {
+            None
+        } else {
+            Some(Bounds::with_points(min, max))
+        }
+    }
+
+    /// Returns true if `other` lies entirely within this bounding box.
+    pub fn contains_bounds(&self, other: &Self) -> bool {
+        other.min.x >= self.min.x
+            && other.min.y >= self.min.y
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+    }
+
     /// Returns the closest point within the bounding box with respect to a given query point.
     pub fn closest_pt(&self, query: Vector2<T>) -> Vector2<T> {
         Vector2::new(
@@ -301,6 +322,39 @@ mod tests {
         assert_eq!(size.y, 20);
     }
 
+    #[test]
+    fn test_intersection() {
+        let b1 = Bounds::with_points(Vector2::new(0, 0), Vector2::new(10, 10));
+        let b2 = Bounds::with_points(Vector2::new(5, -5), Vector2::new(15, 5));
+        let b3 = Bounds::with_points(Vector2::new(10, 0), Vector2::new(20, 10));
+        assert_eq!(
+            b1.intersection(&b2),
+            Some(Bounds::with_points(Vector2::new(5, 0), Vector2::new(10, 5)))
+        );
+        assert_eq!(b1.intersection(&b2), b2.intersection(&b1));
+        assert_eq!(b1.intersection(&b1), Some(b1));
+        // touching edges do not overlap
+        assert_eq!(b1.intersection(&b3), None);
+    }
+
+    #[test]
+    fn test_contains_bounds() {
+        let outer = Bounds::with_points(Vector2::new(0, 0), Vector2::new(10, 10));
+        assert!(outer.contains_bounds(&outer));
+        assert!(outer.contains_bounds(&Bounds::with_points(
+            Vector2::new(2, 3),
+            Vector2::new(10, 4)
+        )));
+        assert!(!outer.contains_bounds(&Bounds::with_points(
+            Vector2::new(-1, 3),
+            Vector2::new(5, 4)
+        )));
+        assert!(!outer.contains_bounds(&Bounds::with_points(
+            Vector2::new(5, 5),
+            Vector2::new(11, 6)
+        )));
+    }
+
     #[test]
     fn test_with_size() {
         let b = Bounds::with_size(Vector2::new(0, 0), Vector2::new(10, 20));
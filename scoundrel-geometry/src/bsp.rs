@@ -1,20 +1,27 @@
-use std::cell::RefCell;
-use std::rc::{Rc, Weak};
+use std::ops::Index;
 
-use crate::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
 
-/// A reference-counted handle to a BSP tree node.
-///
-/// This type provides shared ownership of a node with interior mutability
-/// through RefCell, allowing the tree structure to be modified while
-/// maintaining parent-child relationships.
-pub type NodeHandle<T> = Rc<RefCell<Node<T>>>;
+use crate::*;
 
-/// A weak reference to a BSP tree node.
+/// A stable identifier for a node of a BSP `Tree`.
 ///
-/// Used to prevent reference cycles between parent and child nodes, or
-/// between neighboring nodes in the BSP tree.
-pub type NodeWeakHandle<T> = Weak<RefCell<Node<T>>>;
+/// Nodes are never removed from a tree, so an ID remains valid, and refers to the same
+/// node, for as long as the tree it came from exists.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Returns the position of the node in the tree's arena.
+    ///
+    /// Nodes are stored in the order they were created, starting with the root at 0.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
 
 /// A half-space with integer coordinates.
 ///
@@ -25,18 +32,20 @@ pub type HalfSpace = AxialHalfSpace<i32>;
 /// Represents an edge between two adjacent nodes in a BSP tree.
 ///
 /// A HalfEdge contains a line segment that forms part of the boundary between
-/// two nodes, along with a reference to the neighboring node. The term "half" indicates
+/// two nodes, along with the ID of the neighboring node. The term "half" indicates
 /// that each boundary between two nodes is represented by two separate edge objects,
 /// one in each node pointing to the other.
-#[derive(Clone)]
-pub struct HalfEdge<T> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct HalfEdge {
     /// The line segment that forms this edge.
     pub line: OrthoLine,
 
-    /// A weak reference to the neighboring node connected by this edge.
-    pub neighbor: NodeWeakHandle<T>,
+    /// The neighboring node connected by this edge.
+    pub neighbor: NodeId,
 }
-impl<T> HalfEdge<T> {
+
+impl HalfEdge {
     /// Splits this edge along the given half-space.
     ///
     /// When a node is split by a half-space, its edges may also need to be split
@@ -49,10 +58,10 @@ impl<T> HalfEdge<T> {
     /// # Returns
     /// * `Some(HalfEdge)` if the edge intersects with the half-space
     /// * `None` if the edge does not intersect with the half-space
-    pub fn split(&self, half_space: HalfSpace) -> Option<HalfEdge<T>> {
+    pub fn split(&self, half_space: HalfSpace) -> Option<HalfEdge> {
         half_space.clip_line(self.line).map(|new_line| HalfEdge {
             line: new_line,
-            neighbor: self.neighbor.clone(),
+            neighbor: self.neighbor,
         })
     }
 }
@@ -60,20 +69,28 @@ impl<T> HalfEdge<T> {
 /// A node in a binary space partitioning tree.
 ///
 /// Each node represents a rectangular region of space that may be subdivided into
-/// two child nodes. Nodes maintain connections to their parent, children, and
-/// neighboring nodes through edges. Each node also contains a payload of type `T`.
+/// two child nodes. Nodes refer to their parent, children, and neighboring nodes by
+/// `NodeId`. Each node also contains a payload of type `T`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
 pub struct Node<T> {
     /// The rectangular bounds of this node.
     pub bounds: Rect,
 
-    /// A weak reference to the parent node, if any.
-    pub parent: Option<NodeWeakHandle<T>>,
+    /// The parent node, if any.
+    pub parent: Option<NodeId>,
 
-    /// References to the two child nodes, if this node has been split.
-    pub children: Option<[NodeHandle<T>; 2]>,
+    /// The two child nodes, if this node has been split.
+    ///
+    /// The first child lies in the half-space the node was split along, the second in
+    /// its opposite.
+    pub children: Option<[NodeId; 2]>,
 
     /// Edges connecting this node to adjacent nodes in the BSP tree.
-    pub edges: Vec<HalfEdge<T>>,
+    ///
+    /// Edges are only kept up to date for leaves; once a node is split, its edges
+    /// describe its neighbors at the time of the split.
+    pub edges: Vec<HalfEdge>,
 
     /// The payload data associated with this node.
     pub contents: T,
@@ -97,11 +114,11 @@ impl<T> Node<T> {
     /// parent-child relationships between nodes.
     ///
     /// # Arguments
-    /// * `parent` - A weak reference to the parent node
+    /// * `parent` - The ID of the parent node
     ///
     /// # Returns
-    /// The updated node with the parent reference set
-    pub fn with_parent(mut self, parent: NodeWeakHandle<T>) -> Self {
+    /// The updated node with the parent set
+    pub fn with_parent(mut self, parent: NodeId) -> Self {
         self.parent = Some(parent);
         self
     }
@@ -117,18 +134,47 @@ impl<T> Node<T> {
     ///
     /// # Returns
     /// The updated node with the edges set
-    pub fn with_edges<I: IntoIterator<Item = HalfEdge<T>>>(mut self, edges: I) -> Self {
+    pub fn with_edges<I: IntoIterator<Item = HalfEdge>>(mut self, edges: I) -> Self {
         self.edges = edges.into_iter().collect();
         self
     }
+
+    /// Returns true if the node has not been split.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_none()
+    }
+}
+
+/// Errors returned when building a `Tree` from a list of nodes.
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TreeError {
+    /// The list of nodes was empty, so there is no root.
+    #[error("a BSP tree must have a root node")]
+    Empty,
+    /// A node's parent and children do not agree with the rest of the tree.
+    #[error("node {0} is not linked to its parent or children correctly")]
+    InvalidLinks(usize),
+    /// A node has an edge to a node that does not exist.
+    #[error("node {0} has an edge to a nonexistent node")]
+    InvalidNeighbor(usize),
 }
 
 /// A binary space partitioning tree with a payload of type `T` attached to each node.
+///
+/// Nodes live in a single arena and refer to each other by `NodeId`, so the tree is
+/// `Send` and `Sync` whenever `T` is. The root is created with the tree, and each split
+/// appends the two new children; nodes are never removed.
+///
+/// With the `serde` feature, the tree is serialized as its list of nodes.
+#[derive(Debug, Clone)]
 pub struct Tree<T: Copy> {
-    pub root: NodeHandle<T>,
+    nodes: Vec<Node<T>>,
 }
 
 impl<T: Copy> Tree<T> {
+    /// The ID of the root node of every tree.
+    pub const ROOT: NodeId = NodeId(0);
+
     /// Constructs a new BSP Tree with the specified bounds and root node payload.
     ///
     /// Creates a single root node with the given bounds and contents.
@@ -142,11 +188,66 @@ impl<T: Copy> Tree<T> {
     /// A new BSP Tree with a single root node
     pub fn new(bounds: Rect, root_contents: T) -> Self {
         Self {
-            root: Rc::new(RefCell::new(Node::new(bounds, root_contents))),
+            nodes: vec![Node::new(bounds, root_contents)],
+        }
+    }
+
+    /// Returns the ID of the root node.
+    pub fn root(&self) -> NodeId {
+        Self::ROOT
+    }
+
+    /// Returns the node with the given ID, or `None` if it is not part of this tree.
+    pub fn get(&self, id: NodeId) -> Option<&Node<T>> {
+        self.nodes.get(id.0)
+    }
+
+    /// Returns a mutable reference to the contents of a node.
+    pub fn contents_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.nodes.get_mut(id.0).map(|node| &mut node.contents)
+    }
+
+    /// Returns the total number of nodes in the tree, including the root.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns an iterator over all nodes in the tree in the order they were created,
+    /// which visits every parent before its children.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &Node<T>)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (NodeId(idx), node))
+    }
+
+    /// Returns an iterator over the leaves of the tree, in the order they were created.
+    pub fn leaves(&self) -> impl Iterator<Item = (NodeId, &Node<T>)> {
+        self.nodes().filter(|(_, node)| node.is_leaf())
+    }
+
+    /// Returns an iterator over the nodes sharing an edge with the given node.
+    ///
+    /// For a leaf, these are the neighboring leaves.
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self[id].edges.iter().map(|edge| edge.neighbor)
+    }
+
+    /// Returns the leaf containing the given point, or `None` if it lies outside the tree.
+    pub fn leaf_at(&self, point: Point) -> Option<NodeId> {
+        let mut id = Self::ROOT;
+        if !self[id].bounds.contains(point) {
+            return None;
+        }
+        while let Some(children) = self[id].children {
+            id = children
+                .into_iter()
+                .find(|child| self[*child].bounds.contains(point))?;
         }
+        Some(id)
     }
 
-    /// Splits a node in the tree along the specified half-space.
+    /// Splits a leaf of the tree along the specified half-space.
     ///
     /// This method divides the specified node into two child nodes along the given
     /// half-space boundary. It updates all relevant tree structures including:
@@ -157,69 +258,72 @@ impl<T: Copy> Tree<T> {
     /// - Setting contents for the new nodes using the provided function
     ///
     /// # Arguments
-    /// * `handle` - Reference to the node to split
+    /// * `id` - The node to split
     /// * `half_space` - The half-space to split the node along
     /// * `f` - A function that generates contents for the new child nodes
     ///
     /// # Returns
-    /// `true` if the split was successful, `false` if the half-space doesn't
-    /// intersect the node's bounds (meaning no split is possible)
+    /// The IDs of the new children, the one inside `half_space` first, or `None` if the
+    /// node has already been split or the half-space does not divide its bounds into two
+    /// non-empty parts (meaning no split is possible)
+    ///
+    /// # Panics
+    /// Panics if `id` does not belong to this tree.
     pub fn split<F: FnMut(&T, Rect) -> T>(
         &mut self,
-        handle: NodeHandle<T>,
+        id: NodeId,
         half_space: HalfSpace,
         mut f: F,
-    ) -> bool {
-        let (bounds, contents, old_edges) = {
-            let n = handle.borrow();
-            (n.bounds, n.contents, n.edges.clone())
-        };
-        if !half_space.intersects_rect(bounds) {
-            return false;
+    ) -> Option<[NodeId; 2]> {
+        let node = &self[id];
+        if !node.is_leaf() || !half_space.intersects_rect(node.bounds) {
+            return None;
         }
-
-        let (above, below) = Self::create_children(
-            &handle, &bounds, half_space, contents, &old_edges, &mut f,
-        );
-        Self::update_neighbor_edges(
-            &above, &below, &bounds, half_space, old_edges, &handle,
+        let above_bounds = half_space.clip_rect(node.bounds)?;
+        let below_bounds = half_space.opposite().clip_rect(node.bounds)?;
+        let (bounds, old_edges) = (node.bounds, node.edges.clone());
+
+        let above = self.create_child(id, above_bounds, half_space, &old_edges, &mut f);
+        let below = self.create_child(
+            id,
+            below_bounds,
+            half_space.opposite(),
+            &old_edges,
+            &mut f,
         );
-        handle.borrow_mut().children = Some([above, below]);
-        true
+        self.update_neighbor_edges(id, [above, below], &bounds, half_space, &old_edges);
+        self.nodes[id.0].children = Some([above, below]);
+        Some([above, below])
     }
 
-    /// Creates child nodes for the given parent node.
-    fn create_children<Func: FnMut(&T, Rect) -> T>(
-        handle: &NodeHandle<T>,
-        bounds: &Rect,
+    /// Appends a child of `parent` covering `bounds`, inheriting the parts of the
+    /// parent's edges that lie within `half_space`.
+    fn create_child<F: FnMut(&T, Rect) -> T>(
+        &mut self,
+        parent: NodeId,
+        bounds: Rect,
         half_space: HalfSpace,
-        contents: T,
-        old_edges: &[HalfEdge<T>],
-        f: &mut Func,
-    ) -> (NodeHandle<T>, NodeHandle<T>) {
-        let mut make_child = |hs: HalfSpace| -> NodeHandle<T> {
-            let new_bounds = hs.clip_rect(*bounds).unwrap();
-            let new_contents = f(&contents, new_bounds);
-            let new_edges = old_edges.iter().filter_map(|edge| edge.split(hs));
-            Rc::new(RefCell::new(
-                Node::new(new_bounds, new_contents)
-                    .with_parent(Rc::downgrade(handle))
-                    .with_edges(new_edges),
-            ))
-        };
-        let above = make_child(half_space);
-        let below = make_child(half_space.opposite());
-        (above, below)
+        old_edges: &[HalfEdge],
+        f: &mut F,
+    ) -> NodeId {
+        let contents = f(&self[parent].contents, bounds);
+        let edges = old_edges.iter().filter_map(|edge| edge.split(half_space));
+        self.nodes.push(
+            Node::new(bounds, contents)
+                .with_parent(parent)
+                .with_edges(edges),
+        );
+        NodeId(self.nodes.len() - 1)
     }
 
     /// Updates neighbor edges after splitting a node.
     fn update_neighbor_edges(
-        above: &NodeHandle<T>,
-        below: &NodeHandle<T>,
+        &mut self,
+        id: NodeId,
+        [above, below]: [NodeId; 2],
         bounds: &Rect,
         half_space: HalfSpace,
-        old_edges: Vec<HalfEdge<T>>,
-        handle: &NodeHandle<T>,
+        old_edges: &[HalfEdge],
     ) {
         let mut split_start = bounds.min;
         split_start[half_space.axis] = half_space.offset;
@@ -230,46 +334,103 @@ impl<T: Copy> Tree<T> {
             length: split_length,
         };
 
-        above.borrow_mut().edges.push(HalfEdge {
+        self.nodes[above.0].edges.push(HalfEdge {
             line: split_line,
-            neighbor: Rc::downgrade(below),
+            neighbor: below,
         });
         split_line.start[half_space.axis] -= 1;
-        below.borrow_mut().edges.push(HalfEdge {
+        self.nodes[below.0].edges.push(HalfEdge {
             line: split_line,
-            neighbor: Rc::downgrade(above),
+            neighbor: above,
         });
         for edge in old_edges {
-            let neighbor = edge.neighbor.upgrade().unwrap();
-            let mut neighbor = neighbor.borrow_mut();
-            for idx in 0..neighbor.edges.len() {
-                if let Some(neighbor_neighbor) = neighbor.edges[idx].neighbor.upgrade() {
-                    if Rc::ptr_eq(&neighbor_neighbor, handle) {
-                        let ep = neighbor.edges.remove(idx);
-                        if let Some(edge_above) = half_space.clip_line(ep.line) {
-                            neighbor.edges.push(HalfEdge {
-                                line: edge_above,
-                                neighbor: Rc::downgrade(above),
-                            })
-                        }
-                        if let Some(edge_below) = half_space.opposite().clip_line(ep.line)
-                        {
-                            neighbor.edges.push(HalfEdge {
-                                line: edge_below,
-                                neighbor: Rc::downgrade(below),
-                            })
-                        }
-                        break;
-                    }
+            let neighbor = &mut self.nodes[edge.neighbor.0];
+            if let Some(idx) = neighbor.edges.iter().position(|e| e.neighbor == id) {
+                let ep = neighbor.edges.remove(idx);
+                if let Some(edge_above) = half_space.clip_line(ep.line) {
+                    neighbor.edges.push(HalfEdge {
+                        line: edge_above,
+                        neighbor: above,
+                    })
+                }
+                if let Some(edge_below) = half_space.opposite().clip_line(ep.line) {
+                    neighbor.edges.push(HalfEdge {
+                        line: edge_below,
+                        neighbor: below,
+                    })
                 }
             }
         }
     }
 }
 
+impl<T: Copy> Index<NodeId> for Tree<T> {
+    type Output = Node<T>;
+
+    fn index(&self, id: NodeId) -> &Self::Output {
+        &self.nodes[id.0]
+    }
+}
+
+impl<T: Copy> TryFrom<Vec<Node<T>>> for Tree<T> {
+    type Error = TreeError;
+
+    /// Builds a tree from its nodes, as listed by `Tree::nodes`, checking that they are
+    /// linked together consistently.
+    fn try_from(nodes: Vec<Node<T>>) -> Result<Self, Self::Error> {
+        if nodes.is_empty() {
+            return Err(TreeError::Empty);
+        }
+        for (idx, node) in nodes.iter().enumerate() {
+            // children always come after their parent, which rules out cycles
+            let parent_ok = match node.parent {
+                None => idx == 0,
+                Some(NodeId(parent)) => {
+                    parent < idx
+                        && nodes[parent]
+                            .children
+                            .is_some_and(|children| children.contains(&NodeId(idx)))
+                }
+            };
+            let children_ok = node.children.is_none_or(|children| {
+                children[0] != children[1]
+                    && children.iter().all(|child| {
+                        child.0 > idx
+                            && nodes
+                                .get(child.0)
+                                .is_some_and(|c| c.parent == Some(NodeId(idx)))
+                    })
+            });
+            if !parent_ok || !children_ok {
+                return Err(TreeError::InvalidLinks(idx));
+            }
+            if node.edges.iter().any(|edge| edge.neighbor.0 >= nodes.len()) {
+                return Err(TreeError::InvalidNeighbor(idx));
+            }
+        }
+        Ok(Tree { nodes })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Copy + Serialize> Serialize for Tree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.nodes.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Copy + Deserialize<'de>> Deserialize<'de> for Tree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let nodes = Vec::<Node<T>>::deserialize(deserializer)?;
+        Tree::try_from(nodes).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{four_leaves, split_at};
 
     fn create_test_tree() -> Tree<u32> {
         let bounds = Rect::with_points(Point::new(0, 0), Point::new(10, 10));
@@ -279,59 +440,49 @@ mod tests {
     #[test]
     fn test_tree_creation() {
         let tree = create_test_tree();
+        let root = &tree[tree.root()];
         assert_eq!(
-            tree.root.borrow().bounds,
+            root.bounds,
             Rect::with_points(Point::new(0, 0), Point::new(10, 10))
         );
-        assert_eq!(tree.root.borrow().contents, 1);
-        assert!(tree.root.borrow().parent.is_none());
-        assert!(tree.root.borrow().children.is_none());
+        assert_eq!(root.contents, 1);
+        assert!(root.parent.is_none());
+        assert!(root.children.is_none());
+        assert_eq!(tree.node_count(), 1);
     }
 
     #[test]
     fn test_node_split() {
         let mut tree = create_test_tree();
-        let half_space = HalfSpace {
-            axis: Axis2D::X,
-            offset: 5,
-            positive: true,
-        };
-        let root_clone = tree.root.clone();
-        let split_result = tree.split(root_clone, half_space, |_, _| 2);
+        let half_space = split_at(Axis2D::X, 5);
+        let split_result = tree.split(tree.root(), half_space, |_, _| 2);
 
-        assert!(split_result);
-        let root_node = tree.root.borrow();
-        assert!(root_node.children.is_some());
-        let children = root_node.children.as_ref().unwrap();
-        let (above, below) = (&children[0], &children[1]);
+        assert!(split_result.is_some());
+        let [above, below] = tree[tree.root()].children.unwrap();
+        assert_eq!(split_result, Some([above, below]));
+        let (above, below) = (&tree[above], &tree[below]);
 
         // Test that child nodes are created correctly
         assert_eq!(
-            above.borrow().bounds,
+            above.bounds,
             Rect::with_points(Point::new(5, 0), Point::new(10, 10))
         );
-        assert_eq!(above.borrow().contents, 2);
+        assert_eq!(above.contents, 2);
         assert_eq!(
-            below.borrow().bounds,
+            below.bounds,
             Rect::with_points(Point::new(0, 0), Point::new(5, 10))
         );
-        assert_eq!(below.borrow().contents, 2);
+        assert_eq!(below.contents, 2);
 
         // Test that child nodes have their parent set correctly
-        assert!(Rc::ptr_eq(
-            &above.borrow().parent.as_ref().unwrap().upgrade().unwrap(),
-            &tree.root
-        ));
-        assert!(Rc::ptr_eq(
-            &below.borrow().parent.as_ref().unwrap().upgrade().unwrap(),
-            &tree.root
-        ));
+        assert_eq!(above.parent, Some(tree.root()));
+        assert_eq!(below.parent, Some(tree.root()));
 
         // Test that edges are updated correctly
-        assert_eq!(above.borrow().edges.len(), 1);
-        assert_eq!(below.borrow().edges.len(), 1);
+        assert_eq!(above.edges.len(), 1);
+        assert_eq!(below.edges.len(), 1);
         assert_eq!(
-            above.borrow().edges[0].line,
+            above.edges[0].line,
             OrthoLine {
                 axis: Axis2D::Y,
                 start: Point::new(5, 0),
@@ -339,7 +490,7 @@ mod tests {
             }
         );
         assert_eq!(
-            below.borrow().edges[0].line,
+            below.edges[0].line,
             OrthoLine {
                 axis: Axis2D::Y,
                 start: Point::new(4, 0),
@@ -351,17 +502,29 @@ mod tests {
     #[test]
     fn test_node_split_no_intersection() {
         let mut tree = create_test_tree();
-        let half_space = HalfSpace {
-            axis: Axis2D::X,
-            offset: 20,
-            positive: true,
-        };
-        let root_clone = tree.root.clone();
-        let split_result = tree.split(root_clone, half_space, |_, _| 2);
+        let split_result = tree.split(tree.root(), split_at(Axis2D::X, 20), |_, _| 2);
+
+        assert!(split_result.is_none());
+        assert!(tree[tree.root()].children.is_none());
+
+        // a split along the node's own boundary would leave one side empty
+        assert!(
+            tree.split(tree.root(), split_at(Axis2D::X, 10), |_, _| 2)
+                .is_none()
+        );
+        assert_eq!(tree.node_count(), 1);
+    }
 
-        assert!(!split_result);
-        let root_node = tree.root.borrow();
-        assert!(root_node.children.is_none());
+    #[test]
+    fn test_split_only_leaves() {
+        let mut tree = create_test_tree();
+        tree.split(tree.root(), split_at(Axis2D::X, 5), |_, _| 2)
+            .unwrap();
+        assert!(
+            tree.split(tree.root(), split_at(Axis2D::Y, 5), |_, _| 3)
+                .is_none()
+        );
+        assert_eq!(tree.node_count(), 3);
     }
 
     #[test]
@@ -375,30 +538,25 @@ mod tests {
         assert!(node.parent.is_none());
         assert!(node.children.is_none());
         assert!(node.edges.is_empty());
+        assert!(node.is_leaf());
     }
 
     #[test]
     fn test_node_with_parent() {
         let parent_bounds = Rect::with_points(Point::new(0, 0), Point::new(20, 20));
-        let parent = Rc::new(RefCell::new(Node::new(parent_bounds, 1u32)));
-        let parent_weak = Rc::downgrade(&parent);
+        let tree = Tree::new(parent_bounds, 1u32);
 
         let bounds = Rect::with_points(Point::new(5, 5), Point::new(15, 15));
-        let node = Node::new(bounds, 2u32).with_parent(parent_weak.clone());
+        let node = Node::new(bounds, 2u32).with_parent(tree.root());
 
         // Check parent reference is set
-        assert!(node.parent.is_some());
-        assert!(Rc::ptr_eq(
-            &node.parent.as_ref().unwrap().upgrade().unwrap(),
-            &parent
-        ));
+        assert_eq!(node.parent, Some(tree.root()));
     }
 
     #[test]
     fn test_node_with_edges() {
         let bounds = Rect::with_points(Point::new(0, 0), Point::new(10, 10));
-        let neighbor_bounds = Rect::with_points(Point::new(10, 0), Point::new(20, 10));
-        let neighbor = Rc::new(RefCell::new(Node::new(neighbor_bounds, 2u32)));
+        let neighbor = NodeId(1);
 
         // Create an edge between nodes
         let edge = HalfEdge {
@@ -407,7 +565,7 @@ mod tests {
                 start: Point::new(10, 0),
                 length: 10,
             },
-            neighbor: Rc::downgrade(&neighbor),
+            neighbor,
         };
 
         // Create node with the edge
@@ -418,16 +576,12 @@ mod tests {
         assert_eq!(node.edges[0].line.axis, Axis2D::Y);
         assert_eq!(node.edges[0].line.start, Point::new(10, 0));
         assert_eq!(node.edges[0].line.length, 10);
-        assert!(Rc::ptr_eq(
-            &node.edges[0].neighbor.upgrade().unwrap(),
-            &neighbor
-        ));
+        assert_eq!(node.edges[0].neighbor, neighbor);
     }
 
     #[test]
     fn test_half_edge_split() {
-        let neighbor_bounds = Rect::with_points(Point::new(10, 0), Point::new(20, 10));
-        let neighbor = Rc::new(RefCell::new(Node::new(neighbor_bounds, 2u32)));
+        let neighbor = NodeId(3);
 
         // Create a horizontal edge
         let edge = HalfEdge {
@@ -436,15 +590,11 @@ mod tests {
                 start: Point::new(0, 5),
                 length: 10, // spans x=0 to x=9
             },
-            neighbor: Rc::downgrade(&neighbor),
+            neighbor,
         };
 
         // Test splitting edge with a vertical half-space at x=3 (positive side)
-        let half_space = HalfSpace {
-            axis: Axis2D::X,
-            offset: 3,
-            positive: true,
-        };
+        let half_space = split_at(Axis2D::X, 3);
 
         let split_edge = edge.split(half_space).unwrap();
 
@@ -452,19 +602,10 @@ mod tests {
         assert_eq!(split_edge.line.axis, Axis2D::X);
         assert_eq!(split_edge.line.start, Point::new(3, 5)); // Start moved to x=3
         assert_eq!(split_edge.line.length, 7); // Length reduced to 7 (x=3 to x=9)
-        assert!(Rc::ptr_eq(
-            &split_edge.neighbor.upgrade().unwrap(),
-            &neighbor
-        ));
+        assert_eq!(split_edge.neighbor, neighbor);
 
         // Test splitting with a half-space that doesn't intersect the edge
-        let non_intersecting_hs = HalfSpace {
-            axis: Axis2D::X,
-            offset: 20,
-            positive: true,
-        };
-
-        let split_result = edge.split(non_intersecting_hs);
+        let split_result = edge.split(split_at(Axis2D::X, 20));
         assert!(split_result.is_none());
     }
 
@@ -472,33 +613,23 @@ mod tests {
     fn test_tree_split_y_axis() {
         // Test splitting on Y axis
         let mut tree = create_test_tree();
-        let half_space = HalfSpace {
-            axis: Axis2D::Y,
-            offset: 5,
-            positive: true,
-        };
-
-        let root_clone = tree.root.clone();
-        let split_result = tree.split(root_clone, half_space, |_, _| 3);
-
-        assert!(split_result);
-        let root_node = tree.root.borrow();
-        let children = root_node.children.as_ref().unwrap();
-        let (above, below) = (&children[0], &children[1]);
+        let [above, below] = tree
+            .split(tree.root(), split_at(Axis2D::Y, 5), |_, _| 3)
+            .unwrap();
 
         // Check bounds are split correctly on Y axis
         assert_eq!(
-            above.borrow().bounds,
+            tree[above].bounds,
             Rect::with_points(Point::new(0, 5), Point::new(10, 10))
         );
         assert_eq!(
-            below.borrow().bounds,
+            tree[below].bounds,
             Rect::with_points(Point::new(0, 0), Point::new(10, 5))
         );
 
         // Check contents are updated correctly
-        assert_eq!(above.borrow().contents, 3);
-        assert_eq!(below.borrow().contents, 3);
+        assert_eq!(tree[above].contents, 3);
+        assert_eq!(tree[below].contents, 3);
     }
 
     #[test]
@@ -506,50 +637,28 @@ mod tests {
         // Create a tree and perform multiple splits
         let mut tree = create_test_tree();
 
-        // First split on X axis
-        let half_space_x = HalfSpace {
-            axis: Axis2D::X,
-            offset: 5,
-            positive: true,
-        };
-
-        let root_clone = tree.root.clone();
-        tree.split(root_clone, half_space_x, |_, _| 2);
-
-        // Get the right child (above)
-        let right_child = tree.root.borrow().children.as_ref().unwrap()[0].clone();
-
-        // Split the right child on Y axis
-        let half_space_y = HalfSpace {
-            axis: Axis2D::Y,
-            offset: 5,
-            positive: true,
-        };
-
-        let split_result = tree.split(right_child.clone(), half_space_y, |_, _| 3);
-        assert!(split_result);
-
-        // Check that the right child now has children
-        let right_node = right_child.borrow();
-        assert!(right_node.children.is_some());
+        // First split on X axis, then split the right child (above) on Y axis
+        tree.split(tree.root(), split_at(Axis2D::X, 5), |_, _| 2);
+        let right_child = tree[tree.root()].children.unwrap()[0];
+        let split_result = tree.split(right_child, split_at(Axis2D::Y, 5), |_, _| 3);
+        assert!(split_result.is_some());
 
         // Get the upper and lower parts of the right child
-        let right_children = right_node.children.as_ref().unwrap();
-        let (upper_right, lower_right) = (&right_children[0], &right_children[1]);
+        let [upper_right, lower_right] = tree[right_child].children.unwrap();
 
         // Verify their bounds
         assert_eq!(
-            upper_right.borrow().bounds,
+            tree[upper_right].bounds,
             Rect::with_points(Point::new(5, 5), Point::new(10, 10))
         );
         assert_eq!(
-            lower_right.borrow().bounds,
+            tree[lower_right].bounds,
             Rect::with_points(Point::new(5, 0), Point::new(10, 5))
         );
 
         // Check content values propagated correctly
-        assert_eq!(upper_right.borrow().contents, 3);
-        assert_eq!(lower_right.borrow().contents, 3);
+        assert_eq!(tree[upper_right].contents, 3);
+        assert_eq!(tree[lower_right].contents, 3);
     }
 
     #[test]
@@ -558,29 +667,110 @@ mod tests {
         let mut tree = create_test_tree();
 
         // Split function that uses both parent content and new bounds
-        let half_space = HalfSpace {
-            axis: Axis2D::X,
-            offset: 5,
-            positive: true,
-        };
-
-        let root_clone = tree.root.clone();
-        let split_result =
-            tree.split(root_clone, half_space, |parent_content, bounds| {
+        let split_result = tree.split(
+            tree.root(),
+            split_at(Axis2D::X, 5),
+            |parent_content, bounds| {
                 // Generate content based on parent content and area of new bounds
                 let area = (bounds.max.x - bounds.min.x) * (bounds.max.y - bounds.min.y);
                 parent_content + (area as u32)
-            });
+            },
+        );
 
-        assert!(split_result);
-        let root_node = tree.root.borrow();
-        let children = root_node.children.as_ref().unwrap();
-        let (above, below) = (&children[0], &children[1]);
+        let [above, below] = split_result.unwrap();
 
         // Right side: 1 (parent) + 5*10 (area) = 51
-        assert_eq!(above.borrow().contents, 51);
+        assert_eq!(tree[above].contents, 51);
 
         // Left side: 1 (parent) + 5*10 (area) = 51
-        assert_eq!(below.borrow().contents, 51);
+        assert_eq!(tree[below].contents, 51);
+
+        *tree.contents_mut(above).unwrap() = 7;
+        assert_eq!(tree[above].contents, 7);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let (tree, [a, b, c, d]) = four_leaves();
+        assert_eq!(tree.nodes().count(), 7);
+        assert_eq!(tree.nodes().next().unwrap().0, tree.root());
+        for (id, node) in tree.nodes() {
+            assert!(node.parent.is_none_or(|parent| parent < id));
+        }
+
+        let mut leaves: Vec<NodeId> = tree.leaves().map(|(id, _)| id).collect();
+        leaves.sort();
+        let mut expected = vec![a, b, c, d];
+        expected.sort();
+        assert_eq!(leaves, expected);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let (tree, [a, b, c, d]) = four_leaves();
+        let sorted_neighbors = |id| {
+            let mut neighbors: Vec<NodeId> = tree.neighbors(id).collect();
+            neighbors.sort();
+            neighbors
+        };
+        let mut expected = vec![b, c, d];
+        expected.sort();
+        assert_eq!(sorted_neighbors(a), expected);
+        let mut expected = vec![a, d];
+        expected.sort();
+        assert_eq!(sorted_neighbors(b), expected);
+        assert_eq!(sorted_neighbors(c), expected);
+
+        // edges between neighbors mirror each other
+        for (id, node) in tree.leaves() {
+            for edge in &node.edges {
+                assert!(tree.neighbors(edge.neighbor).any(|n| n == id));
+            }
+        }
+    }
+
+    #[test]
+    fn test_leaf_at() {
+        let (tree, [a, b, c, d]) = four_leaves();
+        assert_eq!(tree.leaf_at(Point::new(0, 0)), Some(a));
+        assert_eq!(tree.leaf_at(Point::new(4, 8)), Some(b));
+        assert_eq!(tree.leaf_at(Point::new(5, 5)), Some(c));
+        assert_eq!(tree.leaf_at(Point::new(9, 9)), Some(d));
+        assert_eq!(tree.leaf_at(Point::new(10, 0)), None);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<S: Send + Sync>(_: &S) {}
+        let (tree, _) = four_leaves();
+        assert_send_sync(&tree);
+    }
+
+    #[test]
+    fn test_try_from_nodes() {
+        let (tree, [a, ..]) = four_leaves();
+        let nodes: Vec<Node<u32>> = tree.nodes().map(|(_, node)| node.clone()).collect();
+        let rebuilt = Tree::try_from(nodes.clone()).unwrap();
+        assert_eq!(rebuilt.leaf_at(Point::new(0, 0)), Some(a));
+
+        assert_eq!(Tree::<u32>::try_from(vec![]).unwrap_err(), TreeError::Empty);
+
+        let mut orphaned = nodes.clone();
+        orphaned[a.index()].parent = None;
+        assert!(matches!(
+            Tree::try_from(orphaned),
+            Err(TreeError::InvalidLinks(_))
+        ));
+
+        let mut cyclic = nodes.clone();
+        cyclic[a.index()].children = Some([NodeId(0), NodeId(1)]);
+        assert!(Tree::try_from(cyclic).is_err());
+
+        let mut dangling = nodes;
+        dangling[a.index()].edges[0].neighbor = NodeId(100);
+        assert_eq!(
+            Tree::try_from(dangling).unwrap_err(),
+            TreeError::InvalidNeighbor(a.index())
+        );
     }
 }
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use crate::{Grid2D, Point, Rect};
+
+/// An unbounded 2D grid that allocates fixed-size square chunks on demand.
+///
+/// Each chunk is a `Grid2D` keyed by its chunk coordinate, which is the cell coordinate
+/// divided by the chunk size and rounded towards negative infinity, so negative
+/// coordinates are supported. Cells in chunks that have not been loaded have no value;
+/// writing to such a cell loads its chunk, filling it with the grid's default value.
+#[derive(Clone)]
+pub struct ChunkedGrid<T> {
+    chunk_size: i32,
+    default: T,
+    chunks: HashMap<Point, Grid2D<T>>,
+}
+
+impl<T> ChunkedGrid<T> {
+    /// Returns the width and height of each chunk.
+    pub fn chunk_size(&self) -> i32 {
+        self.chunk_size
+    }
+
+    /// Returns the value newly loaded chunks are filled with.
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Returns the coordinate of the chunk containing the given cell.
+    pub fn chunk_coord(&self, pt: Point) -> Point {
+        pt.map(|c| c.div_euclid(self.chunk_size))
+    }
+
+    /// Returns the cells covered by the chunk at the given chunk coordinate.
+    pub fn chunk_bounds(&self, chunk: Point) -> Rect {
+        let size = Point::new(self.chunk_size, self.chunk_size);
+        Rect::with_size(chunk * self.chunk_size, size)
+    }
+
+    /// Returns `true` if the chunk at the given chunk coordinate is loaded.
+    pub fn is_loaded(&self, chunk: Point) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    /// Returns the number of loaded chunks.
+    pub fn loaded_chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns the chunk at the given chunk coordinate, if it is loaded.
+    ///
+    /// The chunk's own coordinates are relative to its top-left cell, which is at
+    /// `chunk_bounds(chunk).min` in the chunked grid.
+    pub fn chunk(&self, chunk: Point) -> Option<&Grid2D<T>> {
+        self.chunks.get(&chunk)
+    }
+
+    /// Returns a mutable reference to the chunk at the given chunk coordinate, if it is
+    /// loaded.
+    pub fn chunk_mut(&mut self, chunk: Point) -> Option<&mut Grid2D<T>> {
+        self.chunks.get_mut(&chunk)
+    }
+
+    /// Returns an iterator over the loaded chunks and their chunk coordinates.
+    ///
+    /// The order of iteration is unspecified.
+    pub fn chunks(&self) -> impl Iterator<Item = (Point, &Grid2D<T>)> {
+        self.chunks.iter().map(|(coord, chunk)| (*coord, chunk))
+    }
+
+    /// Inserts a chunk at the given chunk coordinate, returning the chunk it replaced.
+    ///
+    /// This is useful for restoring chunks that were previously unloaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk's size does not match the grid's chunk size.
+    pub fn insert_chunk(&mut self, chunk: Point, grid: Grid2D<T>) -> Option<Grid2D<T>> {
+        assert_eq!(
+            grid.size(),
+            Point::new(self.chunk_size, self.chunk_size),
+            "chunk has the wrong size"
+        );
+        self.chunks.insert(chunk, grid)
+    }
+
+    /// Unloads the chunk at the given chunk coordinate, returning its contents if it was
+    /// loaded.
+    pub fn unload_chunk(&mut self, chunk: Point) -> Option<Grid2D<T>> {
+        self.chunks.remove(&chunk)
+    }
+
+    /// Unloads every chunk for which `keep` returns `false`.
+    pub fn retain_chunks<F: FnMut(Point, &Grid2D<T>) -> bool>(&mut self, mut keep: F) {
+        self.chunks.retain(|coord, chunk| keep(*coord, chunk));
+    }
+
+    /// Returns the smallest rect containing every loaded chunk, or `None` if no chunks
+    /// are loaded.
+    pub fn loaded_bounds(&self) -> Option<Rect> {
+        let mut coords = self.chunks.keys();
+        let first = *coords.next()?;
+        let (min, max) = coords.fold((first, first), |(min, max), c| {
+            (
+                Point::new(min.x.min(c.x), min.y.min(c.y)),
+                Point::new(max.x.max(c.x), max.y.max(c.y)),
+            )
+        });
+        Some(Rect::with_points(
+            self.chunk_bounds(min).min,
+            self.chunk_bounds(max).max,
+        ))
+    }
+
+    /// Returns a reference to the value of the given cell, or `None` if its chunk is not
+    /// loaded.
+    pub fn get(&self, pt: Point) -> Option<&T> {
+        let chunk = self.chunks.get(&self.chunk_coord(pt))?;
+        chunk.get(self.local_coord(pt))
+    }
+
+    /// Returns a mutable reference to the value of the given cell, or `None` if its chunk
+    /// is not loaded.
+    pub fn get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        let local = self.local_coord(pt);
+        let chunk = self.chunks.get_mut(&self.chunk_coord(pt))?;
+        chunk.get_mut(local)
+    }
+
+    /// Returns the value of the given cell, or the default value if its chunk is not
+    /// loaded.
+    pub fn get_or_default(&self, pt: Point) -> &T {
+        self.get(pt).unwrap_or(&self.default)
+    }
+
+    /// Returns an iterator over the coordinates and values of every cell in the loaded
+    /// chunks.
+    ///
+    /// Cells within a chunk are visited in row-major order, but the order of the chunks is
+    /// unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.chunks.iter().flat_map(|(coord, chunk)| {
+            let origin = *coord * self.chunk_size;
+            chunk
+                .iter_coords()
+                .zip(chunk.data.iter())
+                .map(move |(pt, value)| (origin + pt, value))
+        })
+    }
+
+    /// Returns the position of the given cell relative to its chunk.
+    fn local_coord(&self, pt: Point) -> Point {
+        pt.map(|c| c.rem_euclid(self.chunk_size))
+    }
+}
+
+impl<T: Clone> ChunkedGrid<T> {
+    /// Creates an empty grid with the given chunk size.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - The width and height of each chunk.
+    /// * `default` - The value newly loaded chunks are filled with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is not positive.
+    pub fn new(chunk_size: i32, default: T) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        ChunkedGrid {
+            chunk_size,
+            default,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Returns the chunk at the given chunk coordinate, loading it if necessary.
+    pub fn load_chunk(&mut self, chunk: Point) -> &mut Grid2D<T> {
+        let size = self.chunk_size;
+        let default = &self.default;
+        self.chunks.entry(chunk).or_insert_with(|| {
+            let cells = std::iter::repeat_n(default.clone(), (size * size) as usize);
+            Grid2D::from_iter(cells, size, size)
+        })
+    }
+
+    /// Returns a mutable reference to the value of the given cell, loading its chunk if
+    /// necessary.
+    pub fn get_or_load(&mut self, pt: Point) -> &mut T {
+        let local = self.local_coord(pt);
+        self.load_chunk(self.chunk_coord(pt))
+            .get_mut(local)
+            .unwrap()
+    }
+
+    /// Sets the value of the given cell, loading its chunk if necessary.
+    pub fn set(&mut self, pt: Point, value: T) {
+        *self.get_or_load(pt) = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_chunk_coords() {
+        let grid = ChunkedGrid::new(4, 0);
+        assert_eq!(grid.chunk_coord(Point::new(0, 0)), Point::new(0, 0));
+        assert_eq!(grid.chunk_coord(Point::new(3, 4)), Point::new(0, 1));
+        assert_eq!(grid.chunk_coord(Point::new(-1, -4)), Point::new(-1, -1));
+        assert_eq!(grid.chunk_coord(Point::new(-5, 0)), Point::new(-2, 0));
+        assert_eq!(
+            grid.chunk_bounds(Point::new(-1, 2)),
+            Rect::with_points(Point::new(-4, 8), Point::new(0, 12))
+        );
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut grid = ChunkedGrid::new(4, '.');
+        assert_eq!(grid.get(Point::new(-1, -1)), None);
+        assert_eq!(grid.get_or_default(Point::new(-1, -1)), &'.');
+
+        grid.set(Point::new(-1, -1), '#');
+        assert_eq!(grid.loaded_chunk_count(), 1);
+        assert!(grid.is_loaded(Point::new(-1, -1)));
+        assert_eq!(grid.get(Point::new(-1, -1)), Some(&'#'));
+        // the rest of the chunk is filled with the default
+        assert_eq!(grid.get(Point::new(-4, -4)), Some(&'.'));
+        assert_eq!(grid.get(Point::new(0, 0)), None);
+
+        *grid.get_mut(Point::new(-2, -1)).unwrap() = '+';
+        assert_eq!(grid.chunk(Point::new(-1, -1)).unwrap().data[3 * 4 + 2], '+');
+        assert_eq!(grid.get_mut(Point::new(5, 5)), None);
+    }
+
+    #[test]
+    fn test_unload_and_restore() {
+        let mut grid = ChunkedGrid::new(8, 0);
+        grid.set(Point::new(10, 3), 7);
+        grid.set(Point::new(-3, 3), 1);
+
+        let chunk = grid.unload_chunk(Point::new(1, 0)).unwrap();
+        assert_eq!(grid.get(Point::new(10, 3)), None);
+        assert_eq!(grid.loaded_chunk_count(), 1);
+        assert!(grid.unload_chunk(Point::new(1, 0)).is_none());
+
+        grid.insert_chunk(Point::new(1, 0), chunk);
+        assert_eq!(grid.get(Point::new(10, 3)), Some(&7));
+
+        grid.retain_chunks(|coord, _| coord.x >= 0);
+        assert_eq!(grid.get(Point::new(-3, 3)), None);
+        assert_eq!(grid.loaded_chunk_count(), 1);
+    }
+
+    #[test]
+    fn test_loaded_bounds() {
+        let mut grid = ChunkedGrid::new(4, 0);
+        assert_eq!(grid.loaded_bounds(), None);
+        grid.load_chunk(Point::new(-1, 0));
+        grid.load_chunk(Point::new(2, -3));
+        assert_eq!(
+            grid.loaded_bounds(),
+            Some(Rect::with_points(Point::new(-4, -12), Point::new(12, 4)))
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut grid = ChunkedGrid::new(2, false);
+        grid.set(Point::new(-1, 0), true);
+        grid.set(Point::new(5, 5), true);
+
+        let cells: Vec<_> = grid.iter().collect();
+        assert_eq!(cells.len(), 8);
+        let set: HashSet<Point> = cells
+            .into_iter()
+            .filter(|(_, v)| **v)
+            .map(|(pt, _)| pt)
+            .collect();
+        assert_eq!(set, HashSet::from([Point::new(-1, 0), Point::new(5, 5)]));
+    }
+}
@@ -2,7 +2,7 @@ use scoundrel_util::numeric::HasSqrt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{MooreNeighbor, Point, Rect, Vector2};
+use crate::{EdgePolicy, Interpolation, MooreNeighbor, Point, Rect, Vector2};
 
 /// A 2D grid data structure.
 ///
@@ -245,6 +245,42 @@ impl<T> Grid2D<T> {
             ci: self.iter_coords(),
         }
     }
+
+    /// Returns an iterator over the rows of the grid as slices, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero chunk size, which an empty grid would otherwise give
+        self.data.chunks(self._width.max(1) as usize)
+    }
+
+    /// Returns an iterator over the rows of the grid as mutable slices, from top to bottom.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.data.chunks_mut(self._width.max(1) as usize)
+    }
+
+    /// Returns the row at the given `y` coordinate as a slice, if it is within the grid.
+    pub fn row(&self, y: i32) -> Option<&[T]> {
+        let start = self.index(Point::new(0, y))?;
+        Some(&self.data[start..start + self._width as usize])
+    }
+
+    /// Returns the row at the given `y` coordinate as a mutable slice, if it is within the
+    /// grid.
+    pub fn row_mut(&mut self, y: i32) -> Option<&mut [T]> {
+        let start = self.index(Point::new(0, y))?;
+        Some(&mut self.data[start..start + self._width as usize])
+    }
+
+    /// Returns an iterator over the column at the given `x` coordinate, from top to bottom.
+    ///
+    /// The iterator is empty if `x` is outside the grid.
+    pub fn column(&self, x: i32) -> impl Iterator<Item = &T> {
+        let height = if x >= 0 && x < self._width {
+            self._height
+        } else {
+            0
+        };
+        (0..height).filter_map(move |y| self.get(Point::new(x, y)))
+    }
 }
 
 impl<
@@ -255,27 +291,13 @@ impl<
     /// Performs bilinear sampling on the 2D grid using the given floating point
     /// coordinates.
     ///
-    /// Returns the interpolated value at the given coordinates using bilinear
-    /// interpolation. If the given coordinates are outside the bounds of the grid,
-    /// this function returns None.
-    pub fn interpolate(&self, mut pt: Vector2<f32>) -> Option<Tp> {
-        pt.x = pt.x.clamp(0.0, self._width as f32 - 1.0);
-        pt.y = pt.y.clamp(0.0, self._height as f32 - 1.0);
-
-        let pt0 = pt.map(|c| c.floor() as i32);
-        let frac = pt - pt0.map(|c| c as f32);
-        let x0y0 = *self.get(pt0)?;
-        let val_at = |pp| *self.get(pp).unwrap_or(&x0y0);
-        let x1y0 = val_at(pt0 + Point::new(1, 0));
-        let x1y1 = val_at(pt0 + Point::new(1, 1));
-        let x0y1 = val_at(pt0 + Point::new(0, 1));
-
-        let y0 = x0y0 * (1.0 - frac.x) + x1y0 * frac.x;
-        let y1 = x0y1 * (1.0 - frac.x) + x1y1 * frac.x;
-        Some(y0 * (1.0 - frac.y) + y1 * frac.y)
+    /// Coordinates outside the grid are clamped to its edge, so this only returns `None`
+    /// for an empty grid. Use `sample` to choose a different interpolation mode or edge
+    /// policy.
+    pub fn interpolate(&self, pt: Vector2<f32>) -> Option<Tp> {
+        self.sample(pt, Interpolation::Bilinear, EdgePolicy::Clamp)
     }
 }
-
 impl<
     T: Copy + HasSqrt + std::ops::Sub<Output = T> + std::ops::Div<Output = Tp> + From<i8>,
     Tp: Copy + From<i8>,
@@ -286,6 +308,11 @@ impl<
     ///
     /// This method returns the gradient vector at the given `position`, which
     /// represents the rate of change of the stored value with respect to the X and Y axes.
+    /// At the edge of the grid, a one-sided difference is used instead. Use
+    /// `gradient_with` for other difference schemes and edge policies.
+    ///
+    /// Both components follow the grid's axes, so the Y component is positive when values
+    /// increase toward larger Y (`MooreNeighbor::Down`).
     pub fn gradient(&self, at: Point) -> Option<Vector2<Tp>> {
         let center_value = match self.get(at) {
             Some(&value) => value,
@@ -308,7 +335,7 @@ impl<
             }
         };
         let dv_dx = grad(MooreNeighbor::Right);
-        let dv_dy = grad(MooreNeighbor::Up);
+        let dv_dy = grad(MooreNeighbor::Down);
         Some(Vector2::new(dv_dx, dv_dy))
     }
 }
@@ -371,7 +398,7 @@ impl<'a, T: Copy> Iterator for GridNeighborhoodIterator<'a, T> {
         if let Some(pt) = self.ci.next() {
             let v0 = *self.grid.get(pt).unwrap();
             let mut neighbors = [None; 8];
-            for n in MooreNeighbor::all() {
+            for n in MooreNeighbor::ALL {
                 neighbors[n.to_index()] = self.grid.get(pt + n.offset()).copied();
             }
             Some((v0, neighbors))
@@ -432,7 +459,7 @@ mod tests {
 
     #[test]
     fn test_from_iter() {
-        let data = vec![1, 2, 3, 4, 5, 6];
+        let data = [1, 2, 3, 4, 5, 6];
         let grid = Grid2D::from_iter(data.iter().cloned(), 3, 2);
         assert_eq!(grid.width(), 3);
         assert_eq!(grid.height(), 2);
@@ -460,4 +487,20 @@ mod tests {
         assert_eq!(grid.index(Point::new(1, 1)), Some(4));
         assert_eq!(grid.index(Point::new(-1, 0)), None);
     }
+
+    #[test]
+    fn test_gradient_sign() {
+        // values increase by 1 per column and by 2 per row
+        let grid =
+            Grid2D::new(3, 3, 0.0f32).map_coords(|pt| pt.x as f32 + 2.0 * pt.y as f32);
+        assert_eq!(
+            grid.gradient(Point::new(1, 1)),
+            Some(Vector2::new(1.0, 2.0))
+        );
+        assert_eq!(
+            grid.gradient(Point::new(0, 0)),
+            Some(Vector2::new(1.0, 2.0))
+        );
+        assert_eq!(grid.gradient(Point::new(3, 0)), None);
+    }
 }
@@ -0,0 +1,402 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::{Grid2D, Point, Vector2};
+
+/// How coordinates outside a grid are mapped back onto it when sampling.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EdgePolicy {
+    /// Use the nearest cell on the edge of the grid.
+    Clamp,
+    /// Wrap around to the opposite edge, tiling the grid.
+    Wrap,
+    /// Reflect back into the grid about the edge cell, without repeating it.
+    Mirror,
+    /// Treat points outside the grid as having no value, so sampling returns `None`.
+    Reject,
+}
+
+impl EdgePolicy {
+    fn resolve_axis(self, coord: i32, len: i32) -> Option<i32> {
+        if len <= 0 {
+            return None;
+        }
+        match self {
+            EdgePolicy::Clamp => Some(coord.clamp(0, len - 1)),
+            EdgePolicy::Wrap => Some(coord.rem_euclid(len)),
+            EdgePolicy::Mirror if len == 1 => Some(0),
+            EdgePolicy::Mirror => {
+                let period = 2 * (len - 1);
+                let folded = coord.rem_euclid(period);
+                Some(if folded < len {
+                    folded
+                } else {
+                    period - folded
+                })
+            }
+            EdgePolicy::Reject => (0..len).contains(&coord).then_some(coord),
+        }
+    }
+
+    /// Moves a sampling coordinate on an axis of length `len` into a range near the grid
+    /// where every tap around it still resolves to the same cells, so that distant or
+    /// non-finite coordinates cannot overflow the integer tap offsets.
+    fn fold_sample_axis(self, coord: f32, len: i32) -> f32 {
+        match self {
+            EdgePolicy::Clamp | EdgePolicy::Reject => coord.clamp(-2.0, len as f32 + 1.0),
+            EdgePolicy::Wrap => coord.rem_euclid(len as f32),
+            EdgePolicy::Mirror => coord.rem_euclid((2 * (len - 1)).max(1) as f32),
+        }
+    }
+
+    /// Maps a point onto a grid of the given size, or returns `None` if the policy
+    /// rejects it.
+    pub fn resolve(self, pt: Point, size: Point) -> Option<Point> {
+        Some(Point::new(
+            self.resolve_axis(pt.x, size.x)?,
+            self.resolve_axis(pt.y, size.y)?,
+        ))
+    }
+}
+
+/// How values between grid cells are reconstructed when sampling.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Interpolation {
+    /// Use the value of the closest cell.
+    Nearest,
+    /// Blend the four surrounding cells linearly.
+    Bilinear,
+    /// Blend the sixteen surrounding cells with a Catmull-Rom spline, which passes
+    /// through the cell values and has a continuous first derivative.
+    Bicubic,
+}
+
+/// How the gradient of a grid is approximated from neighboring cells.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GradientMode {
+    /// The difference between the next and previous cells, halved.
+    Central,
+    /// The difference between the next cell and the current one.
+    Forward,
+    /// The difference between the current cell and the previous one.
+    Backward,
+    /// The Sobel operator, a central difference smoothed across the perpendicular axis.
+    Sobel,
+}
+
+/// Returns the Catmull-Rom weights of the four taps around a sample at fraction `t`.
+fn cubic_weights(t: f32) -> [f32; 4] {
+    let (t2, t3) = (t * t, t * t * t);
+    [
+        (-t3 + 2.0 * t2 - t) * 0.5,
+        (3.0 * t3 - 5.0 * t2 + 2.0) * 0.5,
+        (-3.0 * t3 + 4.0 * t2 + t) * 0.5,
+        (t3 - t2) * 0.5,
+    ]
+}
+
+impl<T> Grid2D<T> {
+    /// Returns a reference to the element at the given point after mapping it onto the
+    /// grid with `edge`.
+    pub fn get_with_edge(&self, pt: Point, edge: EdgePolicy) -> Option<&T> {
+        self.get(edge.resolve(pt, self.size())?)
+    }
+}
+
+impl<T: Copy + Mul<f32, Output = Tp>, Tp: Mul<f32, Output = Tp> + Add<Output = Tp>>
+    Grid2D<T>
+{
+    /// Samples the grid at the given floating point coordinates.
+    ///
+    /// Cell centers lie at integer coordinates. With `EdgePolicy::Reject`, points outside
+    /// the grid return `None`, and cells just beyond the edge needed to sample near it
+    /// are taken from the edge itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `pt` - The coordinates to sample at.
+    /// * `mode` - How to blend the cells around `pt`.
+    /// * `edge` - How to treat coordinates outside the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{EdgePolicy, Grid2D, Interpolation, Vector2};
+    /// let grid = Grid2D::from_iter([0.0, 10.0, 20.0, 30.0].into_iter(), 4, 1);
+    /// let pt = Vector2::new(1.25, 0.0);
+    /// assert_eq!(grid.sample(pt, Interpolation::Nearest, EdgePolicy::Clamp), Some(10.0));
+    /// assert_eq!(grid.sample(pt, Interpolation::Bilinear, EdgePolicy::Clamp), Some(12.5));
+    /// let outside = Vector2::new(-1.0, 0.0);
+    /// assert_eq!(grid.sample(outside, Interpolation::Bilinear, EdgePolicy::Reject), None);
+    /// ```
+    pub fn sample(
+        &self,
+        pt: Vector2<f32>,
+        mode: Interpolation,
+        edge: EdgePolicy,
+    ) -> Option<Tp> {
+        let tap_edge = match edge {
+            EdgePolicy::Reject => {
+                let max = (self.size() - Point::new(1, 1)).map(|c| c as f32);
+                if !(0.0..=max.x).contains(&pt.x) || !(0.0..=max.y).contains(&pt.y) {
+                    return None;
+                }
+                EdgePolicy::Clamp
+            }
+            edge => edge,
+        };
+        let tap = |p: Point| self.get_with_edge(p, tap_edge).copied();
+        let size = self.size();
+        let pt = Vector2::new(
+            tap_edge.fold_sample_axis(pt.x, size.x),
+            tap_edge.fold_sample_axis(pt.y, size.y),
+        );
+
+        let base = pt.map(|c| c.floor() as i32);
+        let frac = pt - base.map(|c| c as f32);
+        match mode {
+            Interpolation::Nearest => {
+                Some(tap(pt.map(|c| (c + 0.5).floor() as i32))? * 1.0)
+            }
+            Interpolation::Bilinear => {
+                let y0 =
+                    tap(base)? * (1.0 - frac.x) + tap(base + Point::new(1, 0))? * frac.x;
+                let y1 = tap(base + Point::new(0, 1))? * (1.0 - frac.x)
+                    + tap(base + Point::new(1, 1))? * frac.x;
+                Some(y0 * (1.0 - frac.y) + y1 * frac.y)
+            }
+            Interpolation::Bicubic => {
+                let (wx, wy) = (cubic_weights(frac.x), cubic_weights(frac.y));
+                let row = |dy: i32| -> Option<Tp> {
+                    let at = |dx: i32| tap(base + Point::new(dx, dy));
+                    Some(
+                        at(-1)? * wx[0]
+                            + at(0)? * wx[1]
+                            + at(1)? * wx[2]
+                            + at(2)? * wx[3],
+                    )
+                };
+                Some(
+                    row(-1)? * wy[0]
+                        + row(0)? * wy[1]
+                        + row(1)? * wy[2]
+                        + row(2)? * wy[3],
+                )
+            }
+        }
+    }
+}
+
+impl<T, Tp> Grid2D<T>
+where
+    T: Copy + Mul<f32, Output = Tp>,
+    Tp: Copy + Mul<f32, Output = Tp> + Add<Output = Tp> + Sub<Output = Tp>,
+{
+    /// Approximates the gradient of the grid at the given cell.
+    ///
+    /// The gradient points towards increasing values, with `y` increasing downwards as
+    /// it does in the grid's coordinates. Returns `None` if `at` is outside the grid, or
+    /// if `edge` is `EdgePolicy::Reject` and a cell needed by `mode` is outside the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{EdgePolicy, GradientMode, Grid2D, Point, Vector2};
+    /// let ramp = Grid2D::new(4, 4, 0.0).map_coords(|pt| pt.x as f32 * 2.0);
+    /// let at = Point::new(1, 1);
+    /// let expected = Some(Vector2::new(2.0, 0.0));
+    /// assert_eq!(ramp.gradient_with(at, GradientMode::Sobel, EdgePolicy::Reject), expected);
+    /// assert_eq!(ramp.gradient_with(at, GradientMode::Forward, EdgePolicy::Clamp), expected);
+    /// ```
+    pub fn gradient_with(
+        &self,
+        at: Point,
+        mode: GradientMode,
+        edge: EdgePolicy,
+    ) -> Option<Vector2<Tp>> {
+        self.get(at)?;
+        let v = |dx: i32, dy: i32| -> Option<Tp> {
+            Some(*self.get_with_edge(at + Point::new(dx, dy), edge)? * 1.0)
+        };
+        let (dx, dy) = match mode {
+            GradientMode::Central => {
+                ((v(1, 0)? - v(-1, 0)?) * 0.5, (v(0, 1)? - v(0, -1)?) * 0.5)
+            }
+            GradientMode::Forward => (v(1, 0)? - v(0, 0)?, v(0, 1)? - v(0, 0)?),
+            GradientMode::Backward => (v(0, 0)? - v(-1, 0)?, v(0, 0)? - v(0, -1)?),
+            GradientMode::Sobel => {
+                let right = v(1, -1)? + v(1, 0)? * 2.0 + v(1, 1)?;
+                let left = v(-1, -1)? + v(-1, 0)? * 2.0 + v(-1, 1)?;
+                let down = v(-1, 1)? + v(0, 1)? * 2.0 + v(1, 1)?;
+                let up = v(-1, -1)? + v(0, -1)? * 2.0 + v(1, -1)?;
+                ((right - left) * 0.125, (down - up) * 0.125)
+            }
+        };
+        Some(Vector2::new(dx, dy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Option<f32>, b: f32) {
+        let a = a.unwrap();
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_edge_policies() {
+        let size = Point::new(4, 3);
+        let resolve = |policy: EdgePolicy, x: i32| policy.resolve(Point::new(x, 1), size);
+        assert_eq!(resolve(EdgePolicy::Clamp, -2), Some(Point::new(0, 1)));
+        assert_eq!(resolve(EdgePolicy::Clamp, 9), Some(Point::new(3, 1)));
+        assert_eq!(resolve(EdgePolicy::Wrap, -1), Some(Point::new(3, 1)));
+        assert_eq!(resolve(EdgePolicy::Wrap, 5), Some(Point::new(1, 1)));
+        assert_eq!(resolve(EdgePolicy::Mirror, -1), Some(Point::new(1, 1)));
+        assert_eq!(resolve(EdgePolicy::Mirror, 4), Some(Point::new(2, 1)));
+        assert_eq!(resolve(EdgePolicy::Mirror, 6), Some(Point::new(0, 1)));
+        assert_eq!(resolve(EdgePolicy::Reject, 4), None);
+        assert_eq!(resolve(EdgePolicy::Reject, 3), Some(Point::new(3, 1)));
+        assert_eq!(
+            EdgePolicy::Mirror.resolve(Point::new(-3, 2), Point::new(1, 1)),
+            Some(Point::zero())
+        );
+        assert_eq!(
+            EdgePolicy::Clamp.resolve(Point::zero(), Point::zero()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sample_modes_hit_cell_values() {
+        let grid = Grid2D::from_iter((0..20).map(|v| (v * v) as f32), 5, 4);
+        for mode in [
+            Interpolation::Nearest,
+            Interpolation::Bilinear,
+            Interpolation::Bicubic,
+        ] {
+            for pt in grid.iter_coords() {
+                let sampled = grid.sample(pt.map(|c| c as f32), mode, EdgePolicy::Clamp);
+                assert_close(sampled, *grid.get(pt).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_linear_data() {
+        // both bilinear and bicubic reproduce a linear ramp exactly away from the edges
+        let grid =
+            Grid2D::new(6, 6, 0.0).map_coords(|pt| pt.x as f32 + 2.0 * pt.y as f32);
+        let pt = Vector2::new(2.3, 2.6);
+        assert_close(
+            grid.sample(pt, Interpolation::Bilinear, EdgePolicy::Reject),
+            7.5,
+        );
+        assert_close(
+            grid.sample(pt, Interpolation::Bicubic, EdgePolicy::Reject),
+            7.5,
+        );
+        assert_close(
+            grid.sample(pt, Interpolation::Nearest, EdgePolicy::Reject),
+            8.0,
+        );
+    }
+
+    #[test]
+    fn test_sample_edges() {
+        let grid = Grid2D::from_iter([0.0, 1.0, 2.0, 3.0].into_iter(), 4, 1);
+        let at = |x: f32, edge| {
+            grid.sample(Vector2::new(x, 0.0), Interpolation::Bilinear, edge)
+        };
+        assert_close(at(-2.0, EdgePolicy::Clamp), 0.0);
+        assert_close(at(3.5, EdgePolicy::Wrap), 1.5);
+        assert_close(at(-0.5, EdgePolicy::Mirror), 0.5);
+        assert_eq!(at(3.5, EdgePolicy::Reject), None);
+        assert_close(at(3.0, EdgePolicy::Reject), 3.0);
+        // the legacy entry point clamps
+        assert_close(grid.interpolate(Vector2::new(10.0, 10.0)), 3.0);
+    }
+
+    #[test]
+    fn test_sample_distant_coordinates() {
+        let grid = Grid2D::from_iter((0..12).map(|v| v as f32), 4, 3);
+        let coords = [
+            3.0e9,
+            -3.0e9,
+            f32::MAX,
+            f32::MIN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::NAN,
+        ];
+        for mode in [
+            Interpolation::Nearest,
+            Interpolation::Bilinear,
+            Interpolation::Bicubic,
+        ] {
+            for edge in [
+                EdgePolicy::Clamp,
+                EdgePolicy::Wrap,
+                EdgePolicy::Mirror,
+                EdgePolicy::Reject,
+            ] {
+                for c in coords {
+                    for pt in [Vector2::new(c, 1.0), Vector2::new(1.0, c)] {
+                        let sampled = grid.sample(pt, mode, edge);
+                        match edge {
+                            EdgePolicy::Reject => assert_eq!(sampled, None),
+                            _ => assert!(sampled.is_some()),
+                        }
+                    }
+                }
+                // clamping far away lands on the edge cells
+                if edge == EdgePolicy::Clamp {
+                    let at = |x: f32| grid.sample(Vector2::new(x, 1.0), mode, edge);
+                    assert_close(at(3.0e9), 7.0);
+                    assert_close(at(f32::NEG_INFINITY), 4.0);
+                }
+            }
+        }
+        assert_close(grid.interpolate(Vector2::new(3.0e9, 0.0)), 3.0);
+        assert_close(grid.interpolate(Vector2::new(f32::INFINITY, 5.0)), 11.0);
+    }
+
+    #[test]
+    fn test_gradient_modes() {
+        let grid =
+            Grid2D::new(5, 5, 0.0).map_coords(|pt| (pt.x * pt.x + 3 * pt.y) as f32);
+        let at = Point::new(2, 2);
+        let grad = |mode| grid.gradient_with(at, mode, EdgePolicy::Reject).unwrap();
+        assert_eq!(grad(GradientMode::Central), Vector2::new(4.0, 3.0));
+        assert_eq!(grad(GradientMode::Forward), Vector2::new(5.0, 3.0));
+        assert_eq!(grad(GradientMode::Backward), Vector2::new(3.0, 3.0));
+        assert_eq!(grad(GradientMode::Sobel), Vector2::new(4.0, 3.0));
+    }
+
+    #[test]
+    fn test_gradient_edges() {
+        let grid = Grid2D::new(3, 3, 0.0).map_coords(|pt| pt.y as f32);
+        let corner = Point::new(0, 0);
+        assert_eq!(
+            grid.gradient_with(corner, GradientMode::Central, EdgePolicy::Reject),
+            None
+        );
+        assert_eq!(
+            grid.gradient_with(corner, GradientMode::Forward, EdgePolicy::Reject),
+            Some(Vector2::new(0.0, 1.0))
+        );
+        assert_eq!(
+            grid.gradient_with(corner, GradientMode::Central, EdgePolicy::Clamp),
+            Some(Vector2::new(0.0, 0.5))
+        );
+        assert_eq!(
+            grid.gradient_with(Point::new(5, 5), GradientMode::Central, EdgePolicy::Wrap),
+            None
+        );
+        // the central-difference shorthand agrees on direction
+        assert_eq!(
+            grid.gradient(Point::new(1, 1)),
+            Some(Vector2::new(0.0, 1.0))
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{Grid2D, Mat2, Point, Rect, Vector2};
+
+impl<T> Grid2D<T> {
+    /// Returns the position that `pt` moves to when this grid is transformed by `transform`.
+    ///
+    /// This maps points embedded in the grid, such as spawn points or door positions of a
+    /// prefab room, consistently with `transformed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transform` is not one of `Mat2::orientations`.
+    pub fn transform_point(&self, transform: Mat2<i32>, pt: Point) -> Point {
+        assert!(
+            transform.is_orientation(),
+            "transform must be an axis-aligned rotation or reflection"
+        );
+        transform * pt - self.transformed_origin(transform)
+    }
+
+    /// Returns the size of this grid after being transformed by `transform`.
+    pub fn transformed_size(&self, transform: Mat2<i32>) -> Point {
+        (transform * self.size()).map(i32::abs)
+    }
+
+    /// Returns the untranslated image of the grid's top-left corner after `transform`.
+    fn transformed_origin(&self, transform: Mat2<i32>) -> Point {
+        let far = self.size() - Point::new(1, 1);
+        let corners = [
+            transform * Point::zero(),
+            transform * Point::new(far.x, 0),
+            transform * Point::new(0, far.y),
+            transform * far,
+        ];
+        Vector2::new(
+            corners.iter().map(|c| c.x).min().unwrap(),
+            corners.iter().map(|c| c.y).min().unwrap(),
+        )
+    }
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// Returns a copy of this grid rotated or reflected by `transform`.
+    ///
+    /// The result is translated so that its top-left corner is at the origin. Use
+    /// `transform_point` to find where a given cell ended up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transform` is not one of `Mat2::orientations`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{Grid2D, Mat2, Point};
+    /// let room = Grid2D::from_iter("ab\ncd\nef".lines().flat_map(str::chars), 2, 3);
+    /// for transform in Mat2::orientations() {
+    ///     let placed = room.transformed(transform);
+    ///     let door = room.transform_point(transform, Point::new(1, 2));
+    ///     assert_eq!(placed.get(door), Some(&'f'));
+    /// }
+    /// ```
+    pub fn transformed(&self, transform: Mat2<i32>) -> Grid2D<T> {
+        assert!(
+            transform.is_orientation(),
+            "transform must be an axis-aligned rotation or reflection"
+        );
+        let size = self.transformed_size(transform);
+        let origin = self.transformed_origin(transform);
+        // orientations are orthogonal, so the transpose is the inverse
+        let inverse = transform.transpose();
+        let data = Rect::with_size(Point::zero(), size)
+            .contained_points()
+            .into_iter()
+            .map(|pt| self.get(inverse * (pt + origin)).unwrap().clone());
+        Grid2D::from_iter(data, size.x, size.y)
+    }
+
+    /// Returns a copy of this grid rotated a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Grid2D<T> {
+        self.transformed(Mat2::rotate_cw())
+    }
+
+    /// Returns a copy of this grid rotated a quarter turn counter-clockwise.
+    pub fn rotate_ccw(&self) -> Grid2D<T> {
+        self.transformed(Mat2::rotate_ccw())
+    }
+
+    /// Returns a copy of this grid rotated a half turn.
+    pub fn rotate_180(&self) -> Grid2D<T> {
+        self.transformed(Mat2::rotate_180())
+    }
+
+    /// Returns a copy of this grid mirrored left to right.
+    pub fn flip_horizontal(&self) -> Grid2D<T> {
+        self.transformed(Mat2::flip_x())
+    }
+
+    /// Returns a copy of this grid mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Grid2D<T> {
+        self.transformed(Mat2::flip_y())
+    }
+
+    /// Returns a copy of this grid with its rows and columns swapped.
+    pub fn transpose(&self) -> Grid2D<T> {
+        self.transformed(Mat2::row_major(0, 1, 1, 0))
+    }
+
+    /// Returns a copy of the part of this grid within `rect`.
+    ///
+    /// Parts of `rect` outside the grid are discarded, so the result may be smaller than
+    /// `rect`. The cell at the clipped rect's minimum becomes the origin of the result.
+    pub fn crop(&self, rect: Rect) -> Grid2D<T> {
+        self.sub_grid(rect).to_grid()
+    }
+
+    /// Returns a grid covering `rect` in this grid's coordinates.
+    ///
+    /// Cells of `rect` that lie within this grid are copied, and the rest are set to
+    /// `fill`, so a rect larger than the grid pads it and a smaller one crops it. The cell
+    /// at `rect.min` becomes the origin of the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{Grid2D, Point, Rect};
+    /// let room = Grid2D::new(2, 2, '.');
+    /// let padded = room.expand(Rect::with_points(Point::new(-1, -1), Point::new(3, 3)), '#');
+    /// assert_eq!(padded.size(), Point::new(4, 4));
+    /// assert_eq!(padded.get(Point::new(0, 0)), Some(&'#'));
+    /// assert_eq!(padded.get(Point::new(1, 1)), Some(&'.'));
+    /// ```
+    pub fn expand(&self, rect: Rect, fill: T) -> Grid2D<T> {
+        let data = rect
+            .contained_points()
+            .into_iter()
+            .map(|pt| self.get(pt).unwrap_or(&fill).clone());
+        let size = rect.size();
+        Grid2D::from_iter(data, size.x, size.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&str]) -> Grid2D<char> {
+        let width = rows[0].len() as i32;
+        Grid2D::from_iter(
+            rows.iter().flat_map(|r| r.chars()),
+            width,
+            rows.len() as i32,
+        )
+    }
+
+    fn to_rows(grid: &Grid2D<char>) -> Vec<String> {
+        grid.rows().map(|r| r.iter().collect()).collect()
+    }
+
+    #[test]
+    fn test_rotations() {
+        let grid = from_rows(&["abc", "def"]);
+        assert_eq!(to_rows(&grid.rotate_cw()), vec!["da", "eb", "fc"]);
+        assert_eq!(to_rows(&grid.rotate_ccw()), vec!["cf", "be", "ad"]);
+        assert_eq!(to_rows(&grid.rotate_180()), vec!["fed", "cba"]);
+        assert_eq!(
+            to_rows(&grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw()),
+            to_rows(&grid)
+        );
+    }
+
+    #[test]
+    fn test_flips_and_transpose() {
+        let grid = from_rows(&["abc", "def"]);
+        assert_eq!(to_rows(&grid.flip_horizontal()), vec!["cba", "fed"]);
+        assert_eq!(to_rows(&grid.flip_vertical()), vec!["def", "abc"]);
+        assert_eq!(to_rows(&grid.transpose()), vec!["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn test_transform_point_matches_grid() {
+        let grid = Grid2D::from_iter(0..12, 4, 3);
+        for transform in Mat2::orientations() {
+            let transformed = grid.transformed(transform);
+            assert_eq!(transformed.size(), grid.transformed_size(transform));
+            for pt in grid.iter_coords() {
+                let moved = grid.transform_point(transform, pt);
+                assert_eq!(transformed.get(moved), grid.get(pt));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_transform_rejects_shear() {
+        Grid2D::new(2, 2, 0).transformed(Mat2::row_major(1, 1, 0, 1));
+    }
+
+    #[test]
+    fn test_crop() {
+        let grid = from_rows(&["abcd", "efgh", "ijkl"]);
+        let cropped = grid.crop(Rect::with_points(Point::new(1, 1), Point::new(3, 5)));
+        assert_eq!(to_rows(&cropped), vec!["fg", "jk"]);
+    }
+
+    #[test]
+    fn test_expand() {
+        let grid = from_rows(&["ab", "cd"]);
+        let expanded =
+            grid.expand(Rect::with_points(Point::new(-1, 0), Point::new(3, 3)), '#');
+        assert_eq!(to_rows(&expanded), vec!["#ab#", "#cd#", "####"]);
+
+        // a rect inside the grid crops it
+        let shrunk =
+            grid.expand(Rect::with_points(Point::new(1, 0), Point::new(2, 2)), '#');
+        assert_eq!(to_rows(&shrunk), vec!["b", "d"]);
+    }
+}
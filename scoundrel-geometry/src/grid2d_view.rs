@@ -0,0 +1,464 @@
+use crate::{Grid2D, Point, Rect};
+
+/// Clips `rect` to the bounds of `grid`, returning an empty rect at the clipped corner if
+/// there is no overlap.
+fn clip_to_grid<T>(grid: &Grid2D<T>, rect: Rect) -> Rect {
+    grid.rect().intersection(&rect).unwrap_or_else(|| {
+        let corner = grid.rect().closest_pt(rect.min);
+        Rect::with_points(corner, corner)
+    })
+}
+
+/// A borrowed, read-only view of a rectangular region of a `Grid2D`.
+///
+/// Views address cells using the coordinates of the parent grid, so a point means the
+/// same thing in the view as in the grid it was taken from. This lets algorithms such as
+/// pathfinding and field of view run on a window of a larger map without translating
+/// coordinates. Points outside the view's `rect` are treated as out of bounds.
+pub struct Grid2DView<'a, T> {
+    grid: &'a Grid2D<T>,
+    rect: Rect,
+}
+
+// Implemented by hand as a derive would require `T: Clone`.
+impl<T> Clone for Grid2DView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Grid2DView<'_, T> {}
+
+impl<'a, T> From<&'a Grid2D<T>> for Grid2DView<'a, T> {
+    fn from(grid: &'a Grid2D<T>) -> Self {
+        Grid2DView {
+            grid,
+            rect: grid.rect(),
+        }
+    }
+}
+
+impl<'a, T> Grid2DView<'a, T> {
+    /// Returns the region of the parent grid covered by this view.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the size of the view.
+    pub fn size(&self) -> Point {
+        self.rect.size()
+    }
+
+    /// Returns the width of the view.
+    pub fn width(&self) -> i32 {
+        self.rect.size().x
+    }
+
+    /// Returns the height of the view.
+    pub fn height(&self) -> i32 {
+        self.rect.size().y
+    }
+
+    /// Returns a reference to the element at the given point, if it is within the view.
+    pub fn get(&self, pt: Point) -> Option<&'a T> {
+        if self.rect.contains(pt) {
+            self.grid.get(pt)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a view of the part of `rect` that lies within this view.
+    pub fn sub_grid(&self, rect: Rect) -> Grid2DView<'a, T> {
+        let rect = self.rect.intersection(&rect).unwrap_or_else(|| {
+            let corner = self.rect.closest_pt(rect.min);
+            Rect::with_points(corner, corner)
+        });
+        Grid2DView {
+            grid: self.grid,
+            rect,
+        }
+    }
+
+    /// Returns an iterator over the rows of the view as slices, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + use<'a, T> {
+        let (min, max) = (self.rect.min, self.rect.max);
+        self.grid
+            .rows()
+            .skip(min.y as usize)
+            .take((max.y - min.y) as usize)
+            .map(move |row| &row[min.x as usize..max.x as usize])
+    }
+
+    /// Returns an iterator over the coordinates of all cells in the view, in row-major order.
+    pub fn iter_coords(&self) -> impl Iterator<Item = Point> + use<T> {
+        let rect = self.rect;
+        (rect.min.y..rect.max.y)
+            .flat_map(move |y| (rect.min.x..rect.max.x).map(move |x| Point::new(x, y)))
+    }
+
+    /// Returns an iterator over the values in the view, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + use<'a, T> {
+        self.rows().flatten()
+    }
+}
+
+impl<T: Clone> Grid2DView<'_, T> {
+    /// Copies the contents of the view into a new grid.
+    ///
+    /// The cell at the view's `rect().min` becomes the origin of the new grid.
+    pub fn to_grid(&self) -> Grid2D<T> {
+        Grid2D::from_iter(self.iter().cloned(), self.width(), self.height())
+    }
+}
+
+/// A borrowed, mutable view of a rectangular region of a `Grid2D`.
+///
+/// Like `Grid2DView`, this addresses cells using the coordinates of the parent grid, and
+/// only cells within its `rect` can be read or written.
+pub struct Grid2DViewMut<'a, T> {
+    grid: &'a mut Grid2D<T>,
+    rect: Rect,
+}
+
+impl<'a, T> From<&'a mut Grid2D<T>> for Grid2DViewMut<'a, T> {
+    fn from(grid: &'a mut Grid2D<T>) -> Self {
+        let rect = grid.rect();
+        Grid2DViewMut { grid, rect }
+    }
+}
+
+impl<T> Grid2DViewMut<'_, T> {
+    /// Returns a read-only view of the same region.
+    pub fn as_view(&self) -> Grid2DView<'_, T> {
+        Grid2DView {
+            grid: self.grid,
+            rect: self.rect,
+        }
+    }
+
+    /// Returns the region of the parent grid covered by this view.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the size of the view.
+    pub fn size(&self) -> Point {
+        self.rect.size()
+    }
+
+    /// Returns the width of the view.
+    pub fn width(&self) -> i32 {
+        self.rect.size().x
+    }
+
+    /// Returns the height of the view.
+    pub fn height(&self) -> i32 {
+        self.rect.size().y
+    }
+
+    /// Returns a reference to the element at the given point, if it is within the view.
+    pub fn get(&self, pt: Point) -> Option<&T> {
+        if self.rect.contains(pt) {
+            self.grid.get(pt)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at the given point, if it is within the
+    /// view.
+    pub fn get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        if self.rect.contains(pt) {
+            self.grid.get_mut(pt)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the value at the specified position.
+    ///
+    /// # Returns
+    /// `true` if the position is within the view and the value was set, `false` otherwise.
+    pub fn set(&mut self, pt: Point, value: T) -> bool {
+        match self.get_mut(pt) {
+            Some(val) => {
+                *val = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns a mutable view of the part of `rect` that lies within this view.
+    pub fn sub_grid_mut(&mut self, rect: Rect) -> Grid2DViewMut<'_, T> {
+        let rect = self.rect.intersection(&rect).unwrap_or_else(|| {
+            let corner = self.rect.closest_pt(rect.min);
+            Rect::with_points(corner, corner)
+        });
+        Grid2DViewMut {
+            grid: self.grid,
+            rect,
+        }
+    }
+
+    /// Returns an iterator over the rows of the view as slices, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.as_view().rows()
+    }
+
+    /// Returns an iterator over the rows of the view as mutable slices, from top to bottom.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let (min, max) = (self.rect.min, self.rect.max);
+        self.grid
+            .rows_mut()
+            .skip(min.y as usize)
+            .take((max.y - min.y) as usize)
+            .map(move |row| &mut row[min.x as usize..max.x as usize])
+    }
+
+    /// Returns an iterator over the coordinates of all cells in the view, in row-major order.
+    pub fn iter_coords(&self) -> impl Iterator<Item = Point> + use<T> {
+        self.as_view().iter_coords()
+    }
+}
+
+impl<T: Clone> Grid2DViewMut<'_, T> {
+    /// Sets every cell in the view to the given value.
+    pub fn fill(&mut self, value: T) {
+        for row in self.rows_mut() {
+            row.fill(value.clone());
+        }
+    }
+
+    /// Copies the contents of `src` into this view, placing the source's top-left cell at
+    /// `dest`.
+    ///
+    /// Cells that would land outside this view are skipped.
+    pub fn blit<'b>(&mut self, src: impl Into<Grid2DView<'b, T>>, dest: Point)
+    where
+        T: 'b,
+    {
+        let src = src.into();
+        let offset = dest - src.rect().min;
+        let target = match self.rect.intersection(&(src.rect() + offset)) {
+            Some(target) => target,
+            None => return,
+        };
+        let src = src.sub_grid(target - offset);
+        let mut dest_view = self.sub_grid_mut(target);
+        for (dest_row, src_row) in dest_view.rows_mut().zip(src.rows()) {
+            dest_row.clone_from_slice(src_row);
+        }
+    }
+}
+
+impl<T> Grid2D<T> {
+    /// Returns a read-only view of the part of `rect` that lies within the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{Grid2D, Point, Rect};
+    /// let grid = Grid2D::from_iter(0..16, 4, 4);
+    /// let view = grid.sub_grid(Rect::with_points(Point::new(1, 1), Point::new(3, 3)));
+    /// assert_eq!(view.get(Point::new(1, 1)), Some(&5));
+    /// assert_eq!(view.get(Point::new(0, 0)), None);
+    /// assert_eq!(view.rows().collect::<Vec<_>>(), vec![&[5, 6][..], &[9, 10][..]]);
+    /// ```
+    pub fn sub_grid(&self, rect: Rect) -> Grid2DView<'_, T> {
+        Grid2DView {
+            grid: self,
+            rect: clip_to_grid(self, rect),
+        }
+    }
+
+    /// Returns a mutable view of the part of `rect` that lies within the grid.
+    pub fn sub_grid_mut(&mut self, rect: Rect) -> Grid2DViewMut<'_, T> {
+        let rect = clip_to_grid(self, rect);
+        Grid2DViewMut { grid: self, rect }
+    }
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// Sets every cell of the grid within `rect` to the given value.
+    ///
+    /// Parts of `rect` outside the grid are ignored.
+    pub fn fill_rect(&mut self, rect: Rect, value: T) {
+        self.sub_grid_mut(rect).fill(value);
+    }
+
+    /// Copies the contents of `src` into this grid, placing the source's top-left cell at
+    /// `dest`.
+    ///
+    /// `src` may be another grid or a view of one. Cells that would land outside this
+    /// grid are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{Grid2D, Point};
+    /// let mut map = Grid2D::new(4, 4, '.');
+    /// let prefab = Grid2D::new(2, 2, '#');
+    /// map.blit(&prefab, Point::new(3, 1));
+    /// assert_eq!(map.get(Point::new(3, 1)), Some(&'#'));
+    /// assert_eq!(map.get(Point::new(3, 2)), Some(&'#'));
+    /// assert_eq!(map.get(Point::new(2, 1)), Some(&'.'));
+    /// ```
+    pub fn blit<'b>(&mut self, src: impl Into<Grid2DView<'b, T>>, dest: Point)
+    where
+        T: 'b,
+    {
+        Grid2DViewMut::from(self).blit(src, dest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_grid(width: i32, height: i32) -> Grid2D<i32> {
+        Grid2D::from_iter(0..width * height, width, height)
+    }
+
+    #[test]
+    fn test_sub_grid() {
+        let grid = numbered_grid(5, 4);
+        let view = grid.sub_grid(Rect::with_points(Point::new(1, 1), Point::new(4, 3)));
+        assert_eq!(view.size(), Point::new(3, 2));
+        assert_eq!(view.get(Point::new(1, 1)), Some(&6));
+        assert_eq!(view.get(Point::new(3, 2)), Some(&13));
+        assert_eq!(view.get(Point::new(0, 1)), None);
+        assert_eq!(view.get(Point::new(4, 1)), None);
+        assert_eq!(
+            view.iter().copied().collect::<Vec<_>>(),
+            vec![6, 7, 8, 11, 12, 13]
+        );
+        assert_eq!(
+            view.iter_coords().collect::<Vec<_>>(),
+            vec![
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(3, 1),
+                Point::new(1, 2),
+                Point::new(2, 2),
+                Point::new(3, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sub_grid_clipped() {
+        let grid = numbered_grid(4, 4);
+        let view = grid.sub_grid(Rect::with_points(Point::new(-2, 2), Point::new(2, 10)));
+        assert_eq!(
+            view.rect(),
+            Rect::with_points(Point::new(0, 2), Point::new(2, 4))
+        );
+
+        let empty = grid.sub_grid(Rect::with_points(Point::new(8, 8), Point::new(9, 9)));
+        assert_eq!(empty.size(), Point::zero());
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_nested_sub_grid() {
+        let grid = numbered_grid(6, 6);
+        let outer = grid.sub_grid(Rect::with_points(Point::new(1, 1), Point::new(5, 5)));
+        let inner = outer.sub_grid(Rect::with_points(Point::new(3, 0), Point::new(9, 2)));
+        assert_eq!(
+            inner.rect(),
+            Rect::with_points(Point::new(3, 1), Point::new(5, 2))
+        );
+        assert_eq!(inner.iter().copied().collect::<Vec<_>>(), vec![9, 10]);
+    }
+
+    #[test]
+    fn test_view_to_grid() {
+        let grid = numbered_grid(4, 4);
+        let cropped = grid
+            .sub_grid(Rect::with_points(Point::new(2, 1), Point::new(4, 3)))
+            .to_grid();
+        assert_eq!(cropped.size(), Point::new(2, 2));
+        assert_eq!(cropped.data, vec![6, 7, 10, 11]);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut grid = numbered_grid(3, 2);
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[0, 1, 2][..], &[3, 4, 5][..]]
+        );
+        assert_eq!(grid.row(1), Some(&[3, 4, 5][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(grid.column(3).count(), 0);
+
+        for row in grid.rows_mut() {
+            row.reverse();
+        }
+        assert_eq!(grid.data, vec![2, 1, 0, 5, 4, 3]);
+        grid.row_mut(0).unwrap()[0] = 9;
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&9));
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut grid = Grid2D::new(4, 4, 0);
+        let mut view =
+            grid.sub_grid_mut(Rect::with_points(Point::new(1, 1), Point::new(3, 3)));
+        assert!(view.set(Point::new(1, 1), 5));
+        assert!(!view.set(Point::new(0, 0), 5));
+        *view.get_mut(Point::new(2, 2)).unwrap() = 7;
+        for row in view.rows_mut() {
+            row[0] += 1;
+        }
+        assert_eq!(
+            grid.data,
+            vec![0, 0, 0, 0, 0, 6, 0, 0, 0, 1, 7, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_fill_rect() {
+        let mut grid = Grid2D::new(4, 3, '.');
+        grid.fill_rect(Rect::with_points(Point::new(2, 1), Point::new(10, 10)), '#');
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec!["....", "..##", "..##"]);
+    }
+
+    #[test]
+    fn test_blit() {
+        let mut map = Grid2D::new(5, 5, 0);
+        let prefab = numbered_grid(3, 3).map(|v| v + 1);
+        map.blit(&prefab, Point::new(1, 1));
+        assert_eq!(map.get(Point::new(1, 1)), Some(&1));
+        assert_eq!(map.get(Point::new(3, 3)), Some(&9));
+        assert_eq!(map.get(Point::new(0, 0)), Some(&0));
+        assert_eq!(map.get(Point::new(4, 4)), Some(&0));
+    }
+
+    #[test]
+    fn test_blit_clipped() {
+        let mut map = Grid2D::new(3, 3, 0);
+        let prefab = numbered_grid(3, 3).map(|v| v + 1);
+        map.blit(&prefab, Point::new(-1, 2));
+        assert_eq!(map.data, vec![0, 0, 0, 0, 0, 0, 2, 3, 0]);
+
+        // entirely outside the destination
+        map.blit(&prefab, Point::new(5, 5));
+        assert_eq!(map.data, vec![0, 0, 0, 0, 0, 0, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_blit_from_view() {
+        let src = numbered_grid(4, 4);
+        let mut dest = Grid2D::new(2, 2, -1);
+        dest.blit(
+            src.sub_grid(Rect::with_points(Point::new(1, 2), Point::new(3, 4))),
+            Point::new(0, 0),
+        );
+        assert_eq!(dest.data, vec![9, 10, 13, 14]);
+    }
+}
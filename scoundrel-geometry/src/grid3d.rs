@@ -0,0 +1,292 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Grid2D, Point3};
+
+/// The set of cells considered adjacent to a cell of a `Grid3D`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Neighborhood3D {
+    /// The 6 cells sharing a face with the cell.
+    VonNeumann,
+    /// The 26 cells sharing a face, edge or corner with the cell.
+    Moore,
+    /// The 8 cells surrounding the cell on the same z-level.
+    ///
+    /// Useful for multi-level dungeons, where movement between levels only happens at
+    /// explicit stairs or ladders.
+    Planar,
+}
+
+impl Neighborhood3D {
+    const VON_NEUMANN_OFFSETS: [Point3; 6] = [
+        Point3 { x: 0, y: -1, z: 0 },
+        Point3 { x: 1, y: 0, z: 0 },
+        Point3 { x: 0, y: 1, z: 0 },
+        Point3 { x: -1, y: 0, z: 0 },
+        Point3 { x: 0, y: 0, z: -1 },
+        Point3 { x: 0, y: 0, z: 1 },
+    ];
+
+    const MOORE_OFFSETS: [Point3; 26] = {
+        let mut offsets = [Point3 { x: 0, y: 0, z: 0 }; 26];
+        let mut idx = 0;
+        let mut i = 0;
+        while i < 27 {
+            let (x, y, z) = (i % 3 - 1, i / 3 % 3 - 1, i / 9 - 1);
+            if x != 0 || y != 0 || z != 0 {
+                offsets[idx] = Point3 { x, y, z };
+                idx += 1;
+            }
+            i += 1;
+        }
+        offsets
+    };
+
+    const PLANAR_OFFSETS: [Point3; 8] = [
+        Point3 { x: 0, y: -1, z: 0 },
+        Point3 { x: 1, y: -1, z: 0 },
+        Point3 { x: 1, y: 0, z: 0 },
+        Point3 { x: 1, y: 1, z: 0 },
+        Point3 { x: 0, y: 1, z: 0 },
+        Point3 { x: -1, y: 1, z: 0 },
+        Point3 { x: -1, y: 0, z: 0 },
+        Point3 { x: -1, y: -1, z: 0 },
+    ];
+
+    /// Returns the offsets from a cell to each of its neighbors.
+    pub fn offsets(&self) -> &'static [Point3] {
+        match self {
+            Neighborhood3D::VonNeumann => &Self::VON_NEUMANN_OFFSETS,
+            Neighborhood3D::Moore => &Self::MOORE_OFFSETS,
+            Neighborhood3D::Planar => &Self::PLANAR_OFFSETS,
+        }
+    }
+}
+
+/// A 3D grid data structure.
+///
+/// The grid is stored as a stack of `depth` z-levels, each a row-major `width` by `height`
+/// layer laid out exactly like a `Grid2D`. The element at `(x, y, z)` lives at index
+/// `((z * height + y) * width + x) as usize`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct Grid3D<T> {
+    pub data: Vec<T>,
+    width: i32,
+    height: i32,
+    depth: i32,
+}
+
+impl<T: Copy> Grid3D<T> {
+    /// Creates a new grid of the specified dimensions, filled with the given value.
+    pub fn new(width: i32, height: i32, depth: i32, fill: T) -> Grid3D<T> {
+        Grid3D {
+            data: vec![fill; width as usize * height as usize * depth as usize],
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// Stacks 2D grids into a 3D grid, with the first grid at `z = 0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the levels do not all have the same dimensions.
+    pub fn from_levels(levels: &[Grid2D<T>]) -> Grid3D<T> {
+        let (width, height) = levels
+            .first()
+            .map(|level| (level.width(), level.height()))
+            .unwrap_or((0, 0));
+        let mut data =
+            Vec::with_capacity(width as usize * height as usize * levels.len());
+        for level in levels {
+            assert_eq!(
+                (level.width(), level.height()),
+                (width, height),
+                "all levels must have the same dimensions"
+            );
+            data.extend_from_slice(&level.data);
+        }
+        Grid3D {
+            data,
+            width,
+            height,
+            depth: levels.len() as i32,
+        }
+    }
+
+    /// Returns a copy of a single z-level as a `Grid2D`.
+    pub fn level(&self, z: i32) -> Option<Grid2D<T>> {
+        Some(Grid2D::from_iter(
+            self.level_slice(z)?.iter().copied(),
+            self.width,
+            self.height,
+        ))
+    }
+}
+
+impl<T> Grid3D<T> {
+    pub fn size(&self) -> Point3 {
+        Point3::new(self.width, self.height, self.depth)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> i32 {
+        self.depth
+    }
+
+    /// Returns the index of the given point in `data`, or `None` if it is outside the
+    /// grid.
+    pub fn index(&self, pt: Point3) -> Option<usize> {
+        if pt.x < 0
+            || pt.y < 0
+            || pt.z < 0
+            || pt.x >= self.width
+            || pt.y >= self.height
+            || pt.z >= self.depth
+        {
+            None
+        } else {
+            Some(((pt.z * self.height + pt.y) * self.width + pt.x) as usize)
+        }
+    }
+
+    pub fn contains(&self, pt: Point3) -> bool {
+        self.index(pt).is_some()
+    }
+
+    pub fn get(&self, pt: Point3) -> Option<&T> {
+        self.index(pt).map(|idx| &self.data[idx])
+    }
+
+    pub fn get_mut(&mut self, pt: Point3) -> Option<&mut T> {
+        self.index(pt).map(|idx| &mut self.data[idx])
+    }
+
+    /// Sets the value of a cell, returning false if it is outside the grid.
+    pub fn set(&mut self, pt: Point3, value: T) -> bool {
+        match self.get_mut(pt) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the cells of a single z-level in row-major order.
+    pub fn level_slice(&self, z: i32) -> Option<&[T]> {
+        if z < 0 || z >= self.depth {
+            return None;
+        }
+        let len = (self.width * self.height) as usize;
+        Some(&self.data[z as usize * len..(z as usize + 1) * len])
+    }
+
+    /// Returns the cells of a single z-level in row-major order.
+    pub fn level_slice_mut(&mut self, z: i32) -> Option<&mut [T]> {
+        if z < 0 || z >= self.depth {
+            return None;
+        }
+        let len = (self.width * self.height) as usize;
+        Some(&mut self.data[z as usize * len..(z as usize + 1) * len])
+    }
+
+    /// Returns an iterator over every point in the grid, in storage order.
+    pub fn iter_coords(&self) -> impl Iterator<Item = Point3> + use<T> {
+        let (width, height) = (self.width, self.height);
+        (0..self.depth).flat_map(move |z| {
+            (0..height).flat_map(move |y| (0..width).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+
+    /// Returns an iterator over every point in the grid and its value.
+    pub fn iter(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.iter_coords().zip(self.data.iter())
+    }
+
+    /// Creates a new grid by applying `func` to every value.
+    pub fn map<F: FnMut(&T) -> P, P>(&self, func: F) -> Grid3D<P> {
+        Grid3D {
+            data: self.data.iter().map(func).collect(),
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_neighborhood_offsets() {
+        for neighborhood in [
+            Neighborhood3D::VonNeumann,
+            Neighborhood3D::Moore,
+            Neighborhood3D::Planar,
+        ] {
+            let offsets = neighborhood.offsets();
+            let unique: HashSet<_> = offsets.iter().collect();
+            assert_eq!(unique.len(), offsets.len());
+            assert!(!unique.contains(&Point3::new(0, 0, 0)));
+            assert!(
+                offsets
+                    .iter()
+                    .all(|o| o.x.abs() <= 1 && o.y.abs() <= 1 && o.z.abs() <= 1)
+            );
+        }
+        assert_eq!(Neighborhood3D::Moore.offsets().len(), 26);
+        assert!(
+            Neighborhood3D::VonNeumann
+                .offsets()
+                .iter()
+                .all(|o| o.sqr_magnitude() == 1)
+        );
+        assert!(Neighborhood3D::Planar.offsets().iter().all(|o| o.z == 0));
+    }
+
+    #[test]
+    fn test_grid3d_access() {
+        let mut grid = Grid3D::new(3, 2, 4, 0);
+        assert_eq!(grid.data.len(), 24);
+        assert!(grid.set(Point3::new(2, 1, 3), 7));
+        assert_eq!(grid.get(Point3::new(2, 1, 3)), Some(&7));
+        assert_eq!(grid.data[23], 7);
+        assert!(!grid.set(Point3::new(0, 0, 4), 1));
+        assert_eq!(grid.get(Point3::new(-1, 0, 0)), None);
+
+        for (pt, _) in grid.iter() {
+            assert_eq!(grid.index(pt).map(|i| &grid.data[i]), grid.get(pt));
+        }
+        assert_eq!(grid.iter_coords().count(), 24);
+        assert_eq!(grid.level_slice(3).unwrap()[5], 7);
+        assert!(grid.level_slice(4).is_none());
+    }
+
+    #[test]
+    fn test_grid3d_levels() {
+        let floor = |value| Grid2D::new(3, 2, value);
+        let mut levels = vec![floor(0), floor(1), floor(2)];
+        levels[1].set(Point::new(2, 0), 9);
+        let grid = Grid3D::from_levels(&levels);
+        assert_eq!(grid.size(), Point3::new(3, 2, 3));
+        assert_eq!(grid.get(Point3::new(2, 0, 1)), Some(&9));
+        assert_eq!(grid.get(Point3::new(1, 1, 2)), Some(&2));
+        assert_eq!(grid.level(1).unwrap().data, levels[1].data);
+        assert!(grid.level(3).is_none());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{Grid2D, Point, Rect};
+
+/// Common cell access for grid storage types.
+///
+/// This lets algorithms be written once against either a dense `Grid2D` or a
+/// `SparseGrid`. Cells are addressed by `Point` within `rect`; a cell may hold no value,
+/// either because it lies outside the grid or because the storage is sparse and nothing
+/// has been set there.
+pub trait GridAccess<T> {
+    /// Returns the region of space covered by the grid.
+    fn rect(&self) -> Rect;
+
+    /// Returns a reference to the value at the given point, if there is one.
+    fn get(&self, pt: Point) -> Option<&T>;
+
+    /// Returns a mutable reference to the value at the given point, if there is one.
+    fn get_mut(&mut self, pt: Point) -> Option<&mut T>;
+
+    /// Sets the value at the given point.
+    ///
+    /// # Returns
+    /// `true` if the point is within `rect` and the value was set, `false` otherwise.
+    fn set(&mut self, pt: Point, value: T) -> bool;
+
+    /// Returns an iterator over the positions and values of every cell holding a value.
+    ///
+    /// The order of iteration depends on the storage.
+    fn iter_cells<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a;
+}
+
+impl<T> GridAccess<T> for Grid2D<T> {
+    fn rect(&self) -> Rect {
+        Grid2D::rect(self)
+    }
+
+    fn get(&self, pt: Point) -> Option<&T> {
+        Grid2D::get(self, pt)
+    }
+
+    fn get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        Grid2D::get_mut(self, pt)
+    }
+
+    fn set(&mut self, pt: Point, value: T) -> bool {
+        Grid2D::set(self, pt, value)
+    }
+
+    /// Visits every cell in row-major order.
+    fn iter_cells<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter_coords().zip(self.data.iter())
+    }
+}
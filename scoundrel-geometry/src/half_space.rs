@@ -1,6 +1,10 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::{Axis2D, Bounds, Point, Vector2};
 
 /// An orthogonal line segment represented by an axis, a start point, and a length.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct OrthoLine {
     pub axis: Axis2D,
@@ -37,6 +41,7 @@ impl OrthoLine {
 }
 
 /// A half-space represented by an axis, an offset, and a sign.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct AxialHalfSpace<T> {
     pub axis: Axis2D,
@@ -258,13 +263,13 @@ mod tests {
         let opposite = halfspace.opposite();
         assert_eq!(opposite.axis, Axis2D::Y);
         assert_eq!(opposite.offset, 1);
-        assert_eq!(opposite.positive, true);
+        assert!(opposite.positive);
 
         // Check that opposite of opposite is original
         let original = opposite.opposite();
         assert_eq!(original.axis, Axis2D::Y);
         assert_eq!(original.offset, 1);
-        assert_eq!(original.positive, false);
+        assert!(!original.positive);
     }
 
     #[test]
@@ -0,0 +1,346 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, Vector2};
+
+/// A hexagon in axial coordinates.
+///
+/// Hexes are laid out "pointy-top", with `q` increasing to the east and `r` increasing to
+/// the south-east, matching the y-down convention of `Point`. The implicit third cube
+/// coordinate is `s = -q - r`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// An enumeration representing the 6 neighbors of a hex, in clockwise order.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HexNeighbor {
+    East = 0,
+    SouthEast = 1,
+    SouthWest = 2,
+    West = 3,
+    NorthWest = 4,
+    NorthEast = 5,
+}
+
+impl HexNeighbor {
+    /// All `HexNeighbor` variants, in the order they are defined.
+    pub const ALL: [HexNeighbor; 6] = [
+        HexNeighbor::East,
+        HexNeighbor::SouthEast,
+        HexNeighbor::SouthWest,
+        HexNeighbor::West,
+        HexNeighbor::NorthWest,
+        HexNeighbor::NorthEast,
+    ];
+
+    pub fn from_index(int: usize) -> Option<Self> {
+        Self::ALL.get(int).copied()
+    }
+
+    pub fn to_index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the `HexNeighbor` variant that is opposite to this one.
+    pub fn opposite(&self) -> HexNeighbor {
+        self.rotate(3)
+    }
+
+    /// Returns the direction rotated clockwise by `steps` sixths of a turn.
+    ///
+    /// Negative values rotate counterclockwise.
+    pub fn rotate(&self, steps: i32) -> HexNeighbor {
+        Self::ALL[(self.to_index() as i32 + steps).rem_euclid(6) as usize]
+    }
+
+    /// Returns the offset from the origin to the neighboring hex.
+    pub fn offset(&self) -> Hex {
+        let (q, r) = match self {
+            HexNeighbor::East => (1, 0),
+            HexNeighbor::SouthEast => (0, 1),
+            HexNeighbor::SouthWest => (-1, 1),
+            HexNeighbor::West => (-1, 0),
+            HexNeighbor::NorthWest => (0, -1),
+            HexNeighbor::NorthEast => (1, -1),
+        };
+        Hex::new(q, r)
+    }
+}
+
+impl Hex {
+    /// The hex at the origin.
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub const fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    /// Returns the third cube coordinate, `-q - r`.
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Returns the adjacent hex in the given direction.
+    pub fn neighbor(&self, direction: HexNeighbor) -> Hex {
+        *self + direction.offset()
+    }
+
+    /// Returns the six adjacent hexes, in the order of `HexNeighbor::ALL`.
+    pub fn neighbors(&self) -> impl Iterator<Item = Hex> + use<> {
+        let center = *self;
+        HexNeighbor::ALL
+            .into_iter()
+            .map(move |n| center + n.offset())
+    }
+
+    /// Returns the number of steps from this hex to the origin.
+    pub fn length(&self) -> i32 {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    /// Returns the number of steps between two hexes.
+    pub fn distance(&self, other: Hex) -> i32 {
+        (*self - other).length()
+    }
+
+    /// Returns the hexes at exactly `radius` steps from this one, in clockwise order
+    /// starting from the north-west corner.
+    pub fn ring(&self, radius: i32) -> Vec<Hex> {
+        if radius <= 0 {
+            return vec![*self];
+        }
+        let mut result = Vec::with_capacity(6 * radius as usize);
+        let mut hex = *self + HexNeighbor::NorthWest.offset() * radius;
+        for direction in HexNeighbor::ALL {
+            for _ in 0..radius {
+                result.push(hex);
+                hex = hex.neighbor(direction);
+            }
+        }
+        result
+    }
+
+    /// Returns all hexes within `radius` steps of this one, ordered by ring from the
+    /// center outwards.
+    pub fn spiral(&self, radius: i32) -> Vec<Hex> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Returns the hexes on a straight line from this hex to `other`, inclusive.
+    ///
+    /// Each step moves to an adjacent hex. Ties along hex edges are broken consistently
+    /// by nudging the line slightly.
+    pub fn line_to(&self, other: Hex) -> Vec<Hex> {
+        let n = self.distance(other);
+        let (q0, r0) = (self.q as f32 + 1e-6, self.r as f32 + 1e-6);
+        let (q1, r1) = (other.q as f32 + 1e-6, other.r as f32 + 1e-6);
+        (0..=n)
+            .map(|i| {
+                let t = if n == 0 { 0.0 } else { i as f32 / n as f32 };
+                Hex::round(q0 + (q1 - q0) * t, r0 + (r1 - r0) * t)
+            })
+            .collect()
+    }
+
+    /// Rounds fractional axial coordinates to the hex containing them.
+    pub fn round(q: f32, r: f32) -> Hex {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Hex::new(rq as i32, rr as i32)
+    }
+
+    /// Rotates the hex a sixth of a turn clockwise around the origin.
+    pub fn rotate_cw(&self) -> Hex {
+        Hex::new(-self.r, -self.s())
+    }
+
+    /// Rotates the hex a sixth of a turn counterclockwise around the origin.
+    pub fn rotate_ccw(&self) -> Hex {
+        Hex::new(-self.s(), -self.q)
+    }
+
+    /// Rotates the hex around `center` by `steps` sixths of a turn clockwise.
+    ///
+    /// Negative values rotate counterclockwise.
+    pub fn rotate_around(&self, center: Hex, steps: i32) -> Hex {
+        let mut offset = *self - center;
+        for _ in 0..steps.rem_euclid(6) {
+            offset = offset.rotate_cw();
+        }
+        center + offset
+    }
+
+    /// Converts to "odd-r" offset coordinates, where odd rows are shifted half a hex to
+    /// the east.
+    pub fn to_offset(&self) -> Point {
+        Point::new(self.q + (self.r - (self.r & 1)) / 2, self.r)
+    }
+
+    /// Converts from "odd-r" offset coordinates.
+    pub fn from_offset(pt: Point) -> Hex {
+        Hex::new(pt.x - (pt.y - (pt.y & 1)) / 2, pt.y)
+    }
+
+    /// Returns the center of the hex in pixel space, for hexes with the given distance
+    /// from center to corner.
+    pub fn to_pixel(&self, size: f32) -> Vector2<f32> {
+        let sqrt_3 = 3f32.sqrt();
+        Vector2::new(
+            size * (sqrt_3 * self.q as f32 + sqrt_3 / 2.0 * self.r as f32),
+            size * 1.5 * self.r as f32,
+        )
+    }
+
+    /// Returns the hex containing the given pixel position, for hexes with the given
+    /// distance from center to corner.
+    pub fn from_pixel(pt: Vector2<f32>, size: f32) -> Hex {
+        let q = (3f32.sqrt() / 3.0 * pt.x - pt.y / 3.0) / size;
+        let r = (2.0 / 3.0 * pt.y) / size;
+        Hex::round(q, r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, rhs: Hex) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Hex {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let hex = Hex::new(2, -1);
+        for n in HexNeighbor::ALL {
+            assert_eq!(hex.neighbor(n).distance(hex), 1);
+            assert_eq!(hex.neighbor(n).neighbor(n.opposite()), hex);
+            assert_eq!(n.offset().rotate_cw(), n.rotate(1).offset());
+            assert_eq!(n.offset().rotate_ccw(), n.rotate(-1).offset());
+        }
+        assert_eq!(hex.neighbors().count(), 6);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Hex::ORIGIN.distance(Hex::new(3, -3)), 3);
+        assert_eq!(Hex::ORIGIN.distance(Hex::new(2, 2)), 4);
+        assert_eq!(Hex::new(-1, 4).distance(Hex::new(2, 1)), 3);
+    }
+
+    #[test]
+    fn test_ring_and_spiral() {
+        let center = Hex::new(1, 2);
+        assert_eq!(center.ring(0), vec![center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            for (i, hex) in ring.iter().enumerate() {
+                assert_eq!(hex.distance(center), radius);
+                assert_eq!(hex.distance(ring[(i + 1) % ring.len()]), 1);
+            }
+        }
+        let spiral = center.spiral(3);
+        assert_eq!(spiral.len(), 37);
+        assert!(
+            spiral
+                .windows(2)
+                .all(|w| w[0].distance(center) <= w[1].distance(center))
+        );
+    }
+
+    #[test]
+    fn test_line() {
+        let (a, b) = (Hex::new(-2, 0), Hex::new(3, -4));
+        let line = a.line_to(b);
+        assert_eq!(line.len(), a.distance(b) as usize + 1);
+        assert_eq!(line.first(), Some(&a));
+        assert_eq!(line.last(), Some(&b));
+        assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert_eq!(a.line_to(a), vec![a]);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let hex = Hex::new(2, -1);
+        assert_eq!(hex.rotate_cw().rotate_ccw(), hex);
+        let mut rotated = hex;
+        for _ in 0..6 {
+            rotated = rotated.rotate_cw();
+            assert_eq!(rotated.length(), hex.length());
+        }
+        assert_eq!(rotated, hex);
+
+        let center = Hex::new(5, 5);
+        assert_eq!(
+            (center + hex).rotate_around(center, 2),
+            center + hex.rotate_cw().rotate_cw()
+        );
+        assert_eq!(
+            (center + hex).rotate_around(center, -1),
+            center + hex.rotate_ccw()
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        for hex in Hex::new(1, 1).spiral(4) {
+            assert_eq!(Hex::from_offset(hex.to_offset()), hex);
+            assert_eq!(Hex::from_pixel(hex.to_pixel(10.0), 10.0), hex);
+        }
+        assert_eq!(Hex::new(0, 1).to_offset(), Point::new(0, 1));
+        assert_eq!(Hex::new(-1, 2).to_offset(), Point::new(0, 2));
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Hex, Point};
+
+/// A rectangular map of hexes.
+///
+/// Cells are stored row by row in "odd-r" offset layout, so a grid of `width` by `height`
+/// hexes forms a rectangle on screen rather than a rhombus. Cells are addressed by their
+/// axial `Hex` coordinates.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct HexGrid<T> {
+    pub data: Vec<T>,
+    width: i32,
+    height: i32,
+}
+
+impl<T: Copy> HexGrid<T> {
+    /// Creates a new grid of the specified dimensions, filled with the given value.
+    pub fn new(width: i32, height: i32, fill: T) -> HexGrid<T> {
+        HexGrid {
+            data: vec![fill; width as usize * height as usize],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> HexGrid<T> {
+    /// Creates a new grid by calling `f` for every hex.
+    pub fn from_fn<F: FnMut(Hex) -> T>(width: i32, height: i32, mut f: F) -> HexGrid<T> {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .map(|pt| f(Hex::from_offset(pt)))
+            .collect();
+        HexGrid {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the number of hexes in each row.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Returns the index of the given hex in `data`, or `None` if it is outside the grid.
+    pub fn index(&self, hex: Hex) -> Option<usize> {
+        let pt = hex.to_offset();
+        if pt.x < 0 || pt.y < 0 || pt.x >= self.width || pt.y >= self.height {
+            None
+        } else {
+            Some((pt.y * self.width + pt.x) as usize)
+        }
+    }
+
+    /// Returns true if the hex lies within the grid.
+    pub fn contains(&self, hex: Hex) -> bool {
+        self.index(hex).is_some()
+    }
+
+    pub fn get(&self, hex: Hex) -> Option<&T> {
+        self.index(hex).map(|idx| &self.data[idx])
+    }
+
+    pub fn get_mut(&mut self, hex: Hex) -> Option<&mut T> {
+        self.index(hex).map(|idx| &mut self.data[idx])
+    }
+
+    /// Sets the value of a hex, returning false if it is outside the grid.
+    pub fn set(&mut self, hex: Hex, value: T) -> bool {
+        match self.get_mut(hex) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over every hex in the grid, in storage order.
+    pub fn hexes(&self) -> impl Iterator<Item = Hex> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| {
+            (0..width).map(move |x| Hex::from_offset(Point::new(x, y)))
+        })
+    }
+
+    /// Returns an iterator over every hex in the grid and its value.
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, &T)> {
+        self.hexes().zip(self.data.iter())
+    }
+
+    /// Creates a new grid by applying `func` to every value.
+    pub fn map<F: FnMut(&T) -> P, P>(&self, func: F) -> HexGrid<P> {
+        HexGrid {
+            data: self.data.iter().map(func).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_grid_access() {
+        let mut grid = HexGrid::from_fn(4, 3, |hex| hex.to_offset());
+        assert_eq!(grid.data.len(), 12);
+        for (hex, pt) in grid.iter() {
+            assert_eq!(hex.to_offset(), *pt);
+        }
+        assert_eq!(grid.hexes().count(), 12);
+
+        // the left edge of a rectangular map zigzags in axial coordinates
+        assert!(grid.contains(Hex::new(0, 1)));
+        assert!(grid.contains(Hex::new(-1, 2)));
+        assert!(!grid.contains(Hex::new(-1, 1)));
+        assert!(!grid.contains(Hex::new(0, 3)));
+
+        assert!(grid.set(Hex::new(-1, 2), Point::new(9, 9)));
+        assert_eq!(grid.get(Hex::new(-1, 2)), Some(&Point::new(9, 9)));
+        assert!(!grid.set(Hex::new(4, 0), Point::new(9, 9)));
+        assert_eq!(grid.get(Hex::new(4, 0)), None);
+    }
+}
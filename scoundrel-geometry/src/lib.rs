@@ -1,16 +1,34 @@
+/// Bit-packed boolean grid.
+pub use bit_grid::BitGrid;
 pub use bounds::Bounds;
+/// Unbounded grid storage made of chunks loaded on demand.
+pub use chunked_grid::ChunkedGrid;
 pub use matrix::Mat2;
 pub use neighborhood::MooreNeighbor;
 pub use vector::{Axis2D, Vector2, Vector3, Vector4};
 
+mod bit_grid;
 mod bounds;
 pub mod bsp;
+mod chunked_grid;
 mod grid2d;
+mod grid2d_sample;
+mod grid2d_transform;
+mod grid2d_view;
+mod grid3d;
+mod grid_access;
 mod half_space;
+mod hex;
+mod hex_grid;
 mod matrix;
 pub mod metric;
 mod neighborhood;
 pub mod quadtree;
+mod rtree;
+mod sparse_grid;
+mod spatial_hash;
+#[cfg(test)]
+mod test_util;
 pub mod tilebin;
 pub mod vector;
 
@@ -28,9 +46,27 @@ pub type Point3 = Vector3<i32>;
 ///
 /// Alias for `Bounds<i32>`.
 pub type Rect = Bounds<i32>;
+/// Shared cell access for dense and sparse grids.
+pub use grid_access::GridAccess;
 /// Grid data structure and related iterators.
 pub use grid2d::{Grid2D, GridCoordIterator, GridIterator, GridNeighborhoodIterator};
+/// Sampling and gradient options for `Grid2D`.
+pub use grid2d_sample::{EdgePolicy, GradientMode, Interpolation};
+/// Borrowed rectangular views into a `Grid2D`.
+pub use grid2d_view::{Grid2DView, Grid2DViewMut};
+/// Voxel grid storage and 3D neighborhoods.
+pub use grid3d::{Grid3D, Neighborhood3D};
 /// Half space and orthogonal line primitives for spatial partitioning.
 pub use half_space::{AxialHalfSpace, OrthoLine};
+/// Axial hex coordinates and directions.
+pub use hex::{Hex, HexNeighbor};
+/// Rectangular hex map storage.
+pub use hex_grid::HexGrid;
+/// Bulk-loaded R-tree for static rectangular items.
+pub use rtree::RTree;
+/// Grid storage for mostly empty layers.
+pub use sparse_grid::SparseGrid;
+/// Cell-bucketed spatial index for range queries.
+pub use spatial_hash::SpatialHash;
 /// Spatial lookup data structure for tile-based games.
 pub use tilebin::TileBin;
@@ -5,7 +5,7 @@ use scoundrel_util::numeric::{HasOne, HasZero};
 use crate::Vector2;
 
 /// A `Mat2` is a 2x2 matrix with elements of type `T`.
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Mat2<T: Copy> {
     pub col1: Vector2<T>,
     pub col2: Vector2<T>,
@@ -106,6 +106,68 @@ impl<T: Copy + Mul<Output = T> + Add<Output = T>> Mul<Vector2<T>> for Mat2<T> {
     }
 }
 
+impl<T: Copy + Mul<Output = T> + Add<Output = T>> Mul<Mat2<T>> for Mat2<T> {
+    type Output = Mat2<T>;
+
+    fn mul(self, rhs: Mat2<T>) -> Self::Output {
+        Mat2::from_cols(self * rhs.col1, self * rhs.col2)
+    }
+}
+
+impl Mat2<i32> {
+    /// Returns a matrix rotating a quarter turn clockwise, assuming `y` points down as it
+    /// does on a grid.
+    pub fn rotate_cw() -> Self {
+        Mat2::row_major(0, -1, 1, 0)
+    }
+
+    /// Returns a matrix rotating a quarter turn counter-clockwise, assuming `y` points down
+    /// as it does on a grid.
+    pub fn rotate_ccw() -> Self {
+        Mat2::row_major(0, 1, -1, 0)
+    }
+
+    /// Returns a matrix rotating a half turn.
+    pub fn rotate_180() -> Self {
+        Mat2::row_major(-1, 0, 0, -1)
+    }
+
+    /// Returns a matrix mirroring the `x` axis, flipping left and right.
+    pub fn flip_x() -> Self {
+        Mat2::row_major(-1, 0, 0, 1)
+    }
+
+    /// Returns a matrix mirroring the `y` axis, flipping top and bottom.
+    pub fn flip_y() -> Self {
+        Mat2::row_major(1, 0, 0, -1)
+    }
+
+    /// Returns all eight rotations and reflections that map the grid axes onto themselves.
+    ///
+    /// The first four are the rotations by 0, 90, 180 and 270 degrees clockwise; the last
+    /// four are the same rotations applied after `flip_x`.
+    pub fn orientations() -> [Self; 8] {
+        let rotations = [
+            Mat2::ident(),
+            Mat2::rotate_cw(),
+            Mat2::rotate_180(),
+            Mat2::rotate_ccw(),
+        ];
+        let mut result = [Mat2::ident(); 8];
+        for (idx, rotation) in rotations.into_iter().enumerate() {
+            result[idx] = rotation;
+            result[idx + 4] = rotation * Mat2::flip_x();
+        }
+        result
+    }
+
+    /// Returns `true` if this matrix is one of the eight `orientations`.
+    pub fn is_orientation(&self) -> bool {
+        let unit = |v: Vector2<i32>| v.x.abs() + v.y.abs() == 1;
+        unit(self.col1) && unit(self.col2) && self.col1.dot(&self.col2) == 0
+    }
+}
+
 impl<T: Copy + Mul<Output = Tp>, Tp: Sub> Mat2<T> {
     /// Returns the determinant of this matrix.
     pub fn det(&self) -> <Tp as Sub>::Output {
@@ -201,6 +263,36 @@ mod tests {
         assert_eq!(transposed.col2.y, mat.col2.y);
     }
 
+    #[test]
+    fn test_mat2_product() {
+        let a = Mat2::row_major(1, 2, 3, 4);
+        let b = Mat2::row_major(0, 1, 1, 0);
+        assert_eq!(a * b, Mat2::row_major(2, 1, 4, 3));
+        assert_eq!((a * b) * Vector2::new(1, 2), a * (b * Vector2::new(1, 2)));
+    }
+
+    #[test]
+    fn test_orientations() {
+        let right = Vector2::new(1, 0);
+        assert_eq!(Mat2::rotate_cw() * right, Vector2::new(0, 1));
+        assert_eq!(Mat2::rotate_ccw() * right, Vector2::new(0, -1));
+        assert_eq!(Mat2::rotate_cw() * Mat2::rotate_ccw(), Mat2::ident());
+        assert_eq!(Mat2::rotate_cw() * Mat2::rotate_cw(), Mat2::rotate_180());
+        assert_eq!(Mat2::flip_x() * Mat2::flip_y(), Mat2::rotate_180());
+
+        let orientations = Mat2::orientations();
+        for (idx, m) in orientations.iter().enumerate() {
+            assert!(m.is_orientation());
+            assert!(!orientations[idx + 1..].contains(m));
+            // closed under composition
+            for other in &orientations {
+                assert!(orientations.contains(&(*m * *other)));
+            }
+        }
+        assert!(!Mat2::row_major(1, 1, 0, 1).is_orientation());
+        assert!(!Mat2::row_major(2, 0, 0, 1).is_orientation());
+    }
+
     #[test]
     fn test_zero() {
         let mat = Mat2::<i32>::zero();
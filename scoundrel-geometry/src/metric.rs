@@ -26,7 +26,7 @@ impl<
 
     fn distance_fast_monotonic(&self, lhs: VectorT, rhs: VectorT) -> T {
         lhs.into_iter()
-            .zip(rhs.into_iter())
+            .zip(rhs)
             .map(|(l, r)| (l - r) * (l - r))
             .fold(T::zero(), |acc, x| acc + x)
     }
@@ -39,7 +39,7 @@ impl<T: Copy + HasZero + Ring + Sub<Output = T> + HasAbs, VectorT: IntoIterator<
 {
     fn distance(&self, lhs: VectorT, rhs: VectorT) -> T {
         lhs.into_iter()
-            .zip(rhs.into_iter())
+            .zip(rhs)
             .map(|(l, r)| (l - r)._abs())
             .fold(T::zero(), |acc, x| acc + x)
     }
@@ -54,7 +54,7 @@ impl<
 {
     fn distance(&self, lhs: VectorT, rhs: VectorT) -> T {
         lhs.into_iter()
-            .zip(rhs.into_iter())
+            .zip(rhs)
             .map(|(l, r)| (l - r)._abs())
             .fold(T::zero(), |acc, x| acc.max(x))
     }
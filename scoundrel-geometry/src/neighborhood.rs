@@ -46,6 +46,18 @@ int_enum! {
 }
 
 impl MooreNeighbor {
+    /// All `MooreNeighbor` variants, in the order they are defined.
+    pub const ALL: [MooreNeighbor; 8] = [
+        MooreNeighbor::Up,
+        MooreNeighbor::RightUp,
+        MooreNeighbor::Right,
+        MooreNeighbor::RightDown,
+        MooreNeighbor::Down,
+        MooreNeighbor::LeftDown,
+        MooreNeighbor::Left,
+        MooreNeighbor::LeftUp,
+    ];
+
     /// Calls the provided closure `f` once for each `MooreNeighbor` variant, in the order they are defined.
     pub fn for_each<F: FnMut(MooreNeighbor)>(mut f: F) {
         for idx in 0..8 {
@@ -55,7 +67,7 @@ impl MooreNeighbor {
 
     /// Returns a vector containing all `MooreNeighbor` variants, in the order they are defined.
     pub fn all() -> Vec<MooreNeighbor> {
-        (0..8).map(|idx| Self::from_index(idx).unwrap()).collect()
+        Self::ALL.to_vec()
     }
 
     /// Returns the `MooreNeighbor` variant that is opposite to this one.
@@ -0,0 +1,1285 @@
+use std::collections::BinaryHeap;
+
+use scoundrel_util::MinHeapEntry;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::metric::VectorMetric;
+use crate::{Point, Rect};
+
+mod region;
+
+pub use region::RegionQuadTree;
+
+/// The payload of a quadtree node, which can be either leaf contents or child nodes.
+///
+/// A quadtree node is either a leaf node containing a list of items, or an internal
+/// node with four children corresponding to the four quadrants of the node's bounds.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub enum NodePayload<T> {
+    /// A leaf node containing a list of items with their positions.
+    Contents(Vec<(T, Point)>),
+
+    /// An internal node with four children in the following order:
+    /// - 0: upper-right quadrant (x+, y+)
+    /// - 1: upper-left quadrant (x-, y+)  
+    /// - 2: lower-left quadrant (x-, y-)
+    /// - 3: lower-right quadrant (x+, y-)
+    Children(Box<[Node<T>; 4]>),
+}
+
+/// A node in a quadtree spatial data structure.
+///
+/// Each node represents a rectangular region of space and either contains
+/// items directly (leaf node) or has been subdivided into four child nodes
+/// (internal node). This structure enables efficient spatial queries.
+///
+/// With the `serde` feature, deserializing a node checks that its items lie within its
+/// bounds and that its children cover its quadrants.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Debug)]
+pub struct Node<T> {
+    /// The rectangular bounds of the space this node represents.
+    bounds: Rect,
+
+    /// The payload of this node (either items or child nodes).
+    payload: NodePayload<T>,
+}
+
+impl<T> Node<T> {
+    /// Finds the item nearest to the query point in this node's subtree.
+    ///
+    /// This method implements a spatial nearest-neighbor search. It uses the
+    /// quadtree structure to efficiently prune the search space, checking
+    /// nearby quadrants first and avoiding quadrants that cannot contain a
+    /// better match than the current best.
+    ///
+    /// # Arguments
+    /// * `query` - The point to find the nearest item to
+    /// * `best` - The current best match, if any (used for recursive calls)
+    ///
+    /// # Returns
+    /// An option containing a tuple of (squared distance, reference to item)
+    /// for the nearest item found, or None if the node contains no items.
+    pub fn nearest<'a>(
+        &'a self,
+        query: Point,
+        mut best: Option<(i32, &'a (T, Point))>,
+    ) -> Option<(i32, &'a (T, Point))> {
+        let closest_possible = self.bounds.closest_pt(query);
+        if best.is_some_and(|b| b.0 < (closest_possible - query).sqr_magnitude()) {
+            // if best current candidate is closer than anything inside our bounds, early exit
+            return best;
+        }
+
+        match &self.payload {
+            NodePayload::Contents(items) => {
+                for item in items {
+                    let sqr_dist = (item.1 - query).sqr_magnitude();
+                    if best.is_none_or(|b| b.0 > sqr_dist) {
+                        best = Some((sqr_dist, item));
+                    }
+                }
+            }
+            NodePayload::Children(children) => {
+                let quadrant = self.bounds.containing_quadrant_idx(query);
+                best = children[quadrant].nearest(query, best);
+                best = children[(quadrant + 1) % 4].nearest(query, best);
+                best = children[(quadrant + 2) % 4].nearest(query, best);
+                best = children[(quadrant + 3) % 4].nearest(query, best);
+            }
+        }
+        best
+    }
+
+    /// Finds all items contained within the specified rectangular region.
+    ///
+    /// This method efficiently queries the quadtree to find all items whose
+    /// positions are inside the given rectangle. It uses the tree structure
+    /// to quickly prune branches that don't intersect with the query region.
+    ///
+    /// # Arguments
+    /// * `rect` - The rectangular region to query
+    /// * `f` - A callback function that will be invoked for each item found
+    ///
+    /// # Example
+    /// ```
+    /// # use scoundrel_geometry::{quadtree, Rect, Point};
+    /// # let items = vec![(1, Point::new(5, 5)), (2, Point::new(15, 15))];
+    /// # let bounds = Rect::with_points(Point::new(0, 0), Point::new(20, 20));
+    /// # let tree = quadtree::build_quadtree(items, bounds, 2);
+    /// let mut results = Vec::new();
+    /// tree.query_rect(
+    ///     Rect::with_points(Point::new(0, 0), Point::new(10, 10)),
+    ///     &mut |&(id, _)| { results.push(id); }
+    /// );
+    /// assert_eq!(results, vec![1]); // Only the item at (5,5) is found
+    /// ```
+    pub fn query_rect<F: FnMut(&(T, Point))>(&self, rect: Rect, f: &mut F) {
+        if !self.bounds.intersects(&rect) {
+            return;
+        }
+
+        match &self.payload {
+            NodePayload::Contents(contents) => {
+                for item in contents {
+                    if rect.contains(item.1) {
+                        f(item)
+                    }
+                }
+            }
+            NodePayload::Children(children) => {
+                for child in &children[..] {
+                    child.query_rect(rect, f);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Node<T> {
+    /// Returns the rectangular bounds of the space this node represents.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Returns the payload of this node.
+    pub fn payload(&self) -> &NodePayload<T> {
+        &self.payload
+    }
+
+    /// Returns the number of items in this node's subtree.
+    pub fn len(&self) -> usize {
+        match &self.payload {
+            NodePayload::Contents(items) => items.len(),
+            NodePayload::Children(children) => children.iter().map(Node::len).sum(),
+        }
+    }
+
+    /// Returns true if this node's subtree contains no items.
+    pub fn is_empty(&self) -> bool {
+        match &self.payload {
+            NodePayload::Contents(items) => items.is_empty(),
+            NodePayload::Children(children) => children.iter().all(Node::is_empty),
+        }
+    }
+
+    /// Calls `f` for every item in this node's subtree.
+    pub fn for_each<F: FnMut(&(T, Point))>(&self, f: &mut F) {
+        match &self.payload {
+            NodePayload::Contents(items) => items.iter().for_each(f),
+            NodePayload::Children(children) => {
+                for child in &children[..] {
+                    child.for_each(f);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over the items in this node's subtree in order of increasing
+    /// distance from the query point, along with their squared distances.
+    ///
+    /// Items are found lazily with a best-first search, so taking only the first few
+    /// items visits only the nearby part of the tree.
+    pub fn iter_nearest(
+        &self,
+        query: Point,
+    ) -> impl Iterator<Item = (i32, &(T, Point))> + '_ {
+        self.iter_by_distance(
+            move |pt| (pt - query).sqr_magnitude(),
+            move |bounds| (bounds.closest_pt(query) - query).sqr_magnitude(),
+        )
+    }
+
+    /// Finds the `k` items nearest to the query point, sorted by increasing squared
+    /// distance.
+    pub fn k_nearest(&self, query: Point, k: usize) -> Vec<(i32, &(T, Point))> {
+        self.iter_nearest(query).take(k).collect()
+    }
+
+    /// Finds the nearest item to the query point for which `pred` returns true.
+    pub fn nearest_where<F: FnMut(&T) -> bool>(
+        &self,
+        query: Point,
+        mut pred: F,
+    ) -> Option<(i32, &(T, Point))> {
+        self.iter_nearest(query).find(|(_, (item, _))| pred(item))
+    }
+
+    /// Finds all items within `radius` of the query point under the given metric, sorted
+    /// by increasing distance.
+    ///
+    /// Distances are compared with `VectorMetric::distance_fast_monotonic`, so results are
+    /// exact even for metrics whose integer distances are rounded. The metric must not
+    /// increase when any coordinate of a point moves towards the query point, which holds
+    /// for the Euclidean, Manhattan and Chebyshev metrics.
+    pub fn within_radius<M: VectorMetric<i32, Point>>(
+        &self,
+        query: Point,
+        radius: i32,
+        metric: &M,
+    ) -> Vec<&(T, Point)> {
+        // a point `radius` away along an axis is exactly at the limit under any such metric
+        let limit =
+            metric.distance_fast_monotonic(Point::new(0, 0), Point::new(radius, 0));
+        self.iter_by_distance(
+            |pt| metric.distance_fast_monotonic(query, pt),
+            |bounds| metric.distance_fast_monotonic(query, bounds.closest_pt(query)),
+        )
+        .take_while(|(dist, _)| *dist <= limit)
+        .map(|(_, item)| item)
+        .collect()
+    }
+
+    /// Visits items in order of increasing `item_distance`, where `node_distance` gives a
+    /// lower bound on the distance of every item within a node's bounds.
+    fn iter_by_distance<P: Ord + Copy, FI: Fn(Point) -> P, FN: Fn(Rect) -> P>(
+        &self,
+        item_distance: FI,
+        node_distance: FN,
+    ) -> impl Iterator<Item = (P, &(T, Point))> {
+        let mut frontier = BinaryHeap::new();
+        frontier.push(MinHeapEntry {
+            value: Candidate::Node(self),
+            priority: node_distance(self.bounds),
+        });
+        std::iter::from_fn(move || {
+            while let Some(MinHeapEntry { value, priority }) = frontier.pop() {
+                match value {
+                    Candidate::Item(item) => return Some((priority, item)),
+                    Candidate::Node(node) => match &node.payload {
+                        NodePayload::Contents(items) => {
+                            frontier.extend(items.iter().map(|item| MinHeapEntry {
+                                value: Candidate::Item(item),
+                                priority: item_distance(item.1),
+                            }))
+                        }
+                        NodePayload::Children(children) => {
+                            frontier.extend(children.iter().map(|child| MinHeapEntry {
+                                value: Candidate::Node(child),
+                                priority: node_distance(child.bounds),
+                            }))
+                        }
+                    },
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns the leaf whose region the given point falls into.
+    fn leaf_mut(&mut self, position: Point) -> &mut Node<T> {
+        match self.payload {
+            NodePayload::Contents(_) => self,
+            NodePayload::Children(ref mut children) => {
+                children[self.bounds.containing_quadrant_idx(position)].leaf_mut(position)
+            }
+        }
+    }
+
+    /// Inserts an item, splitting any leaf that grows beyond `split_threshold` items
+    /// unless `max_depth` has been reached.
+    fn insert(&mut self, item: (T, Point), max_depth: usize, split_threshold: usize) {
+        match &mut self.payload {
+            NodePayload::Children(children) => {
+                let idx = self.bounds.containing_quadrant_idx(item.1);
+                children[idx].insert(item, max_depth - 1, split_threshold);
+            }
+            NodePayload::Contents(items) => {
+                items.push(item);
+                let size = self.bounds.size();
+                if items.len() > split_threshold
+                    && max_depth > 0
+                    && size.x > 1
+                    && size.y > 1
+                {
+                    let items = std::mem::take(items);
+                    let children = [0, 1, 2, 3].map(|i| Node {
+                        bounds: self.bounds.quadrant(i),
+                        payload: NodePayload::Contents(vec![]),
+                    });
+                    self.payload = NodePayload::Children(Box::new(children));
+                    for item in items {
+                        self.insert(item, max_depth, split_threshold);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the first item at `position` matching `pred`, collapsing any node whose
+    /// subtree shrinks to `merge_threshold` items or fewer back into a leaf.
+    fn remove<F: FnMut(&T) -> bool>(
+        &mut self,
+        position: Point,
+        pred: &mut F,
+        merge_threshold: usize,
+    ) -> Option<T> {
+        let removed = match &mut self.payload {
+            NodePayload::Contents(items) => {
+                let idx = items
+                    .iter()
+                    .position(|(item, pt)| *pt == position && pred(item))?;
+                return Some(items.swap_remove(idx).0);
+            }
+            NodePayload::Children(children) => {
+                let idx = self.bounds.containing_quadrant_idx(position);
+                children[idx].remove(position, pred, merge_threshold)?
+            }
+        };
+        if self.count_up_to(merge_threshold + 1) <= merge_threshold {
+            let mut items = vec![];
+            if let NodePayload::Children(children) =
+                std::mem::replace(&mut self.payload, NodePayload::Contents(vec![]))
+            {
+                for child in *children {
+                    child.into_items(&mut items);
+                }
+            }
+            self.payload = NodePayload::Contents(items);
+        }
+        Some(removed)
+    }
+
+    /// Counts the items in this node's subtree, stopping once `limit` have been found.
+    ///
+    /// Every internal node holds more than the merge threshold, so checking whether a
+    /// subtree can be merged only visits a bounded part of it.
+    fn count_up_to(&self, limit: usize) -> usize {
+        match &self.payload {
+            NodePayload::Contents(items) => items.len().min(limit),
+            NodePayload::Children(children) => {
+                let mut count = 0;
+                for child in &children[..] {
+                    if count >= limit {
+                        break;
+                    }
+                    count += child.count_up_to(limit - count);
+                }
+                count
+            }
+        }
+    }
+
+    /// Returns the number of levels below this node.
+    #[cfg(feature = "serde")]
+    fn depth(&self) -> usize {
+        match &self.payload {
+            NodePayload::Contents(_) => 0,
+            NodePayload::Children(children) => {
+                1 + children.iter().map(Node::depth).max().unwrap_or(0)
+            }
+        }
+    }
+
+    /// Moves every item in this node's subtree into `out`.
+    fn into_items(self, out: &mut Vec<(T, Point)>) {
+        match self.payload {
+            NodePayload::Contents(items) => out.extend(items),
+            NodePayload::Children(children) => {
+                for child in *children {
+                    child.into_items(out);
+                }
+            }
+        }
+    }
+}
+
+/// An entry in the frontier of a best-first search through a quadtree.
+enum Candidate<'a, T> {
+    Node(&'a Node<T>),
+    Item(&'a (T, Point)),
+}
+
+/// A quadtree that supports inserting, removing and moving items after construction.
+///
+/// Leaves are split into four children once they hold more than `split_threshold` items,
+/// up to `max_depth` levels deep, and subtrees are collapsed back into a single leaf once
+/// they hold `merge_threshold` items or fewer. Keeping the merge threshold below the
+/// split threshold avoids repeatedly splitting and merging as items move back and forth.
+///
+/// Items must lie within the bounds of the tree.
+///
+/// With the `serde` feature, deserializing a tree also checks its thresholds and that it
+/// is no deeper than `max_depth`.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Debug)]
+pub struct QuadTree<T> {
+    root: Node<T>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    len: usize,
+    max_depth: usize,
+    split_threshold: usize,
+    merge_threshold: usize,
+}
+
+impl<T> QuadTree<T> {
+    /// Creates an empty quadtree covering `bounds`, splitting leaves with more than 8
+    /// items and merging subtrees with 4 or fewer.
+    pub fn new(bounds: Rect, max_depth: usize) -> QuadTree<T> {
+        QuadTree::with_thresholds(bounds, max_depth, 8, 4)
+    }
+
+    /// Creates an empty quadtree covering `bounds` with the given split and merge
+    /// thresholds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `merge_threshold` is greater than `split_threshold`.
+    pub fn with_thresholds(
+        bounds: Rect,
+        max_depth: usize,
+        split_threshold: usize,
+        merge_threshold: usize,
+    ) -> QuadTree<T> {
+        assert!(
+            merge_threshold <= split_threshold,
+            "merge threshold must not exceed split threshold"
+        );
+        QuadTree {
+            root: Node {
+                bounds,
+                payload: NodePayload::Contents(vec![]),
+            },
+            len: 0,
+            max_depth,
+            split_threshold,
+            merge_threshold,
+        }
+    }
+
+    /// Returns the root node of the tree.
+    pub fn root(&self) -> &Node<T> {
+        &self.root
+    }
+
+    /// Returns the bounds covered by the tree.
+    pub fn bounds(&self) -> Rect {
+        self.root.bounds
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the tree contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all items from the tree.
+    pub fn clear(&mut self) {
+        self.root.payload = NodePayload::Contents(vec![]);
+        self.len = 0;
+    }
+
+    /// Inserts an item at the given position.
+    ///
+    /// Returns the item back as an error if the position lies outside the tree's bounds.
+    pub fn insert(&mut self, item: T, position: Point) -> Result<(), T> {
+        if !self.root.bounds.contains(position) {
+            return Err(item);
+        }
+        self.root
+            .insert((item, position), self.max_depth, self.split_threshold);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the first item at `position` for which `pred` returns true.
+    pub fn remove_where<F: FnMut(&T) -> bool>(
+        &mut self,
+        position: Point,
+        mut pred: F,
+    ) -> Option<T> {
+        let removed = self
+            .root
+            .remove(position, &mut pred, self.merge_threshold)?;
+        self.len -= 1;
+        Some(removed)
+    }
+
+    /// Calls `f` for every item in the tree.
+    pub fn for_each<F: FnMut(&(T, Point))>(&self, mut f: F) {
+        self.root.for_each(&mut f)
+    }
+
+    /// Finds the item nearest to the query point, returning its squared distance.
+    pub fn nearest(&self, query: Point) -> Option<(i32, &(T, Point))> {
+        self.root.nearest(query, None)
+    }
+
+    /// Returns an iterator over the items in order of increasing distance from the query
+    /// point, along with their squared distances.
+    pub fn iter_nearest(
+        &self,
+        query: Point,
+    ) -> impl Iterator<Item = (i32, &(T, Point))> + '_ {
+        self.root.iter_nearest(query)
+    }
+
+    /// Finds the `k` items nearest to the query point, sorted by increasing squared
+    /// distance.
+    pub fn k_nearest(&self, query: Point, k: usize) -> Vec<(i32, &(T, Point))> {
+        self.root.k_nearest(query, k)
+    }
+
+    /// Finds the nearest item to the query point for which `pred` returns true.
+    pub fn nearest_where<F: FnMut(&T) -> bool>(
+        &self,
+        query: Point,
+        pred: F,
+    ) -> Option<(i32, &(T, Point))> {
+        self.root.nearest_where(query, pred)
+    }
+
+    /// Finds all items within `radius` of the query point under the given metric, sorted
+    /// by increasing distance.
+    pub fn within_radius<M: VectorMetric<i32, Point>>(
+        &self,
+        query: Point,
+        radius: i32,
+        metric: &M,
+    ) -> Vec<&(T, Point)> {
+        self.root.within_radius(query, radius, metric)
+    }
+
+    /// Calls `f` for every item contained within the specified rectangular region.
+    pub fn query_rect<F: FnMut(&(T, Point))>(&self, rect: Rect, mut f: F) {
+        self.root.query_rect(rect, &mut f)
+    }
+}
+
+impl<T: PartialEq> QuadTree<T> {
+    /// Removes an item equal to `item` at `position`, returning it if found.
+    pub fn remove(&mut self, item: &T, position: Point) -> Option<T> {
+        self.remove_where(position, |candidate| candidate == item)
+    }
+
+    /// Moves an item equal to `item` from `from` to `to`.
+    ///
+    /// Returns false, leaving the tree unchanged, if the item was not found at `from` or
+    /// `to` lies outside the tree's bounds.
+    pub fn relocate(&mut self, item: &T, from: Point, to: Point) -> bool {
+        if !self.root.bounds.contains(to) {
+            return false;
+        }
+        let leaf = self.root.leaf_mut(from);
+        if leaf.bounds.contains(to) {
+            // moving within a single leaf doesn't change the shape of the tree
+            if let NodePayload::Contents(items) = &mut leaf.payload {
+                return match items.iter_mut().find(|(c, pt)| *pt == from && c == item) {
+                    Some(entry) => {
+                        entry.1 = to;
+                        true
+                    }
+                    None => false,
+                };
+            }
+        }
+        match self.remove(item, from) {
+            Some(item) => self.insert(item, to).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// The serialized form of a `Node`, checked for consistency before use.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct NodeData<T> {
+    bounds: Rect,
+    payload: NodePayload<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Node<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let NodeData { bounds, payload } = NodeData::deserialize(deserializer)?;
+        // children have already checked their own subtrees
+        let valid = match &payload {
+            NodePayload::Contents(items) => {
+                items.iter().all(|(_, pt)| bounds.contains(*pt))
+            }
+            NodePayload::Children(children) => {
+                (0..4).all(|i| children[i].bounds == bounds.quadrant(i))
+            }
+        };
+        if !valid {
+            return Err(serde::de::Error::custom(
+                "quadtree node contents do not match its bounds",
+            ));
+        }
+        Ok(Node { bounds, payload })
+    }
+}
+
+/// The serialized form of a `QuadTree`, checked for consistency before use.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct QuadTreeData<T> {
+    root: Node<T>,
+    max_depth: usize,
+    split_threshold: usize,
+    merge_threshold: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for QuadTree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = QuadTreeData::deserialize(deserializer)?;
+        if data.merge_threshold > data.split_threshold {
+            return Err(serde::de::Error::custom(
+                "merge threshold must not exceed split threshold",
+            ));
+        }
+        if data.root.depth() > data.max_depth {
+            return Err(serde::de::Error::custom(
+                "quadtree is deeper than its max depth",
+            ));
+        }
+        Ok(QuadTree {
+            len: data.root.len(),
+            root: data.root,
+            max_depth: data.max_depth,
+            split_threshold: data.split_threshold,
+            merge_threshold: data.merge_threshold,
+        })
+    }
+}
+
+/// Builds a quadtree from a collection of items with associated positions.
+///
+/// This function recursively constructs a quadtree by dividing space into quadrants
+/// and distributing items among those quadrants. The process continues until either:
+/// - A node contains 0 or 1 items
+/// - The maximum depth limit is reached
+/// - All items in a node are at the same position
+///
+/// # Arguments
+/// * `items` - A vector of items with their associated 2D positions
+/// * `bounds` - The rectangular bounds of the entire space
+/// * `max_depth` - The maximum recursion depth for tree construction
+///
+/// # Returns
+/// A quadtree node representing the root of the constructed tree
+///
+/// # Example
+/// ```
+/// use scoundrel_geometry::{quadtree, Point, Rect};
+///
+/// // Create some items with positions
+/// let items = vec![(1, Point::new(1, 1)), (2, Point::new(8, 8))];
+///
+/// // Build a quadtree with the items
+/// let tree = quadtree::build_quadtree(
+///     items,
+///     Rect::with_points(Point::new(0, 0), Point::new(10, 10)),
+///     2
+/// );
+///
+/// // Use the tree for spatial queries
+/// ```
+pub fn build_quadtree<T>(
+    items: Vec<(T, Point)>,
+    bounds: Rect,
+    max_depth: usize,
+) -> Node<T> {
+    if items.len() <= 1 || max_depth == 0 {
+        return Node {
+            bounds,
+            payload: NodePayload::Contents(items),
+        };
+    }
+
+    let mut quadrant_contents = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+    for item in items {
+        let idx = bounds.containing_quadrant_idx(item.1);
+        quadrant_contents[idx].push(item);
+    }
+
+    let mut children = Vec::with_capacity(4);
+    for (i, contents) in quadrant_contents.into_iter().enumerate() {
+        let child_node = build_quadtree(contents, bounds.quadrant(i), max_depth - 1);
+        children.push(child_node);
+    }
+
+    let bs = children.into_boxed_slice();
+    let ti = bs.try_into();
+    let children = ti.map_err(|_| "whoops").unwrap();
+    Node {
+        bounds,
+        payload: NodePayload::Children(children), //Box::new(children.into_iter().collect())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{depth, scattered_tree};
+
+    #[test]
+    fn test_node_nearest() {
+        let node = Node {
+            bounds: Rect::with_points(Point::new(-5, -5), Point::new(5, 5)),
+            payload: NodePayload::Contents(vec![
+                (0, Point::new(-3, -3)),
+                (1, Point::new(3, 3)),
+            ]),
+        };
+
+        // Test nearest to a point that is closer to the point at (-3, -3)
+        let query1 = Point::new(-4, -4);
+        let nearest1 = node.nearest(query1, None).unwrap().1;
+        assert_eq!(nearest1, &(0, Point::new(-3, -3)));
+
+        // Test nearest to a point that is closer to the point at (3, 3)
+        let query2 = Point::new(4, 4);
+        let nearest2 = node.nearest(query2, None).unwrap().1;
+        assert_eq!(nearest2, &(1, Point::new(3, 3)));
+
+        // Test nearest to a point that is equidistant to both points
+        let query3 = Point::new(0, 0);
+        let nearest3 = node.nearest(query3, None).unwrap().1;
+        assert!(
+            nearest3 == &(0, Point::new(-3, -3)) || nearest3 == &(1, Point::new(3, 3))
+        );
+    }
+
+    #[test]
+    fn test_node_query_rect() {
+        let node = Node {
+            bounds: Rect::with_points(Point::new(0, 0), Point::new(100, 100)),
+            payload: NodePayload::Contents(vec![
+                (0, Point::new(50, 50)),
+                (1, Point::new(25, 25)),
+            ]),
+        };
+
+        let mut results = Vec::new();
+        node.query_rect(
+            Rect::with_points(Point::new(0, 0), Point::new(40, 40)),
+            &mut |&(id, _)| {
+                results.push(id);
+            },
+        );
+        assert_eq!(results, vec![1]);
+
+        results.clear();
+        node.query_rect(
+            Rect::with_points(Point::new(30, 30), Point::new(70, 70)),
+            &mut |&(id, _)| {
+                results.push(id);
+            },
+        );
+        assert_eq!(results, vec![0]);
+    }
+
+    #[test]
+    fn test_node_query_rect_empty() {
+        let node = Node {
+            bounds: Rect::with_points(Point::new(0, 0), Point::new(100, 100)),
+            payload: NodePayload::Contents(vec![
+                (0, Point::new(50, 50)),
+                (1, Point::new(25, 25)),
+            ]),
+        };
+
+        let mut results = Vec::new();
+        node.query_rect(
+            Rect::with_points(Point::new(200, 200), Point::new(300, 300)),
+            &mut |&(id, _)| {
+                results.push(id);
+            },
+        );
+        assert_eq!(results, vec![]);
+    }
+
+    #[test]
+    fn test_node_query_rect_nested() {
+        let node = Node {
+            bounds: Rect::with_points(Point::new(0, 0), Point::new(100, 100)),
+            payload: NodePayload::Children(Box::new([
+                Node {
+                    bounds: Rect::with_points(Point::new(50, 50), Point::new(100, 100)),
+                    payload: NodePayload::Contents(vec![(3, Point::new(74, 74))]),
+                },
+                Node {
+                    bounds: Rect::with_points(Point::new(0, 50), Point::new(50, 100)),
+                    payload: NodePayload::Contents(vec![(2, Point::new(25, 74))]),
+                },
+                Node {
+                    bounds: Rect::with_points(Point::new(0, 0), Point::new(50, 50)),
+                    payload: NodePayload::Contents(vec![(0, Point::new(25, 25))]),
+                },
+                Node {
+                    bounds: Rect::with_points(Point::new(50, 0), Point::new(100, 50)),
+                    payload: NodePayload::Contents(vec![(1, Point::new(74, 25))]),
+                },
+            ])),
+        };
+
+        let mut results = Vec::new();
+        node.query_rect(
+            Rect::with_points(Point::new(25, 25), Point::new(75, 75)),
+            &mut |&(id, _)| {
+                results.push(id);
+            },
+        );
+        results.sort();
+        assert_eq!(results, vec![0, 1, 2, 3]);
+
+        results.clear();
+        node.query_rect(
+            Rect::with_points(Point::new(0, 0), Point::new(26, 26)),
+            &mut |&(id, _)| {
+                results.push(id);
+            },
+        );
+        assert_eq!(results, vec![0]);
+    }
+
+    #[test]
+    fn test_build_quadtree() {
+        let items = vec![
+            (1, Point { x: 0, y: 0 }),
+            (2, Point { x: 2, y: 2 }),
+            (3, Point { x: 4, y: 4 }),
+            (4, Point { x: 6, y: 6 }),
+        ];
+        let bounds = Rect {
+            min: Point { x: 0, y: 0 },
+            max: Point { x: 8, y: 8 },
+        };
+        let max_depth = 2;
+
+        let quadtree = build_quadtree(items, bounds, max_depth);
+
+        assert_eq!(
+            quadtree.bounds,
+            Rect {
+                min: Point { x: 0, y: 0 },
+                max: Point { x: 8, y: 8 },
+            }
+        );
+
+        if let NodePayload::Children(children) = &quadtree.payload {
+            assert_eq!(
+                children[0].bounds,
+                Rect {
+                    min: Point { x: 4, y: 4 },
+                    max: Point { x: 8, y: 8 },
+                }
+            );
+            if let NodePayload::Contents(contents) = &children[0].payload {
+                assert_eq!(
+                    contents,
+                    &[(3, Point { x: 4, y: 4 }), (4, Point { x: 6, y: 6 })]
+                );
+            }
+
+            assert_eq!(
+                children[1].bounds,
+                Rect {
+                    min: Point { x: 0, y: 4 },
+                    max: Point { x: 4, y: 8 },
+                }
+            );
+            if let NodePayload::Contents(contents) = &children[1].payload {
+                assert_eq!(contents, &[]);
+            }
+
+            assert_eq!(
+                children[2].bounds,
+                Rect {
+                    min: Point { x: 0, y: 0 },
+                    max: Point { x: 4, y: 4 },
+                }
+            );
+            if let NodePayload::Contents(contents) = &children[2].payload {
+                assert_eq!(
+                    contents,
+                    &[(1, Point { x: 0, y: 0 }), (2, Point { x: 2, y: 2 })]
+                );
+            }
+
+            assert_eq!(
+                children[3].bounds,
+                Rect {
+                    min: Point { x: 4, y: 0 },
+                    max: Point { x: 8, y: 4 },
+                }
+            );
+            if let NodePayload::Contents(contents) = &children[3].payload {
+                assert_eq!(contents, &[]);
+            }
+        } else {
+            panic!("expected quadtree with children");
+        }
+    }
+
+    #[test]
+    fn test_empty_quadtree() {
+        // Test building an empty quadtree
+        let items: Vec<(i32, Point)> = vec![];
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(10, 10));
+        let tree = build_quadtree(items, bounds, 3);
+
+        // Check it's a leaf node with no contents
+        if let NodePayload::Contents(contents) = &tree.payload {
+            assert!(contents.is_empty());
+        } else {
+            panic!("Expected empty quadtree to be a leaf node");
+        }
+
+        // Test queries on empty tree
+        let mut results = Vec::new();
+        tree.query_rect(
+            Rect::with_points(Point::new(0, 0), Point::new(10, 10)),
+            &mut |&(id, _)| {
+                results.push(id);
+            },
+        );
+        assert!(results.is_empty());
+
+        // Test nearest on empty tree
+        let nearest = tree.nearest(Point::new(5, 5), None);
+        assert!(nearest.is_none());
+    }
+
+    #[test]
+    fn test_build_quadtree_max_depth_zero() {
+        // With max_depth = 0, should create a leaf node regardless of number of items
+        let items = vec![
+            (1, Point::new(1, 1)),
+            (2, Point::new(5, 5)),
+            (3, Point::new(8, 8)),
+        ];
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(10, 10));
+        let tree = build_quadtree(items.clone(), bounds, 0);
+
+        // Check it's a leaf node containing all items
+        if let NodePayload::Contents(contents) = &tree.payload {
+            assert_eq!(contents.len(), 3);
+            assert!(contents.contains(&(1, Point::new(1, 1))));
+            assert!(contents.contains(&(2, Point::new(5, 5))));
+            assert!(contents.contains(&(3, Point::new(8, 8))));
+        } else {
+            panic!("Expected tree with max_depth=0 to be a leaf node");
+        }
+    }
+
+    #[test]
+    fn test_all_points_in_same_quadrant() {
+        // All points in bottom-left quadrant
+        let items = vec![
+            (1, Point::new(1, 1)),
+            (2, Point::new(2, 2)),
+            (3, Point::new(3, 3)),
+        ];
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(10, 10));
+        let tree = build_quadtree(items, bounds, 1);
+
+        // Should have children, but only one populated
+        if let NodePayload::Children(children) = &tree.payload {
+            // Check bottom-left quadrant (index 2)
+            if let NodePayload::Contents(contents) = &children[2].payload {
+                assert_eq!(contents.len(), 3);
+            } else {
+                panic!("Expected bottom-left child to be a leaf node");
+            }
+
+            // Check other quadrants are empty
+            for i in [0, 1, 3] {
+                if let NodePayload::Contents(contents) = &children[i].payload {
+                    assert!(contents.is_empty());
+                } else {
+                    panic!("Expected empty quadrant to be a leaf node");
+                }
+            }
+        } else {
+            panic!("Expected tree to have children");
+        }
+    }
+
+    #[test]
+    fn test_nearest_with_priority() {
+        // Test nearest when a best candidate is already provided
+        let node = Node {
+            bounds: Rect::with_points(Point::new(0, 0), Point::new(10, 10)),
+            payload: NodePayload::Contents(vec![
+                (1, Point::new(5, 4)),
+                (2, Point::new(7, 7)),
+            ]),
+        };
+
+        // Query point is at (6,6)
+        let query = Point::new(6, 6);
+
+        // Without existing best candidate, (7,7) should be closest
+        let nearest = node.nearest(query, None).unwrap();
+        assert_eq!(nearest.1, &(2, Point::new(7, 7)));
+
+        // With existing best candidate very close to query point, should keep that one
+        let best_candidate = (0, &(0, Point::new(6, 6))); // Distance = 0
+        let nearest_with_best = node.nearest(query, Some(best_candidate)).unwrap();
+        assert_eq!(nearest_with_best.1, &(0, Point::new(6, 6)));
+    }
+
+    #[test]
+    fn test_nearest_point_outside_bounds() {
+        // Test with query point outside the bounds
+        let node = Node {
+            bounds: Rect::with_points(Point::new(0, 0), Point::new(10, 10)),
+            payload: NodePayload::Contents(vec![
+                (1, Point::new(1, 1)),
+                (2, Point::new(9, 9)),
+            ]),
+        };
+
+        // Query point outside bounds
+        let query = Point::new(15, 15);
+
+        // Should still find (9,9) as closest
+        let nearest = node.nearest(query, None).unwrap();
+        assert_eq!(nearest.1, &(2, Point::new(9, 9)));
+    }
+
+    #[test]
+    fn test_nested_nearest() {
+        // More complex tree with multiple levels for nearest search
+        let tree = build_quadtree(
+            vec![
+                (0, Point::new(25, 25)),
+                (1, Point::new(75, 75)),
+                (2, Point::new(10, 90)),
+                (3, Point::new(25, 75)),
+                (4, Point::new(60, 40)),
+                (5, Point::new(60, 10)),
+            ],
+            Rect::with_points(Point::new(0, 0), Point::new(100, 100)),
+            2,
+        );
+
+        // Test queries in different areas
+        // Query in center
+        assert_eq!(tree.nearest(Point::new(50, 50), None).unwrap().1.0, 4); // (60,40) is closest
+        assert_eq!(tree.nearest(Point::new(90, 90), None).unwrap().1.0, 1); // (75,75) is closest
+        assert_eq!(tree.nearest(Point::new(100, 10), None).unwrap().1.0, 5); // (60,10) is closest
+    }
+
+    fn collect(tree: &QuadTree<u32>) -> Vec<(u32, Point)> {
+        let mut items = vec![];
+        tree.for_each(|item| items.push(*item));
+        items.sort_by_key(|(id, _)| *id);
+        items
+    }
+
+    #[test]
+    fn test_dynamic_insert_splits() {
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(64, 64));
+        let mut tree = QuadTree::with_thresholds(bounds, 4, 2, 1);
+        assert!(tree.is_empty());
+        for i in 0..32u32 {
+            let pos = Point::new((i as i32 * 7) % 64, (i as i32 * 13) % 64);
+            assert_eq!(tree.insert(i, pos), Ok(()));
+        }
+        assert_eq!(tree.len(), 32);
+        assert!(depth(tree.root()) > 0);
+        assert!(depth(tree.root()) <= 4);
+        assert_eq!(tree.insert(99, Point::new(64, 0)), Err(99));
+
+        // queries agree with a brute force scan
+        let query = Rect::with_points(Point::new(10, 10), Point::new(40, 30));
+        let mut found = vec![];
+        tree.query_rect(query, |&(id, _)| found.push(id));
+        found.sort();
+        let expected: Vec<u32> = collect(&tree)
+            .into_iter()
+            .filter(|(_, pt)| query.contains(*pt))
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(found, expected);
+
+        let target = Point::new(33, 33);
+        let (dist, _) = tree.nearest(target).unwrap();
+        let best = collect(&tree)
+            .iter()
+            .map(|(_, pt)| (*pt - target).sqr_magnitude())
+            .min()
+            .unwrap();
+        assert_eq!(dist, best);
+    }
+
+    #[test]
+    fn test_dynamic_remove_merges() {
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(16, 16));
+        let mut tree = QuadTree::with_thresholds(bounds, 3, 2, 2);
+        let items: Vec<(u32, Point)> = (0..10)
+            .map(|i| (i, Point::new(i as i32, (i as i32 * 5) % 16)))
+            .collect();
+        for (id, pos) in &items {
+            tree.insert(*id, *pos).unwrap();
+        }
+        assert!(depth(tree.root()) > 0);
+
+        assert_eq!(tree.remove(&3, Point::new(0, 0)), None);
+        for (id, pos) in &items[..8] {
+            assert_eq!(tree.remove(id, *pos), Some(*id));
+        }
+        assert_eq!(collect(&tree), items[8..].to_vec());
+        assert_eq!(depth(tree.root()), 0);
+
+        assert_eq!(tree.remove_where(items[8].1, |_| true), Some(8));
+        tree.clear();
+        assert!(tree.is_empty());
+        assert!(tree.nearest(Point::new(0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_dynamic_relocate() {
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(32, 32));
+        let mut tree = QuadTree::with_thresholds(bounds, 4, 1, 0);
+        tree.insert(1, Point::new(1, 1)).unwrap();
+        tree.insert(2, Point::new(30, 30)).unwrap();
+        tree.insert(3, Point::new(2, 2)).unwrap();
+
+        // a short move within a leaf and a long move across the tree
+        assert!(tree.relocate(&3, Point::new(2, 2), Point::new(3, 2)));
+        assert!(tree.relocate(&1, Point::new(1, 1), Point::new(29, 31)));
+        assert!(!tree.relocate(&1, Point::new(1, 1), Point::new(5, 5)));
+        assert!(!tree.relocate(&2, Point::new(30, 30), Point::new(40, 40)));
+        assert_eq!(
+            collect(&tree),
+            vec![
+                (1, Point::new(29, 31)),
+                (2, Point::new(30, 30)),
+                (3, Point::new(3, 2)),
+            ]
+        );
+        assert_eq!(tree.nearest(Point::new(28, 31)).unwrap().1.0, 1);
+        assert_eq!(tree.nearest(Point::new(0, 0)).unwrap().1.0, 3);
+    }
+
+    /// Checks that every internal node holds more than `merge_threshold` items.
+    fn assert_merged<T>(node: &Node<T>, merge_threshold: usize) {
+        if let NodePayload::Children(children) = node.payload() {
+            assert!(node.len() > merge_threshold);
+            for child in &children[..] {
+                assert_merged(child, merge_threshold);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_len_tracks_edits() {
+        let bounds = Rect::with_points(Point::new(0, 0), Point::new(64, 64));
+        let mut tree = QuadTree::with_thresholds(bounds, 5, 3, 2);
+        let mut positions: Vec<Point> = (0..40)
+            .map(|i| Point::new((i * 37) % 64, (i * 23 + 5) % 64))
+            .collect();
+        for (id, pos) in positions.iter().enumerate() {
+            tree.insert(id, *pos).unwrap();
+        }
+        for step in 0..200 {
+            let id = (step * 7) % positions.len();
+            let to = Point::new((step as i32 * 11) % 64, (step as i32 * 29) % 64);
+            assert!(tree.relocate(&id, positions[id], to));
+            positions[id] = to;
+        }
+        assert_eq!(tree.len(), 40);
+        assert_eq!(tree.root().len(), 40);
+        assert_merged(tree.root(), 2);
+
+        for (id, pos) in positions.iter().enumerate().skip(2) {
+            assert_eq!(tree.remove(&id, *pos), Some(id));
+            assert_eq!(tree.len(), tree.root().len());
+            assert_merged(tree.root(), 2);
+        }
+        assert_eq!(tree.len(), 2);
+        assert_eq!(depth(tree.root()), 0);
+    }
+
+    #[test]
+    fn test_k_nearest() {
+        let tree = scattered_tree();
+        let query = Point::new(20, 40);
+        let mut expected: Vec<i32> = collect(&tree)
+            .iter()
+            .map(|(_, pt)| (*pt - query).sqr_magnitude())
+            .collect();
+        expected.sort();
+
+        let found = tree.k_nearest(query, 10);
+        assert_eq!(found.len(), 10);
+        for (dist, (_, pt)) in &found {
+            assert_eq!(*dist, (*pt - query).sqr_magnitude());
+        }
+        let dists: Vec<i32> = found.iter().map(|(dist, _)| *dist).collect();
+        assert_eq!(dists, expected[..10]);
+
+        assert_eq!(tree.k_nearest(query, 100).len(), 60);
+        assert!(tree.k_nearest(query, 0).is_empty());
+        assert_eq!(tree.iter_nearest(query).count(), 60);
+    }
+
+    #[test]
+    fn test_nearest_where() {
+        let tree = scattered_tree();
+        let query = Point::new(50, 3);
+        let (dist, (id, pt)) = tree.nearest_where(query, |id| id % 7 == 3).unwrap();
+        assert_eq!(id % 7, 3);
+        let best = collect(&tree)
+            .iter()
+            .filter(|(id, _)| id % 7 == 3)
+            .map(|(_, pt)| (*pt - query).sqr_magnitude())
+            .min()
+            .unwrap();
+        assert_eq!(dist, best);
+        assert_eq!((*pt - query).sqr_magnitude(), best);
+        assert!(tree.nearest_where(query, |id| *id > 100).is_none());
+    }
+
+    #[test]
+    fn test_within_radius() {
+        use crate::metric::{Chebyshev, Euclidean, Manhattan};
+
+        fn check<M: VectorMetric<i32, Point>>(tree: &QuadTree<u32>, metric: M) {
+            let query = Point::new(31, 17);
+            for radius in [0, 5, 13, 20] {
+                let found = tree.within_radius(query, radius, &metric);
+                let dists: Vec<i32> = found
+                    .iter()
+                    .map(|(_, pt)| metric.distance_fast_monotonic(query, *pt))
+                    .collect();
+                assert!(dists.windows(2).all(|w| w[0] <= w[1]));
+
+                let mut ids: Vec<u32> = found.iter().map(|(id, _)| *id).collect();
+                ids.sort();
+                let limit = metric
+                    .distance_fast_monotonic(Point::new(0, 0), Point::new(radius, 0));
+                let expected: Vec<u32> = collect(tree)
+                    .into_iter()
+                    .filter(|(_, pt)| metric.distance_fast_monotonic(query, *pt) <= limit)
+                    .map(|(id, _)| id)
+                    .collect();
+                assert_eq!(ids, expected);
+            }
+        }
+
+        let tree = scattered_tree();
+        check(&tree, Euclidean);
+        check(&tree, Manhattan);
+        check(&tree, Chebyshev);
+
+        // distances that land exactly on the radius are included
+        let mut tree =
+            QuadTree::new(Rect::with_points(Point::new(0, 0), Point::new(8, 8)), 3);
+        tree.insert(0, Point::new(3, 4)).unwrap();
+        tree.insert(1, Point::new(4, 4)).unwrap();
+        let found = tree.within_radius(Point::new(0, 0), 5, &Euclidean);
+        assert_eq!(found, vec![&(0, Point::new(3, 4))]);
+    }
+}
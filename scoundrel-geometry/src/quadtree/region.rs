@@ -0,0 +1,332 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{Point, Rect};
+
+/// A node of a `RegionQuadTree`.
+///
+/// Each item is stored at the deepest node whose bounds fully contain it, so large items
+/// and items straddling a quadrant boundary stay close to the root.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+struct RegionNode<T> {
+    bounds: Rect,
+    items: Vec<(T, Rect)>,
+    children: Option<Box<[RegionNode<T>; 4]>>,
+}
+
+impl<T> RegionNode<T> {
+    fn new(bounds: Rect) -> Self {
+        RegionNode {
+            bounds,
+            items: vec![],
+            children: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.children.is_none()
+    }
+
+    /// Returns the index of the quadrant that fully contains `rect`, if any.
+    fn child_idx(&self, rect: Rect) -> Option<usize> {
+        (0..4).find(|idx| self.bounds.quadrant(*idx).contains_bounds(&rect))
+    }
+
+    fn insert(&mut self, item: (T, Rect), max_depth: usize) {
+        let size = self.bounds.size();
+        let idx = if max_depth > 0 && size.x > 1 && size.y > 1 {
+            self.child_idx(item.1)
+        } else {
+            None
+        };
+        match idx {
+            Some(idx) => {
+                let bounds = self.bounds;
+                let children = self.children.get_or_insert_with(|| {
+                    Box::new([0, 1, 2, 3].map(|i| RegionNode::new(bounds.quadrant(i))))
+                });
+                children[idx].insert(item, max_depth - 1)
+            }
+            None => self.items.push(item),
+        }
+    }
+
+    /// Removes the first item with exactly the bounds `rect` matching `pred`, pruning
+    /// subtrees that become empty.
+    fn remove<F: FnMut(&T) -> bool>(&mut self, rect: Rect, pred: &mut F) -> Option<T> {
+        if let Some(idx) = self
+            .items
+            .iter()
+            .position(|(item, bounds)| *bounds == rect && pred(item))
+        {
+            return Some(self.items.swap_remove(idx).0);
+        }
+        let idx = self.child_idx(rect)?;
+        let children = self.children.as_mut()?;
+        let removed = children[idx].remove(rect, pred)?;
+        if children.iter().all(RegionNode::is_empty) {
+            self.children = None;
+        }
+        Some(removed)
+    }
+
+    fn query<Q: Fn(Rect) -> bool, F: FnMut(&(T, Rect))>(&self, overlaps: &Q, f: &mut F) {
+        for item in &self.items {
+            if overlaps(item.1) {
+                f(item);
+            }
+        }
+        if let Some(children) = &self.children {
+            for child in &children[..] {
+                if overlaps(child.bounds) {
+                    child.query(overlaps, f);
+                }
+            }
+        }
+    }
+
+    /// Checks that every child covers its quadrant, that items below the root lie within
+    /// their node and that no node is more than `max_depth` levels below this one.
+    #[cfg(feature = "serde")]
+    fn is_consistent(&self, max_depth: usize, is_root: bool) -> bool {
+        if !is_root
+            && !self
+                .items
+                .iter()
+                .all(|(_, rect)| self.bounds.contains_bounds(rect))
+        {
+            return false;
+        }
+        match &self.children {
+            None => true,
+            Some(children) => {
+                max_depth > 0
+                    && children.iter().enumerate().all(|(i, child)| {
+                        child.bounds == self.bounds.quadrant(i)
+                            && child.is_consistent(max_depth - 1, false)
+                    })
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+            + self
+                .children
+                .iter()
+                .flat_map(|children| children.iter())
+                .map(RegionNode::len)
+                .sum::<usize>()
+    }
+}
+
+/// A quadtree indexing items by their rectangular extent rather than a single point.
+///
+/// This suits objects that cover more than one tile, such as large monsters, area
+/// effects or rooms. Each item is stored at the smallest node that fully contains it, and
+/// nodes are subdivided lazily as items are inserted, up to `max_depth` levels deep.
+/// Items that extend outside the tree's bounds are kept at the root, so they are still
+/// found by queries, only less efficiently.
+///
+/// Rectangles are half-open as with `Bounds`: an item overlaps a query only if they
+/// share at least one point, so rectangles that merely touch along an edge do not.
+///
+/// With the `serde` feature, deserializing a tree checks that its nodes are consistent
+/// with their bounds and no deeper than `max_depth`.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Debug)]
+pub struct RegionQuadTree<T> {
+    root: RegionNode<T>,
+    max_depth: usize,
+}
+
+impl<T> RegionQuadTree<T> {
+    /// Creates an empty tree covering `bounds`.
+    pub fn new(bounds: Rect, max_depth: usize) -> Self {
+        RegionQuadTree {
+            root: RegionNode::new(bounds),
+            max_depth,
+        }
+    }
+
+    /// Returns the bounds covered by the tree.
+    pub fn bounds(&self) -> Rect {
+        self.root.bounds
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    /// Returns true if the tree contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    /// Removes all items from the tree.
+    pub fn clear(&mut self) {
+        self.root = RegionNode::new(self.root.bounds);
+    }
+
+    /// Inserts an item covering the given rectangle.
+    pub fn insert(&mut self, item: T, rect: Rect) {
+        self.root.insert((item, rect), self.max_depth);
+    }
+
+    /// Removes and returns the first item with bounds `rect` for which `pred` returns
+    /// true.
+    pub fn remove_where<F: FnMut(&T) -> bool>(
+        &mut self,
+        rect: Rect,
+        mut pred: F,
+    ) -> Option<T> {
+        self.root.remove(rect, &mut pred)
+    }
+
+    /// Calls `f` for every item overlapping the given rectangle.
+    pub fn query_rect<F: FnMut(&(T, Rect))>(&self, rect: Rect, mut f: F) {
+        self.root
+            .query(&|bounds: Rect| bounds.intersection(&rect).is_some(), &mut f)
+    }
+
+    /// Calls `f` for every item whose rectangle contains the given point.
+    pub fn query_point<F: FnMut(&(T, Rect))>(&self, pt: Point, mut f: F) {
+        self.root.query(&|bounds: Rect| bounds.contains(pt), &mut f)
+    }
+
+    /// Calls `f` for every item in the tree.
+    pub fn for_each<F: FnMut(&(T, Rect))>(&self, mut f: F) {
+        self.root.query(&|_| true, &mut f)
+    }
+}
+
+/// The serialized form of a `RegionQuadTree`, checked for consistency before use.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RegionQuadTreeData<T> {
+    root: RegionNode<T>,
+    max_depth: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for RegionQuadTree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RegionQuadTreeData { root, max_depth } =
+            RegionQuadTreeData::deserialize(deserializer)?;
+        if !root.is_consistent(max_depth, true) {
+            return Err(serde::de::Error::custom(
+                "region quadtree nodes do not match their bounds",
+            ));
+        }
+        Ok(RegionQuadTree { root, max_depth })
+    }
+}
+
+impl<T: PartialEq> RegionQuadTree<T> {
+    /// Removes an item equal to `item` with bounds `rect`, returning it if found.
+    pub fn remove(&mut self, item: &T, rect: Rect) -> Option<T> {
+        self.remove_where(rect, |candidate| candidate == item)
+    }
+
+    /// Moves an item equal to `item` from bounds `from` to bounds `to`.
+    ///
+    /// Returns false, leaving the tree unchanged, if the item was not found.
+    pub fn relocate(&mut self, item: &T, from: Rect, to: Rect) -> bool {
+        match self.remove(item, from) {
+            Some(item) => {
+                self.insert(item, to);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{query_ids, rect, scattered_rects};
+
+    #[test]
+    fn test_region_queries_match_brute_force() {
+        let items = scattered_rects(50, 60);
+        let mut tree = RegionQuadTree::new(rect(0, 0, 64, 64), 5);
+        for (id, bounds) in &items {
+            tree.insert(*id, *bounds);
+        }
+        assert_eq!(tree.len(), items.len());
+
+        for query in [
+            rect(0, 0, 10, 10),
+            rect(20, 30, 25, 5),
+            rect(63, 63, 10, 10),
+        ] {
+            let found = query_ids(|f| tree.query_rect(query, f));
+            let expected: Vec<u32> = items
+                .iter()
+                .filter(|(_, bounds)| bounds.intersection(&query).is_some())
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(found, expected);
+        }
+
+        for pt in [Point::new(5, 5), Point::new(32, 32), Point::new(70, 1)] {
+            let found = query_ids(|f| tree.query_point(pt, f));
+            let expected: Vec<u32> = items
+                .iter()
+                .filter(|(_, bounds)| bounds.contains(pt))
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_region_items_held_at_enclosing_node() {
+        let mut tree = RegionQuadTree::new(rect(0, 0, 16, 16), 4);
+        // straddles the center, so it can only live at the root
+        tree.insert(0, rect(6, 6, 4, 4));
+        tree.insert(1, rect(1, 1, 1, 1));
+        tree.insert(2, rect(-5, -5, 30, 30));
+        assert_eq!(tree.root.items.len(), 2);
+        assert_eq!(tree.len(), 3);
+
+        // touching edges are not overlaps
+        assert_eq!(
+            query_ids(|f| tree.query_rect(rect(10, 10, 2, 2), f)),
+            vec![2]
+        );
+        assert_eq!(
+            query_ids(|f| tree.query_point(Point::new(9, 9), f)),
+            vec![0, 2]
+        );
+        assert_eq!(
+            query_ids(|f| tree.query_point(Point::new(-3, -3), f)),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_region_remove_and_relocate() {
+        let mut tree = RegionQuadTree::new(rect(0, 0, 32, 32), 4);
+        tree.insert(1, rect(1, 1, 2, 2));
+        tree.insert(2, rect(20, 20, 3, 3));
+        assert_eq!(tree.remove(&1, rect(1, 1, 2, 3)), None);
+        assert_eq!(tree.remove(&1, rect(1, 1, 2, 2)), Some(1));
+        assert_eq!(tree.len(), 1);
+
+        assert!(tree.relocate(&2, rect(20, 20, 3, 3), rect(2, 2, 3, 3)));
+        assert!(!tree.relocate(&2, rect(20, 20, 3, 3), rect(2, 2, 3, 3)));
+        assert_eq!(
+            query_ids(|f| tree.query_point(Point::new(4, 4), f)),
+            vec![2]
+        );
+        assert!(query_ids(|f| tree.query_point(Point::new(21, 21), f)).is_empty());
+
+        assert_eq!(tree.remove_where(rect(2, 2, 3, 3), |_| true), Some(2));
+        assert!(tree.is_empty());
+        assert!(tree.root.children.is_none());
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::BinaryHeap;
+
+use scoundrel_util::MinHeapEntry;
+
+use crate::{Point, Rect};
+
+/// A node of an `RTree`, covering a contiguous range of either items or child nodes.
+#[derive(Debug, Clone)]
+struct RNode {
+    bounds: Rect,
+    first: usize,
+    len: usize,
+    leaf: bool,
+}
+
+/// An entry in the frontier of a best-first search through an `RTree`.
+enum Candidate {
+    Node(usize),
+    Item(usize),
+}
+
+/// A static R-tree over rectangular items, bulk loaded with Sort-Tile-Recursive packing.
+///
+/// STR packing sorts items into vertical slices by center x, then sorts each slice by
+/// center y, producing tightly packed leaves with little overlap. The tree cannot be
+/// modified after construction, which makes it a good fit for static level geometry such
+/// as furniture, light sources and trigger zones. For items that move every turn, use
+/// `quadtree::QuadTree` or `quadtree::RegionQuadTree` instead. The `spatial_index`
+/// benchmark compares these indexes on a given workload.
+///
+/// Rectangles are half-open as with `Bounds`; empty rectangles are stored but never
+/// overlap anything.
+#[derive(Debug, Clone)]
+pub struct RTree<T> {
+    items: Vec<(T, Rect)>,
+    nodes: Vec<RNode>,
+}
+
+impl<T> RTree<T> {
+    /// Builds a tree from the given items, with at most `node_capacity` entries per node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node_capacity` is less than 2.
+    pub fn new(items: Vec<(T, Rect)>, node_capacity: usize) -> RTree<T> {
+        assert!(node_capacity >= 2, "node capacity must be at least 2");
+        if items.is_empty() {
+            return RTree {
+                items,
+                nodes: vec![],
+            };
+        }
+
+        let order = str_order(
+            &items.iter().map(|(_, r)| *r).collect::<Vec<_>>(),
+            node_capacity,
+        );
+        let mut slots: Vec<Option<(T, Rect)>> = items.into_iter().map(Some).collect();
+        let items: Vec<(T, Rect)> = order
+            .iter()
+            .map(|idx| slots[*idx].take().unwrap())
+            .collect();
+
+        let mut level: Vec<RNode> = items
+            .chunks(node_capacity)
+            .enumerate()
+            .map(|(i, chunk)| RNode {
+                bounds: union_all(chunk.iter().map(|(_, r)| *r)),
+                first: i * node_capacity,
+                len: chunk.len(),
+                leaf: true,
+            })
+            .collect();
+
+        let mut nodes = vec![];
+        while level.len() > 1 {
+            let bounds: Vec<Rect> = level.iter().map(|n| n.bounds).collect();
+            let order = str_order(&bounds, node_capacity);
+            let start = nodes.len();
+            nodes.extend(order.iter().map(|idx| level[*idx].clone()));
+            level = nodes[start..]
+                .chunks(node_capacity)
+                .enumerate()
+                .map(|(i, chunk)| RNode {
+                    bounds: union_all(chunk.iter().map(|n| n.bounds)),
+                    first: start + i * node_capacity,
+                    len: chunk.len(),
+                    leaf: false,
+                })
+                .collect();
+        }
+        nodes.extend(level);
+        RTree { items, nodes }
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the tree contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the bounds of all items in the tree, or `None` if it is empty.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.nodes.last().map(|root| root.bounds)
+    }
+
+    /// Returns an iterator over all items in the tree, in storage order.
+    pub fn iter(&self) -> impl Iterator<Item = &(T, Rect)> {
+        self.items.iter()
+    }
+
+    /// Calls `f` for every item overlapping the given rectangle.
+    pub fn query_rect<F: FnMut(&(T, Rect))>(&self, rect: Rect, mut f: F) {
+        self.query(&|bounds: Rect| bounds.intersection(&rect).is_some(), &mut f)
+    }
+
+    /// Calls `f` for every item whose rectangle contains the given point.
+    pub fn query_point<F: FnMut(&(T, Rect))>(&self, pt: Point, mut f: F) {
+        self.query(&|bounds: Rect| bounds.contains(pt), &mut f)
+    }
+
+    /// Finds the item nearest to the query point, returning its squared distance.
+    ///
+    /// The distance to an item is measured to the closest point inside its rectangle, so
+    /// it is zero for items containing the query point.
+    pub fn nearest(&self, query: Point) -> Option<(i32, &(T, Rect))> {
+        self.iter_nearest(query).next()
+    }
+
+    /// Returns an iterator over the items in order of increasing distance from the query
+    /// point, along with their squared distances.
+    pub fn iter_nearest(
+        &self,
+        query: Point,
+    ) -> impl Iterator<Item = (i32, &(T, Rect))> + '_ {
+        let mut frontier = BinaryHeap::new();
+        if let Some(root) = self.nodes.last() {
+            frontier.push(MinHeapEntry {
+                value: Candidate::Node(self.nodes.len() - 1),
+                priority: sqr_distance(root.bounds, query),
+            });
+        }
+        std::iter::from_fn(move || {
+            while let Some(MinHeapEntry { value, priority }) = frontier.pop() {
+                let node = match value {
+                    Candidate::Item(idx) => return Some((priority, &self.items[idx])),
+                    Candidate::Node(idx) => &self.nodes[idx],
+                };
+                let range = node.first..node.first + node.len;
+                if node.leaf {
+                    frontier.extend(range.map(|idx| MinHeapEntry {
+                        value: Candidate::Item(idx),
+                        priority: sqr_distance(self.items[idx].1, query),
+                    }));
+                } else {
+                    frontier.extend(range.map(|idx| MinHeapEntry {
+                        value: Candidate::Node(idx),
+                        priority: sqr_distance(self.nodes[idx].bounds, query),
+                    }));
+                }
+            }
+            None
+        })
+    }
+
+    /// Calls `f` for every item matching `filter`, skipping nodes whose bounds do not.
+    ///
+    /// `filter` must match a node's bounds whenever it matches any rectangle within them.
+    fn query<Q: Fn(Rect) -> bool, F: FnMut(&(T, Rect))>(&self, filter: &Q, f: &mut F) {
+        let Some(root) = self.nodes.len().checked_sub(1) else {
+            return;
+        };
+        let mut stack = vec![root];
+        while let Some(idx) = stack.pop() {
+            let node = &self.nodes[idx];
+            if !filter(node.bounds) {
+                continue;
+            }
+            let range = node.first..node.first + node.len;
+            if node.leaf {
+                for item in &self.items[range] {
+                    if filter(item.1) {
+                        f(item);
+                    }
+                }
+            } else {
+                stack.extend(range);
+            }
+        }
+    }
+}
+
+/// Returns the squared distance from `pt` to the closest point inside `rect`.
+fn sqr_distance(rect: Rect, pt: Point) -> i32 {
+    let dx = (rect.min.x - pt.x).max(pt.x - (rect.max.x - 1)).max(0);
+    let dy = (rect.min.y - pt.y).max(pt.y - (rect.max.y - 1)).max(0);
+    dx * dx + dy * dy
+}
+
+/// Returns the smallest rectangle containing all of the given rectangles.
+fn union_all<I: Iterator<Item = Rect>>(mut rects: I) -> Rect {
+    let first = rects
+        .next()
+        .expect("cannot take the union of no rectangles");
+    rects.fold(first, |acc, r| {
+        Rect::with_points(
+            Point::new(acc.min.x.min(r.min.x), acc.min.y.min(r.min.y)),
+            Point::new(acc.max.x.max(r.max.x), acc.max.y.max(r.max.y)),
+        )
+    })
+}
+
+/// Returns the order in which to pack rectangles into nodes of `capacity` entries using
+/// Sort-Tile-Recursive.
+fn str_order(rects: &[Rect], capacity: usize) -> Vec<usize> {
+    // centers are compared doubled to avoid rounding
+    let center = |idx: usize| rects[idx].min + rects[idx].max;
+    let node_count = rects.len().div_ceil(capacity);
+    let slice_count = (node_count as f64).sqrt().ceil() as usize;
+    let slice_len = slice_count * capacity;
+
+    let mut order: Vec<usize> = (0..rects.len()).collect();
+    order.sort_by_key(|idx| (center(*idx).x, center(*idx).y));
+    for slice in order.chunks_mut(slice_len) {
+        slice.sort_by_key(|idx| (center(*idx).y, center(*idx).x));
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{query_ids, rect, scattered_rects};
+
+    #[test]
+    fn test_rtree_structure() {
+        let tree = RTree::new(scattered_rects(200, 90), 4);
+        assert_eq!(tree.len(), 200);
+        let mut ids: Vec<u32> = tree.iter().map(|(id, _)| *id).collect();
+        ids.sort();
+        assert_eq!(ids, (0..200).collect::<Vec<_>>());
+
+        // every node's bounds cover its entries
+        for node in &tree.nodes {
+            assert!(node.len >= 1 && node.len <= 4);
+            let range = node.first..node.first + node.len;
+            if node.leaf {
+                assert!(
+                    tree.items[range]
+                        .iter()
+                        .all(|(_, r)| node.bounds.contains_bounds(r))
+                );
+            } else {
+                assert!(
+                    tree.nodes[range]
+                        .iter()
+                        .all(|child| node.bounds.contains_bounds(&child.bounds))
+                );
+            }
+        }
+        assert_eq!(tree.bounds(), Some(tree.nodes.last().unwrap().bounds));
+    }
+
+    #[test]
+    fn test_rtree_queries_match_brute_force() {
+        let items = scattered_rects(300, 90);
+        let tree = RTree::new(items.clone(), 6);
+
+        for query in [
+            rect(0, 0, 10, 10),
+            rect(40, 20, 30, 7),
+            rect(95, 95, 10, 10),
+        ] {
+            let expected: Vec<u32> = items
+                .iter()
+                .filter(|(_, r)| r.intersection(&query).is_some())
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(query_ids(|f| tree.query_rect(query, f)), expected);
+        }
+
+        for pt in [Point::new(3, 3), Point::new(50, 41), Point::new(-5, 10)] {
+            let expected: Vec<u32> = items
+                .iter()
+                .filter(|(_, r)| r.contains(pt))
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(query_ids(|f| tree.query_point(pt, f)), expected);
+
+            let best = items
+                .iter()
+                .map(|(_, r)| sqr_distance(*r, pt))
+                .min()
+                .unwrap();
+            let (dist, (_, r)) = tree.nearest(pt).unwrap();
+            assert_eq!(dist, best);
+            assert_eq!(sqr_distance(*r, pt), best);
+
+            let dists: Vec<i32> = tree.iter_nearest(pt).map(|(d, _)| d).collect();
+            assert_eq!(dists.len(), items.len());
+            assert!(dists.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test]
+    fn test_rtree_small() {
+        let empty: RTree<u32> = RTree::new(vec![], 4);
+        assert!(empty.is_empty());
+        assert_eq!(empty.bounds(), None);
+        assert!(empty.nearest(Point::new(0, 0)).is_none());
+        assert!(query_ids(|f| empty.query_rect(rect(0, 0, 10, 10), f)).is_empty());
+
+        let single = RTree::new(vec![(7, rect(2, 2, 3, 3))], 4);
+        assert_eq!(single.bounds(), Some(rect(2, 2, 3, 3)));
+        assert_eq!(single.nearest(Point::new(4, 4)).unwrap().0, 0);
+        assert_eq!(single.nearest(Point::new(7, 4)).unwrap().0, 9);
+        assert_eq!(
+            query_ids(|f| single.query_point(Point::new(4, 4), f)),
+            vec![7]
+        );
+        assert!(query_ids(|f| single.query_point(Point::new(5, 4), f)).is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use crate::{GridAccess, Point, Rect};
+
+/// A grid that only stores the cells holding a value.
+///
+/// This is suited to mostly empty layers such as items or traps, where a dense
+/// `Grid2D<Option<T>>` would spend most of its memory on empty cells. Like `Grid2D`, the
+/// grid covers a fixed `rect`, and values cannot be set outside of it.
+#[derive(Clone)]
+pub struct SparseGrid<T> {
+    rect: Rect,
+    cells: HashMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Creates an empty grid covering the given region.
+    pub fn new(rect: Rect) -> Self {
+        SparseGrid {
+            rect,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty grid of the specified dimensions, with its top-left corner at the
+    /// origin.
+    pub fn with_size(width: i32, height: i32) -> Self {
+        SparseGrid::new(Rect::with_size(Point::zero(), Point::new(width, height)))
+    }
+
+    /// Returns the region of space covered by the grid.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the number of cells holding a value.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if no cells hold a value.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns a reference to the value at the given point, if there is one.
+    pub fn get(&self, pt: Point) -> Option<&T> {
+        self.cells.get(&pt)
+    }
+
+    /// Returns a mutable reference to the value at the given point, if there is one.
+    pub fn get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        self.cells.get_mut(&pt)
+    }
+
+    /// Sets the value at the specified position.
+    ///
+    /// # Returns
+    /// `true` if the position is within the grid and the value was set, `false` otherwise.
+    pub fn set(&mut self, pt: Point, value: T) -> bool {
+        if self.rect.contains(pt) {
+            self.cells.insert(pt, value);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the value at the given point, returning it if there was one.
+    pub fn remove(&mut self, pt: Point) -> Option<T> {
+        self.cells.remove(&pt)
+    }
+
+    /// Removes all values from the grid.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Returns an iterator over the positions and values of every cell holding a value.
+    ///
+    /// The order of iteration is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(pt, value)| (*pt, value))
+    }
+}
+
+impl<T> GridAccess<T> for SparseGrid<T> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn get(&self, pt: Point) -> Option<&T> {
+        SparseGrid::get(self, pt)
+    }
+
+    fn get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        SparseGrid::get_mut(self, pt)
+    }
+
+    fn set(&mut self, pt: Point, value: T) -> bool {
+        SparseGrid::set(self, pt, value)
+    }
+
+    fn iter_cells<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grid2D;
+
+    #[test]
+    fn test_set_and_get() {
+        let mut grid = SparseGrid::with_size(100, 100);
+        assert!(grid.is_empty());
+        assert!(grid.set(Point::new(3, 4), 'x'));
+        assert!(!grid.set(Point::new(100, 4), 'x'));
+        assert!(!grid.set(Point::new(-1, 4), 'x'));
+        assert_eq!(grid.len(), 1);
+        assert_eq!(grid.get(Point::new(3, 4)), Some(&'x'));
+        assert_eq!(grid.get(Point::new(4, 3)), None);
+
+        *grid.get_mut(Point::new(3, 4)).unwrap() = 'y';
+        assert_eq!(grid.remove(Point::new(3, 4)), Some('y'));
+        assert_eq!(grid.remove(Point::new(3, 4)), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_offset_rect() {
+        let rect = Rect::with_points(Point::new(-10, -10), Point::new(0, 0));
+        let mut grid = SparseGrid::new(rect);
+        assert!(grid.set(Point::new(-10, -1), 1));
+        assert!(!grid.set(Point::new(0, -1), 1));
+        assert_eq!(grid.rect(), rect);
+    }
+
+    /// Sums every value in the grid, written once against the shared trait.
+    fn total<G: GridAccess<i32>>(grid: &G) -> i32 {
+        grid.iter_cells().map(|(_, v)| *v).sum()
+    }
+
+    /// Marks every cell of `grid` that holds a value in `layer`.
+    fn stamp<G: GridAccess<bool>, L: GridAccess<i32>>(grid: &mut G, layer: &L) {
+        for (pt, _) in layer.iter_cells() {
+            grid.set(pt, true);
+        }
+    }
+
+    #[test]
+    fn test_shared_access() {
+        let mut dense = Grid2D::new(4, 4, 0);
+        let mut sparse = SparseGrid::with_size(4, 4);
+        for pt in [Point::new(1, 1), Point::new(2, 3)] {
+            assert!(GridAccess::set(&mut dense, pt, 5));
+            assert!(GridAccess::set(&mut sparse, pt, 5));
+        }
+        assert_eq!(total(&dense), 10);
+        assert_eq!(total(&sparse), 10);
+        assert_eq!(dense.iter_cells().count(), 16);
+        assert_eq!(sparse.iter_cells().count(), 2);
+        assert_eq!(GridAccess::rect(&dense), GridAccess::rect(&sparse));
+
+        let mut marks = Grid2D::new(4, 4, false);
+        stamp(&mut marks, &sparse);
+        assert_eq!(marks.data.iter().filter(|v| **v).count(), 2);
+        assert_eq!(marks.get(Point::new(2, 3)), Some(&true));
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::metric::VectorMetric;
+use crate::{Point, Rect};
+
+/// A data structure that indexes values by position, bucketed into square cells.
+///
+/// Where `TileBin` keeps one bin per tile, a spatial hash groups all values within a
+/// `cell_size` by `cell_size` square into a single cell. Range queries then only visit
+/// the handful of cells overlapping the range instead of every tile in it, which makes
+/// proximity checks among hundreds of actors cheap. A cell size close to the typical
+/// query radius works well.
+pub struct SpatialHash<T: Hash> {
+    cell_size: i32,
+    cells: HashMap<Point, Vec<(T, Point)>>,
+    positions: HashMap<T, Point>,
+}
+
+impl<T: Hash + Eq + Clone> SpatialHash<T> {
+    /// Creates an empty spatial hash with the given cell size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not positive.
+    pub fn new(cell_size: i32) -> Self {
+        assert!(cell_size > 0, "cell size must be positive");
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the width and height of each cell.
+    pub fn cell_size(&self) -> i32 {
+        self.cell_size
+    }
+
+    /// Returns the coordinates of the cell containing `position`.
+    pub fn cell_of(&self, position: Point) -> Point {
+        Point::new(
+            position.x.div_euclid(self.cell_size),
+            position.y.div_euclid(self.cell_size),
+        )
+    }
+
+    /// Returns the number of values in the index.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the index contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the position of a value, if it is in the index.
+    pub fn position_of(&self, value: &T) -> Option<Point> {
+        self.positions.get(value).copied()
+    }
+
+    /// Inserts a value into the index at a given position.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value was inserted, `false` if it was already present.
+    pub fn insert(&mut self, value: T, position: Point) -> bool {
+        if self.positions.contains_key(&value) {
+            return false;
+        }
+        self.positions.insert(value.clone(), position);
+        self.cells
+            .entry(self.cell_of(position))
+            .or_default()
+            .push((value, position));
+        true
+    }
+
+    /// Removes a value from the index, returning the position it was at.
+    pub fn remove(&mut self, value: &T) -> Option<Point> {
+        let position = self.positions.remove(value)?;
+        let cell = self.cell_of(position);
+        if let Some(entries) = self.cells.get_mut(&cell) {
+            entries.retain(|(v, _)| v != value);
+            if entries.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+        Some(position)
+    }
+
+    /// Moves a value to a new position, returning its old position.
+    ///
+    /// Returns `None`, leaving the index unchanged, if the value is not present.
+    pub fn relocate(&mut self, value: &T, new_position: Point) -> Option<Point> {
+        let old_position = *self.positions.get(value)?;
+        let (old_cell, new_cell) =
+            (self.cell_of(old_position), self.cell_of(new_position));
+        if old_cell == new_cell {
+            // staying within a cell only needs the stored position updated
+            let entries = self.cells.get_mut(&old_cell)?;
+            if let Some(entry) = entries.iter_mut().find(|(v, _)| v == value) {
+                entry.1 = new_position;
+            }
+            self.positions.insert(value.clone(), new_position);
+        } else {
+            self.remove(value);
+            self.insert(value.clone(), new_position);
+        }
+        Some(old_position)
+    }
+
+    /// Removes all values from the index.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.positions.clear();
+    }
+
+    /// Returns an iterator over all values at exactly the given position.
+    pub fn values_at(&self, position: Point) -> impl Iterator<Item = &T> {
+        self.cell_entries(self.cell_of(position))
+            .filter(move |(_, pt)| *pt == position)
+            .map(|(value, _)| value)
+    }
+
+    /// Returns an iterator over all values within the given rectangle and their
+    /// positions.
+    ///
+    /// Only the cells overlapping the rectangle are visited, unless the rectangle covers
+    /// more cells than are occupied, in which case the occupied cells are scanned instead.
+    pub fn query_rect(&self, rect: Rect) -> impl Iterator<Item = (&T, Point)> {
+        let cells = self.cells_overlapping(rect);
+        let (by_cell, by_entry) = if cells.count() <= self.cells.len() {
+            (Some(cells.points()), None)
+        } else {
+            (None, Some(&self.cells))
+        };
+        let probed = by_cell
+            .into_iter()
+            .flatten()
+            .flat_map(|cell| self.cell_entries(cell));
+        let scanned = by_entry
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, entries)| entries.iter());
+        probed
+            .chain(scanned)
+            .filter(move |(_, pt)| rect.contains(*pt))
+            .map(|(value, pt)| (value, *pt))
+    }
+
+    /// Returns an iterator over all values within `radius` of `center` under the given
+    /// metric, and their positions.
+    ///
+    /// Distances are compared with `VectorMetric::distance_fast_monotonic`, so the metric
+    /// must measure a point `radius` tiles away along an axis as exactly `radius` away;
+    /// this holds for the Euclidean, Manhattan and Chebyshev metrics.
+    pub fn query_radius<'a, M: VectorMetric<i32, Point>>(
+        &'a self,
+        center: Point,
+        radius: i32,
+        metric: &'a M,
+    ) -> impl Iterator<Item = (&'a T, Point)> {
+        let limit =
+            metric.distance_fast_monotonic(Point::new(0, 0), Point::new(radius, 0));
+        let bounds = Rect::with_points(
+            center - Point::new(radius, radius),
+            center + Point::new(radius + 1, radius + 1),
+        );
+        self.query_rect(bounds)
+            .filter(move |(_, pt)| metric.distance_fast_monotonic(center, *pt) <= limit)
+    }
+
+    /// Returns an iterator over all values and their positions, in cell order.
+    ///
+    /// Cells are visited in row-major order of their coordinates; within a cell, values
+    /// are visited in the order they were inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, Point)> {
+        let mut cells: Vec<Point> = self.cells.keys().copied().collect();
+        cells.sort_by_key(|cell| (cell.y, cell.x));
+        cells
+            .into_iter()
+            .flat_map(|cell| self.cell_entries(cell))
+            .map(|(value, pt)| (value, *pt))
+    }
+
+    fn cell_entries(&self, cell: Point) -> impl Iterator<Item = &(T, Point)> {
+        self.cells
+            .get(&cell)
+            .map(|entries| &entries[..])
+            .unwrap_or(&[])
+            .iter()
+    }
+
+    /// Returns the range of cells overlapping the given rectangle.
+    fn cells_overlapping(&self, rect: Rect) -> CellRange {
+        if rect.max.x <= rect.min.x || rect.max.y <= rect.min.y {
+            // empty rectangles overlap no cells
+            return CellRange {
+                min: Point::new(0, 0),
+                max: Point::new(-1, -1),
+            };
+        }
+        CellRange {
+            min: self.cell_of(rect.min),
+            max: self.cell_of(rect.max - Point::new(1, 1)),
+        }
+    }
+}
+
+/// An inclusive range of cell coordinates.
+struct CellRange {
+    min: Point,
+    max: Point,
+}
+
+impl CellRange {
+    /// Returns the number of cells in the range.
+    fn count(&self) -> usize {
+        let width = (self.max.x as i64 - self.min.x as i64 + 1).max(0) as u64;
+        let height = (self.max.y as i64 - self.min.y as i64 + 1).max(0) as u64;
+        width
+            .saturating_mul(height)
+            .try_into()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Returns the coordinates of every cell in the range, in row-major order.
+    fn points(self) -> impl Iterator<Item = Point> {
+        let CellRange { min, max } = self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Chebyshev, Euclidean, Manhattan};
+    use crate::test_util::{scattered_points, sorted_ids};
+
+    fn scattered(count: u32) -> Vec<(u32, Point)> {
+        // centered on the origin, so cells with negative coordinates are exercised
+        scattered_points(count, 50)
+            .into_iter()
+            .map(|(id, pt)| (id, pt - Point::new(25, 25)))
+            .collect()
+    }
+
+    fn filled(cell_size: i32) -> SpatialHash<u32> {
+        let mut hash = SpatialHash::new(cell_size);
+        for (id, pt) in scattered(120) {
+            assert!(hash.insert(id, pt));
+        }
+        hash
+    }
+
+    fn found_ids<'a>(found: impl Iterator<Item = (&'a u32, Point)>) -> Vec<u32> {
+        sorted_ids(found.map(|(id, _)| *id))
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut hash = SpatialHash::new(4);
+        assert!(hash.insert(1, Point::new(-1, -1)));
+        assert!(!hash.insert(1, Point::new(3, 3)));
+        assert!(hash.insert(2, Point::new(-1, -1)));
+        assert_eq!(hash.cell_of(Point::new(-1, -1)), Point::new(-1, -1));
+        assert_eq!(hash.cell_of(Point::new(4, 3)), Point::new(1, 0));
+        assert_eq!(hash.len(), 2);
+        assert_eq!(hash.values_at(Point::new(-1, -1)).count(), 2);
+        assert_eq!(hash.values_at(Point::new(-2, -1)).count(), 0);
+
+        assert_eq!(hash.remove(&1), Some(Point::new(-1, -1)));
+        assert_eq!(hash.remove(&1), None);
+        assert_eq!(hash.position_of(&2), Some(Point::new(-1, -1)));
+        hash.clear();
+        assert!(hash.is_empty());
+        assert_eq!(hash.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_relocate() {
+        let mut hash = SpatialHash::new(8);
+        hash.insert(1, Point::new(0, 0));
+        assert_eq!(hash.relocate(&1, Point::new(3, 5)), Some(Point::new(0, 0)));
+        assert_eq!(
+            hash.relocate(&1, Point::new(20, -5)),
+            Some(Point::new(3, 5))
+        );
+        assert_eq!(hash.relocate(&2, Point::new(0, 0)), None);
+        assert_eq!(hash.position_of(&1), Some(Point::new(20, -5)));
+        assert_eq!(hash.values_at(Point::new(20, -5)).next(), Some(&1));
+        assert_eq!(hash.values_at(Point::new(3, 5)).next(), None);
+        assert_eq!(hash.cells.len(), 1);
+    }
+
+    #[test]
+    fn test_query_rect_matches_brute_force() {
+        let items = scattered(120);
+        for cell_size in [1, 3, 16] {
+            let hash = filled(cell_size);
+            for rect in [
+                Rect::with_points(Point::new(-10, -10), Point::new(10, 10)),
+                Rect::with_points(Point::new(-25, 0), Point::new(-20, 25)),
+                Rect::with_points(Point::new(5, 5), Point::new(5, 9)),
+            ] {
+                let expected: Vec<u32> = items
+                    .iter()
+                    .filter(|(_, pt)| rect.contains(*pt))
+                    .map(|(id, _)| *id)
+                    .collect();
+                assert_eq!(found_ids(hash.query_rect(rect)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_rect_sparse() {
+        // a map-wide query over a sparse hash scans the occupied cells instead of every
+        // cell in the rectangle
+        let mut hash = SpatialHash::new(1);
+        hash.insert(1, Point::new(-40_000, 7));
+        hash.insert(2, Point::new(123_456, -9));
+        hash.insert(3, Point::new(0, 1_000_000));
+        let world =
+            Rect::with_points(Point::new(-100_000, -100_000), Point::new(200_000, 10));
+        assert_eq!(found_ids(hash.query_rect(world)), vec![1, 2]);
+        assert_eq!(
+            found_ids(
+                hash.query_rect(Rect::with_points(Point::new(0, 0), Point::new(0, 5)))
+            ),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn test_query_radius_matches_brute_force() {
+        fn check<M: VectorMetric<i32, Point>>(metric: M) {
+            let items = scattered(120);
+            let hash = filled(5);
+            let center = Point::new(2, -3);
+            for radius in [0, 4, 11] {
+                let limit = metric
+                    .distance_fast_monotonic(Point::new(0, 0), Point::new(radius, 0));
+                let expected: Vec<u32> = items
+                    .iter()
+                    .filter(|(_, pt)| {
+                        metric.distance_fast_monotonic(center, *pt) <= limit
+                    })
+                    .map(|(id, _)| *id)
+                    .collect();
+                assert_eq!(
+                    found_ids(hash.query_radius(center, radius, &metric)),
+                    expected
+                );
+            }
+        }
+        check(Euclidean);
+        check(Manhattan);
+        check(Chebyshev);
+    }
+
+    #[test]
+    fn test_iter_cell_order() {
+        let hash = filled(10);
+        let cells: Vec<Point> = hash.iter().map(|(_, pt)| hash.cell_of(pt)).collect();
+        assert_eq!(cells.len(), 120);
+        assert!(
+            cells
+                .windows(2)
+                .all(|w| (w[0].y, w[0].x) <= (w[1].y, w[1].x))
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::bsp::{HalfSpace, NodeId, Tree};
+use crate::quadtree::{Node, NodePayload, QuadTree};
+use crate::{Axis2D, Point, Rect};
+
+/// Returns a rectangle with its minimum corner at `(x, y)` and the given size.
+pub(crate) fn rect(x: i32, y: i32, w: i32, h: i32) -> Rect {
+    Rect::with_size(Point::new(x, y), Point::new(w, h))
+}
+
+/// Returns `count` numbered points spread pseudo-randomly over `[0, extent)` on each axis.
+pub(crate) fn scattered_points(count: u32, extent: i32) -> Vec<(u32, Point)> {
+    (0..count)
+        .map(|id| {
+            let i = id as i32;
+            (id, Point::new((i * 37) % extent, (i * 23) % extent))
+        })
+        .collect()
+}
+
+/// Returns `count` numbered rectangles of varying sizes whose minimum corners are
+/// spread over `[0, extent)` on each axis.
+pub(crate) fn scattered_rects(count: u32, extent: i32) -> Vec<(u32, Rect)> {
+    scattered_points(count, extent)
+        .into_iter()
+        .map(|(id, pt)| {
+            let i = id as i32;
+            (id, rect(pt.x, pt.y, 1 + (i * 7) % 9, 1 + (i * 3) % 5))
+        })
+        .collect()
+}
+
+/// Returns the given ids in ascending order.
+pub(crate) fn sorted_ids(ids: impl IntoIterator<Item = u32>) -> Vec<u32> {
+    let mut ids: Vec<u32> = ids.into_iter().collect();
+    ids.sort();
+    ids
+}
+
+/// Runs a callback-based query and returns the ids it reports in ascending order.
+pub(crate) fn query_ids<P>(query: impl FnOnce(&mut dyn FnMut(&(u32, P)))) -> Vec<u32> {
+    let mut ids = vec![];
+    query(&mut |(id, _)| ids.push(*id));
+    sorted_ids(ids)
+}
+
+/// Returns the half space on the positive side of `offset` along `axis`.
+pub(crate) fn split_at(axis: Axis2D, offset: i32) -> HalfSpace {
+    HalfSpace {
+        axis,
+        offset,
+        positive: true,
+    }
+}
+
+/// Splits a 10x10 tree into the following leaves:
+///
+/// ```text
+/// +-----+-----+
+/// |  b  |     |
+/// +-----+  d  |
+/// |     |     |
+/// |  a  +-----+
+/// |     |  c  |
+/// +-----+-----+
+/// ```
+pub(crate) fn four_leaves() -> (Tree<u32>, [NodeId; 4]) {
+    let mut tree = Tree::new(Rect::with_points(Point::new(0, 0), Point::new(10, 10)), 1);
+    let [right, left] = tree
+        .split(tree.root(), split_at(Axis2D::X, 5), |_, _| 0)
+        .unwrap();
+    let [d, c] = tree.split(right, split_at(Axis2D::Y, 6), |_, _| 0).unwrap();
+    let [b, a] = tree.split(left, split_at(Axis2D::Y, 8), |_, _| 0).unwrap();
+    (tree, [a, b, c, d])
+}
+
+/// Returns a quadtree over `[0, 64)` holding 60 scattered points, deep enough to split.
+pub(crate) fn scattered_tree() -> QuadTree<u32> {
+    let bounds = Rect::with_points(Point::new(0, 0), Point::new(64, 64));
+    let mut tree = QuadTree::with_thresholds(bounds, 5, 2, 1);
+    for (id, pos) in scattered_points(60, 64) {
+        tree.insert(id, pos).unwrap();
+    }
+    tree
+}
+
+/// Returns the number of levels below `node`.
+pub(crate) fn depth<T>(node: &Node<T>) -> usize {
+    match node.payload() {
+        NodePayload::Contents(_) => 0,
+        NodePayload::Children(children) => 1 + children.iter().map(depth).max().unwrap(),
+    }
+}
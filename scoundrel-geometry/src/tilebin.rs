@@ -1,12 +1,51 @@
 use std::collections::HashMap;
 use std::hash::Hash;
 
-use crate::Point;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::{Point, Rect};
+
+/// A rule deciding whether a value may enter a tile, given the values already on it.
+type TileRule<T> = Box<dyn Fn(&T, Point, &[T]) -> bool + Send + Sync>;
+
+/// Errors returned when a value cannot be placed on a tile of a `TileBin`.
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TileBinError {
+    /// The tile already holds as many values as the bin's capacity allows.
+    #[error("tile {0:?} is at capacity")]
+    TileFull(Point),
+    /// The bin's tile rule refused to let the value onto the tile.
+    #[error("tile {0:?} is blocked")]
+    Blocked(Point),
+}
+
+/// A change in the contents of a tile, as recorded by a `TileBin`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TileEvent<T> {
+    /// The value was placed on the tile.
+    Entered { value: T, position: Point },
+    /// The value was taken off the tile.
+    Left { value: T, position: Point },
+}
 
 /// A data structure that indexes values based on their position in a 2D grid.
+///
+/// Placement can optionally be restricted, either by a maximum number of values per tile
+/// or by a rule that inspects the tile's current occupants, in which case `insert` and
+/// `relocate` fail with a `TileBinError` and leave the bin unchanged. The bin can also
+/// record a log of `TileEvent`s for triggers such as traps or pressure plates to drain
+/// each turn.
+///
+/// With the `serde` feature, a bin serializes its values, their positions and its
+/// capacity. The tile rule and event log are not saved; set them again after loading.
 pub struct TileBin<T: Hash> {
     bins: HashMap<Point, Vec<T>>,
     positions: HashMap<T, Point>,
+    capacity: Option<usize>,
+    rule: Option<TileRule<T>>,
+    events: Option<Vec<TileEvent<T>>>,
 }
 
 impl<T: Hash> Default for TileBin<T> {
@@ -15,13 +54,88 @@ impl<T: Hash> Default for TileBin<T> {
         Self {
             bins: HashMap::new(),
             positions: HashMap::new(),
+            capacity: None,
+            rule: None,
+            events: None,
         }
     }
 }
 
 impl<T: Hash + Eq + Clone> TileBin<T> {
+    /// Limits the number of values a single tile may hold, or removes the limit.
+    ///
+    /// Tiles already holding more values than the new capacity keep them.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+    }
+
+    /// Sets a rule that must return true for a value to enter a tile.
+    ///
+    /// The rule is called with the value, the tile, and the values already on the tile.
+    /// For example, forbidding two blocking actors from sharing a tile:
+    ///
+    /// ```
+    /// # use scoundrel_geometry::{Point, TileBin};
+    /// # use scoundrel_geometry::tilebin::TileBinError;
+    /// let mut actors = TileBin::default();
+    /// actors.set_rule(|(_, blocking): &(&str, bool), _, occupants: &[(&str, bool)]| {
+    ///     !blocking || occupants.iter().all(|(_, other)| !other)
+    /// });
+    /// actors.insert(("orc", true), Point::new(1, 1)).unwrap();
+    /// actors.insert(("coin", false), Point::new(1, 1)).unwrap();
+    /// assert_eq!(
+    ///     actors.insert(("troll", true), Point::new(1, 1)),
+    ///     Err(TileBinError::Blocked(Point::new(1, 1)))
+    /// );
+    /// ```
+    pub fn set_rule<F: Fn(&T, Point, &[T]) -> bool + Send + Sync + 'static>(
+        &mut self,
+        rule: F,
+    ) {
+        self.rule = Some(Box::new(rule));
+    }
+
+    /// Removes the tile rule, if any.
+    pub fn clear_rule(&mut self) {
+        self.rule = None;
+    }
+
+    /// Turns the recording of `TileEvent`s on or off.
+    ///
+    /// Turning recording off discards any events not yet drained.
+    pub fn set_event_logging(&mut self, enabled: bool) {
+        self.events = enabled.then(Vec::new);
+    }
+
+    /// Removes and returns all events recorded since the last drain, oldest first.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = TileEvent<T>> + '_ {
+        self.events.iter_mut().flat_map(|events| events.drain(..))
+    }
+
+    /// Checks whether `value` may enter the tile at `position`.
+    pub fn can_enter(&self, value: &T, position: Point) -> Result<(), TileBinError> {
+        if self.positions.get(value) == Some(&position) {
+            return Ok(());
+        }
+        let occupants = self.bins.get(&position).map(|bin| &bin[..]).unwrap_or(&[]);
+        if self
+            .capacity
+            .is_some_and(|capacity| occupants.len() >= capacity)
+        {
+            return Err(TileBinError::TileFull(position));
+        }
+        match &self.rule {
+            Some(rule) if !rule(value, position, occupants) => {
+                Err(TileBinError::Blocked(position))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Inserts a value into the index at a given position.
     ///
+    /// A value already in the index at a different position is moved to the new one.
+    ///
     /// # Arguments
     ///
     /// * `value` - The value to insert into the index.
@@ -29,15 +143,16 @@ impl<T: Hash + Eq + Clone> TileBin<T> {
     ///
     /// # Returns
     ///
-    /// `true` if the value was inserted successfully, `false` if the value was already present at the given location.
-    pub fn insert(&mut self, value: T, position: Point) -> bool {
-        let bin = self.bins.entry(position).or_insert_with(Vec::new);
-        if bin.contains(&value) {
-            return false;
+    /// `Ok(true)` if the value was inserted successfully, `Ok(false)` if the value was already present at the given location,
+    /// or an error if the tile's capacity or rule does not allow the value.
+    pub fn insert(&mut self, value: T, position: Point) -> Result<bool, TileBinError> {
+        if self.positions.get(&value) == Some(&position) {
+            return Ok(false);
         }
-        self.positions.insert(value.clone(), position);
-        bin.push(value);
-        true
+        self.can_enter(&value, position)?;
+        self.remove(&value);
+        self.place(value, position);
+        Ok(true)
     }
 
     /// Removes a given value from the index.
@@ -48,28 +163,94 @@ impl<T: Hash + Eq + Clone> TileBin<T> {
     ///
     /// # Returns
     ///
-    /// `true` if the value was found and removed, `false` otherwise.
-    pub fn remove(&mut self, value: &T) -> bool {
-        if let Some(position) = self.positions.get(value) {
-            if let Some(bin) = self.bins.get_mut(position) {
-                bin.retain(|v| v != value);
+    /// The position the value was removed from, or `None` if it was not in the index.
+    pub fn remove(&mut self, value: &T) -> Option<Point> {
+        let position = self.positions.remove(value)?;
+        if let Some(bin) = self.bins.get_mut(&position) {
+            bin.retain(|v| v != value);
+            if bin.is_empty() {
+                self.bins.remove(&position);
             }
         }
-        self.positions.remove(value).is_some()
+        self.record(|| TileEvent::Left {
+            value: value.clone(),
+            position,
+        });
+        Some(position)
     }
 
     /// Changes the position of a given value in the index.
-    pub fn relocate(&mut self, value: T, new_position: Point) {
-        self.remove(&value);
-        self.insert(value, new_position);
+    ///
+    /// # Returns
+    ///
+    /// The previous position of the value, or `None` if it was not in the index, in which
+    /// case it is inserted at `new_position`. Fails without moving the value if the tile's
+    /// capacity or rule does not allow it.
+    pub fn relocate(
+        &mut self,
+        value: T,
+        new_position: Point,
+    ) -> Result<Option<Point>, TileBinError> {
+        let old_position = self.position_of(&value);
+        self.insert(value, new_position)?;
+        Ok(old_position)
     }
 
     /// Remove all values from the index.
+    ///
+    /// When event logging is on, a `TileEvent::Left` is recorded for every value.
     pub fn clear(&mut self) {
+        if let Some(events) = &mut self.events {
+            events.extend(
+                self.positions
+                    .drain()
+                    .map(|(value, position)| TileEvent::Left { value, position }),
+            );
+        }
         self.bins.clear();
         self.positions.clear();
     }
 
+    fn place(&mut self, value: T, position: Point) {
+        self.record(|| TileEvent::Entered {
+            value: value.clone(),
+            position,
+        });
+        self.positions.insert(value.clone(), position);
+        self.bins.entry(position).or_default().push(value);
+    }
+
+    fn record<F: FnOnce() -> TileEvent<T>>(&mut self, event: F) {
+        if let Some(events) = &mut self.events {
+            events.push(event());
+        }
+    }
+
+    /// Returns the position of a value, or `None` if it is not in the index.
+    pub fn position_of(&self, value: &T) -> Option<Point> {
+        self.positions.get(value).copied()
+    }
+
+    /// Returns true if the value is in the index.
+    pub fn contains(&self, value: &T) -> bool {
+        self.positions.contains_key(value)
+    }
+
+    /// Returns the number of values in the index.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the index contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the number of values at a given position.
+    pub fn count_at(&self, position: Point) -> usize {
+        self.bins.get(&position).map_or(0, Vec::len)
+    }
+
     /// Returns an iterator over all values associated with a given position.
     ///
     /// # Arguments
@@ -82,6 +263,76 @@ impl<T: Hash + Eq + Clone> TileBin<T> {
             .unwrap_or(&[])
             .iter()
     }
+
+    /// Returns an iterator over every value in the index and its position, in no
+    /// particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, Point)> {
+        self.positions.iter().map(|(value, pt)| (value, *pt))
+    }
+
+    /// Returns an iterator over every value within the given rectangle and its position.
+    ///
+    /// Small rectangles are answered by looking up each tile they contain, large ones by
+    /// scanning the occupied tiles, whichever visits fewer entries.
+    pub fn query_rect(&self, rect: Rect) -> impl Iterator<Item = (&T, Point)> {
+        let size = rect.size();
+        let area = size.x.max(0) as usize * size.y.max(0) as usize;
+        let (by_tile, by_bin) = if area <= self.bins.len() {
+            (Some(rect.contained_points()), None)
+        } else {
+            (None, Some(&self.bins))
+        };
+        let tiles = by_tile
+            .into_iter()
+            .flatten()
+            .filter_map(|pt| self.bins.get_key_value(&pt));
+        let bins = by_bin
+            .into_iter()
+            .flatten()
+            .filter(move |(pt, _)| rect.contains(**pt));
+        tiles
+            .chain(bins)
+            .flat_map(|(pt, bin)| bin.iter().map(move |value| (value, *pt)))
+    }
+}
+
+/// The serialized form of a `TileBin`, listing each tile's values in order.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct TileBinData<V> {
+    entries: Vec<(V, Point)>,
+    capacity: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Hash + Serialize> Serialize for TileBin<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TileBinData {
+            entries: self
+                .bins
+                .iter()
+                .flat_map(|(pt, bin)| bin.iter().map(move |value| (value, *pt)))
+                .collect(),
+            capacity: self.capacity,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Hash + Eq + Clone + Deserialize<'de>> Deserialize<'de> for TileBin<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = TileBinData::<T>::deserialize(deserializer)?;
+        let mut bin = TileBin::default();
+        for (value, position) in data.entries {
+            if bin.contains(&value) {
+                return Err(serde::de::Error::custom("value appears more than once"));
+            }
+            bin.place(value, position);
+        }
+        bin.capacity = data.capacity;
+        Ok(bin)
+    }
 }
 
 #[cfg(test)]
@@ -93,43 +344,122 @@ mod tests {
     #[test]
     fn test_insert() {
         let mut bin = TileBin::<i32>::default();
-        assert!(bin.insert(1, Point::new(0, 0)));
-        assert!(bin.insert(2, Point::new(0, 0)));
-        assert!(!bin.insert(1, Point::new(0, 0)));
-        assert!(bin.insert(3, Point::new(1, 0)));
-        assert!(bin.insert(4, Point::new(1, 1)));
-        assert!(bin.insert(5, Point::new(2, 2)));
+        assert_eq!(bin.insert(1, Point::new(0, 0)), Ok(true));
+        assert_eq!(bin.insert(2, Point::new(0, 0)), Ok(true));
+        assert_eq!(bin.insert(1, Point::new(0, 0)), Ok(false));
+        assert_eq!(bin.insert(3, Point::new(1, 0)), Ok(true));
+        assert_eq!(bin.insert(4, Point::new(1, 1)), Ok(true));
+        assert_eq!(bin.insert(5, Point::new(2, 2)), Ok(true));
     }
 
     #[test]
     fn test_remove() {
         let mut bin = TileBin::<i32>::default();
-        assert!(!bin.remove(&1));
-        bin.insert(1, Point::new(0, 0));
-        assert!(bin.remove(&1));
-        assert!(!bin.remove(&1));
-        bin.insert(1, Point::new(0, 0));
-        bin.insert(2, Point::new(0, 0));
-        assert!(bin.remove(&1));
-        assert!(bin.remove(&2));
+        assert_eq!(bin.remove(&1), None);
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        assert_eq!(bin.remove(&1), Some(Point::new(0, 0)));
+        assert_eq!(bin.remove(&1), None);
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(0, 0)).unwrap();
+        assert_eq!(bin.remove(&1), Some(Point::new(0, 0)));
+        assert_eq!(bin.remove(&2), Some(Point::new(0, 0)));
     }
 
     #[test]
     fn test_relocate() {
         let mut bin = TileBin::<i32>::default();
-        bin.insert(1, Point::new(0, 0));
-        bin.insert(2, Point::new(0, 1));
-        bin.relocate(1, Point::new(1, 1));
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(0, 1)).unwrap();
+        assert_eq!(
+            bin.relocate(1, Point::new(1, 1)),
+            Ok(Some(Point::new(0, 0)))
+        );
         assert!(bin.values_at(Point::new(0, 0)).next().is_none());
         assert_eq!(bin.values_at(Point::new(1, 1)).next(), Some(&1));
         assert_eq!(bin.values_at(Point::new(0, 1)).next(), Some(&2));
+
+        // relocating a missing value inserts it
+        assert_eq!(bin.relocate(3, Point::new(2, 2)), Ok(None));
+        assert_eq!(bin.position_of(&3), Some(Point::new(2, 2)));
+    }
+
+    #[test]
+    fn test_insert_moves_existing_value() {
+        let mut bin = TileBin::<i32>::default();
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        assert_eq!(bin.insert(1, Point::new(4, 4)), Ok(true));
+        assert_eq!(bin.len(), 1);
+        assert_eq!(bin.count_at(Point::new(0, 0)), 0);
+        assert_eq!(bin.count_at(Point::new(4, 4)), 1);
+        assert!(!bin.bins.contains_key(&Point::new(0, 0)));
+    }
+
+    #[test]
+    fn test_lookup_and_iteration() {
+        let mut bin = TileBin::<i32>::default();
+        assert!(bin.is_empty());
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(0, 0)).unwrap();
+        bin.insert(3, Point::new(-2, 5)).unwrap();
+
+        assert_eq!(bin.len(), 3);
+        assert!(bin.contains(&3));
+        assert!(!bin.contains(&4));
+        assert_eq!(bin.position_of(&3), Some(Point::new(-2, 5)));
+        assert_eq!(bin.position_of(&4), None);
+        assert_eq!(bin.count_at(Point::new(0, 0)), 2);
+
+        let mut entries: Vec<(i32, Point)> = bin.iter().map(|(v, pt)| (*v, pt)).collect();
+        entries.sort_by_key(|(v, _)| *v);
+        assert_eq!(
+            entries,
+            vec![
+                (1, Point::new(0, 0)),
+                (2, Point::new(0, 0)),
+                (3, Point::new(-2, 5))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_rect() {
+        let mut bin = TileBin::<i32>::default();
+        let items: Vec<(i32, Point)> = (0..40)
+            .map(|i| (i, Point::new((i * 7) % 13 - 6, (i * 5) % 11 - 5)))
+            .collect();
+        for (value, pt) in &items {
+            bin.insert(*value, *pt).unwrap();
+        }
+
+        // small rectangles look up tiles, large ones scan bins
+        for rect in [
+            Rect::with_size(Point::new(0, 0), Point::new(2, 2)),
+            Rect::with_size(Point::new(-6, -5), Point::new(13, 11)),
+            Rect::with_size(Point::new(-3, -1), Point::new(20, 3)),
+            Rect::with_size(Point::new(1, 1), Point::new(0, 3)),
+        ] {
+            let mut found: Vec<i32> = bin
+                .query_rect(rect)
+                .map(|(value, pt)| {
+                    assert_eq!(bin.position_of(value), Some(pt));
+                    *value
+                })
+                .collect();
+            found.sort();
+            let expected: Vec<i32> = items
+                .iter()
+                .filter(|(_, pt)| rect.contains(*pt))
+                .map(|(value, _)| *value)
+                .collect();
+            assert_eq!(found, expected);
+        }
     }
 
     #[test]
     fn test_clear() {
         let mut bin = TileBin::<i32>::default();
-        bin.insert(1, Point::new(0, 0));
-        bin.insert(2, Point::new(1, 1));
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(1, 1)).unwrap();
         bin.clear();
         assert!(bin.values_at(Point::new(0, 0)).next().is_none());
         assert!(bin.values_at(Point::new(1, 1)).next().is_none());
@@ -140,9 +470,9 @@ mod tests {
         let mut bin = TileBin::<i32>::default();
 
         // Insert multiple values at the same position
-        bin.insert(1, Point::new(5, 5));
-        bin.insert(2, Point::new(5, 5));
-        bin.insert(3, Point::new(5, 5));
+        bin.insert(1, Point::new(5, 5)).unwrap();
+        bin.insert(2, Point::new(5, 5)).unwrap();
+        bin.insert(3, Point::new(5, 5)).unwrap();
 
         // Collect values into a set to compare regardless of order
         let values: HashSet<i32> = bin.values_at(Point::new(5, 5)).cloned().collect();
@@ -166,7 +496,7 @@ mod tests {
     #[test]
     fn test_remove_nonexistent_bin() {
         let mut bin = TileBin::<i32>::default();
-        bin.insert(1, Point::new(0, 0));
+        bin.insert(1, Point::new(0, 0)).unwrap();
 
         // Remove item, then try to access its original position
         bin.remove(&1);
@@ -175,20 +505,16 @@ mod tests {
 
         // Verify internal hashmap cleanup
         assert!(!bin.positions.contains_key(&1));
-        assert!(
-            bin.bins
-                .get(&Point::new(0, 0))
-                .map_or(true, |v| v.is_empty())
-        );
+        assert!(bin.bins.get(&Point::new(0, 0)).is_none_or(|v| v.is_empty()));
     }
 
     #[test]
     fn test_relocate_to_same_position() {
         let mut bin = TileBin::<i32>::default();
-        bin.insert(1, Point::new(3, 3));
+        bin.insert(1, Point::new(3, 3)).unwrap();
 
         // Relocate to the same position
-        bin.relocate(1, Point::new(3, 3));
+        bin.relocate(1, Point::new(3, 3)).unwrap();
 
         // Should still be there
         assert_eq!(bin.values_at(Point::new(3, 3)).next(), Some(&1));
@@ -199,12 +525,12 @@ mod tests {
         let mut bin = TileBin::<i32>::default();
 
         // Setup initial state
-        bin.insert(1, Point::new(0, 0));
-        bin.insert(2, Point::new(1, 1));
-        bin.insert(3, Point::new(1, 1));
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(1, 1)).unwrap();
+        bin.insert(3, Point::new(1, 1)).unwrap();
 
         // Relocate to a position that already has items
-        bin.relocate(1, Point::new(1, 1));
+        bin.relocate(1, Point::new(1, 1)).unwrap();
 
         // Check original position is empty
         assert!(bin.values_at(Point::new(0, 0)).next().is_none());
@@ -235,15 +561,15 @@ mod tests {
             name: "Enemy".to_string(),
         };
 
-        bin.insert(e1.clone(), Point::new(10, 10));
-        bin.insert(e2.clone(), Point::new(20, 20));
+        bin.insert(e1.clone(), Point::new(10, 10)).unwrap();
+        bin.insert(e2.clone(), Point::new(20, 20)).unwrap();
 
         // Check entities are at the correct positions
         assert_eq!(bin.values_at(Point::new(10, 10)).next().unwrap().id, 1);
         assert_eq!(bin.values_at(Point::new(20, 20)).next().unwrap().id, 2);
 
         // Relocate one entity
-        bin.relocate(e1.clone(), Point::new(15, 15));
+        bin.relocate(e1.clone(), Point::new(15, 15)).unwrap();
 
         // Verify relocation
         assert!(bin.values_at(Point::new(10, 10)).next().is_none());
@@ -255,9 +581,9 @@ mod tests {
         let mut bin = TileBin::<i32>::default();
 
         // Insert multiple items at different positions
-        bin.insert(1, Point::new(0, 0));
-        bin.insert(2, Point::new(1, 1));
-        bin.insert(3, Point::new(2, 2));
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(1, 1)).unwrap();
+        bin.insert(3, Point::new(2, 2)).unwrap();
 
         // Remove the only item at a position
         bin.remove(&2);
@@ -269,4 +595,118 @@ mod tests {
         assert_eq!(bin.values_at(Point::new(0, 0)).next(), Some(&1));
         assert_eq!(bin.values_at(Point::new(2, 2)).next(), Some(&3));
     }
+
+    #[test]
+    fn test_capacity() {
+        let mut bin = TileBin::<i32>::default();
+        bin.set_capacity(Some(2));
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.insert(2, Point::new(0, 0)).unwrap();
+        assert_eq!(
+            bin.insert(3, Point::new(0, 0)),
+            Err(TileBinError::TileFull(Point::new(0, 0)))
+        );
+        assert!(!bin.contains(&3));
+
+        // re-inserting a value where it already is never fails
+        assert_eq!(bin.insert(1, Point::new(0, 0)), Ok(false));
+
+        bin.insert(3, Point::new(1, 0)).unwrap();
+        assert_eq!(
+            bin.relocate(3, Point::new(0, 0)),
+            Err(TileBinError::TileFull(Point::new(0, 0)))
+        );
+        assert_eq!(bin.position_of(&3), Some(Point::new(1, 0)));
+
+        bin.set_capacity(None);
+        assert_eq!(
+            bin.relocate(3, Point::new(0, 0)),
+            Ok(Some(Point::new(1, 0)))
+        );
+        assert_eq!(bin.count_at(Point::new(0, 0)), 3);
+    }
+
+    #[test]
+    fn test_blocking_rule() {
+        // negative values are blocking actors, positive ones are items
+        let mut bin = TileBin::<i32>::default();
+        bin.set_rule(|value, position, occupants| {
+            position.x >= 0 && (*value > 0 || occupants.iter().all(|other| *other > 0))
+        });
+        bin.insert(-1, Point::new(2, 2)).unwrap();
+        bin.insert(5, Point::new(2, 2)).unwrap();
+        bin.insert(-2, Point::new(3, 2)).unwrap();
+        assert_eq!(
+            bin.relocate(-2, Point::new(2, 2)),
+            Err(TileBinError::Blocked(Point::new(2, 2)))
+        );
+        assert_eq!(
+            bin.insert(6, Point::new(-1, 0)),
+            Err(TileBinError::Blocked(Point::new(-1, 0)))
+        );
+        assert_eq!(bin.position_of(&-2), Some(Point::new(3, 2)));
+
+        bin.remove(&-1);
+        assert_eq!(
+            bin.relocate(-2, Point::new(2, 2)),
+            Ok(Some(Point::new(3, 2)))
+        );
+
+        bin.clear_rule();
+        assert_eq!(bin.insert(6, Point::new(-1, 0)), Ok(true));
+    }
+
+    #[test]
+    fn test_events() {
+        let mut bin = TileBin::<i32>::default();
+        bin.insert(1, Point::new(0, 0)).unwrap();
+        bin.set_event_logging(true);
+        assert_eq!(bin.drain_events().count(), 0);
+
+        bin.insert(2, Point::new(1, 1)).unwrap();
+        bin.relocate(1, Point::new(1, 1)).unwrap();
+        bin.relocate(1, Point::new(1, 1)).unwrap();
+        bin.remove(&2);
+        assert_eq!(
+            bin.drain_events().collect::<Vec<_>>(),
+            vec![
+                TileEvent::Entered {
+                    value: 2,
+                    position: Point::new(1, 1)
+                },
+                TileEvent::Left {
+                    value: 1,
+                    position: Point::new(0, 0)
+                },
+                TileEvent::Entered {
+                    value: 1,
+                    position: Point::new(1, 1)
+                },
+                TileEvent::Left {
+                    value: 2,
+                    position: Point::new(1, 1)
+                },
+            ]
+        );
+        assert_eq!(bin.drain_events().count(), 0);
+
+        // failed moves record nothing
+        bin.set_capacity(Some(0));
+        assert!(bin.insert(3, Point::new(5, 5)).is_err());
+        assert_eq!(bin.drain_events().count(), 0);
+
+        bin.clear();
+        assert_eq!(
+            bin.drain_events().collect::<Vec<_>>(),
+            vec![TileEvent::Left {
+                value: 1,
+                position: Point::new(1, 1)
+            }]
+        );
+
+        bin.set_event_logging(false);
+        bin.set_capacity(None);
+        bin.insert(4, Point::new(0, 0)).unwrap();
+        assert_eq!(bin.drain_events().count(), 0);
+    }
 }
@@ -259,6 +259,7 @@ impl<T: Ring + std::ops::Sub<Output = T> + Copy> Vector3<T> {
 macro_rules! define_axes {
     ($(#[$outer:meta])* $name:ident {$($case:ident),+}, $vector:ident) => {
         $(#[$outer])*
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[derive(Debug, Copy, Clone, Eq, PartialEq)]
         pub enum $name {
             $(
@@ -0,0 +1,213 @@
+#![cfg(feature = "serde_json")]
+
+use scoundrel_geometry::bsp::{self, Tree};
+use scoundrel_geometry::quadtree::{self, Node, QuadTree, RegionQuadTree};
+use scoundrel_geometry::tilebin::TileBinError;
+use scoundrel_geometry::{Axis2D, BitGrid, OrthoLine, Point, Rect, TileBin};
+
+// Shares the unit tests' fixtures, which only rely on the public API. They name items
+// through `crate::`, which resolves to the imports above.
+#[allow(dead_code)]
+#[path = "../src/test_util.rs"]
+mod test_util;
+
+use test_util::{depth, four_leaves, scattered_tree, split_at};
+
+type LeafDescription = (Rect, u32, Vec<(OrthoLine, Rect)>);
+
+/// Describes each leaf by its bounds, contents and the bounds of its neighbors.
+fn describe(tree: &Tree<u32>) -> Vec<LeafDescription> {
+    tree.leaves()
+        .map(|(_, node)| {
+            let edges = node
+                .edges
+                .iter()
+                .map(|edge| (edge.line, tree[edge.neighbor].bounds))
+                .collect();
+            (node.bounds, node.contents, edges)
+        })
+        .collect()
+}
+
+#[test]
+fn test_bsp_roundtrip() {
+    let (tree, [a, ..]) = four_leaves();
+    let json = serde_json::to_string(&tree).unwrap();
+    let mut restored: Tree<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(describe(&restored), describe(&tree));
+
+    // the restored tree can still be split
+    assert!(
+        restored
+            .split(a, split_at(Axis2D::X, 2), |c, _| *c)
+            .is_some()
+    );
+    assert_eq!(restored.leaves().count(), 5);
+}
+
+#[test]
+fn test_bsp_deserialize_invalid_tree() {
+    let bounds = r#"{"min":{"x":0,"y":0},"max":{"x":4,"y":4}}"#;
+    let node = |parent: &str, children: &str| {
+        format!(
+            r#"{{"bounds":{bounds},"parent":{parent},"children":{children},"edges":[],"contents":0}}"#
+        )
+    };
+    assert!(
+        serde_json::from_str::<Tree<u32>>(&format!("[{}]", node("null", "null"))).is_ok()
+    );
+    for json in [
+        "[]".to_string(),
+        format!("[{}]", node("null", "[0,1]")),
+        format!("[{},{}]", node("null", "null"), node("null", "null")),
+        format!("[{},{}]", node("null", "null"), node("0", "null")),
+    ] {
+        assert!(serde_json::from_str::<Tree<u32>>(&json).is_err(), "{json}");
+    }
+}
+
+#[test]
+fn test_quadtree_roundtrip() {
+    let tree = scattered_tree();
+    let json = serde_json::to_string(&tree).unwrap();
+    let mut restored: QuadTree<u32> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.len(), tree.len());
+    assert_eq!(depth(restored.root()), depth(tree.root()));
+    let query = Point::new(20, 40);
+    assert_eq!(restored.k_nearest(query, 5), tree.k_nearest(query, 5));
+
+    // the restored tree keeps its thresholds and remains editable
+    let pos = Point::new(1, 1);
+    restored.insert(100, pos).unwrap();
+    assert!(restored.relocate(&100, pos, Point::new(60, 60)));
+    assert_eq!(restored.nearest(Point::new(63, 63)).unwrap().1.0, 100);
+
+    let node: Node<u32> =
+        serde_json::from_str(&serde_json::to_string(tree.root()).unwrap()).unwrap();
+    assert_eq!(node.bounds(), tree.bounds());
+    assert_eq!(node.len(), tree.len());
+}
+
+#[test]
+fn test_tilebin_roundtrip() {
+    let mut bin = TileBin::<i32>::default();
+    bin.set_capacity(Some(3));
+    bin.set_event_logging(true);
+    for (value, x) in [(1, 0), (2, 0), (3, 4), (4, 0)] {
+        bin.insert(value, Point::new(x, -x)).unwrap();
+    }
+
+    let json = serde_json::to_string(&bin).unwrap();
+    let mut restored: TileBin<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.len(), 4);
+    for position in [Point::new(0, 0), Point::new(4, -4)] {
+        assert_eq!(
+            restored.values_at(position).collect::<Vec<_>>(),
+            bin.values_at(position).collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(restored.position_of(&3), Some(Point::new(4, -4)));
+    assert_eq!(restored.drain_events().count(), 0);
+    assert_eq!(
+        restored.insert(5, Point::new(0, 0)),
+        Err(TileBinError::TileFull(Point::new(0, 0)))
+    );
+}
+
+#[test]
+fn test_tilebin_deserialize_duplicate_value() {
+    let json = r#"{"entries":[[1,{"x":0,"y":0}],[1,{"x":1,"y":0}]],"capacity":null}"#;
+    assert!(serde_json::from_str::<TileBin<i32>>(json).is_err());
+}
+
+#[test]
+fn test_bit_grid_roundtrip() {
+    let mut grid = BitGrid::new(70, 3);
+    grid.set(Point::new(0, 0), true);
+    grid.set(Point::new(69, 2), true);
+    let json = serde_json::to_string(&grid).unwrap();
+    assert_eq!(serde_json::from_str::<BitGrid>(&json).unwrap(), grid);
+}
+
+#[test]
+fn test_bit_grid_deserialize_invalid() {
+    for json in [
+        // too few words for the dimensions
+        r#"{"words":[0,0,0],"width":70,"height":2,"words_per_row":2}"#,
+        // words per row disagrees with the width
+        r#"{"words":[0,0],"width":70,"height":2,"words_per_row":1}"#,
+        // a bit set past the end of the row
+        r#"{"words":[1024],"width":10,"height":1,"words_per_row":1}"#,
+    ] {
+        assert!(serde_json::from_str::<BitGrid>(json).is_err(), "{json}");
+    }
+    let valid = r#"{"words":[512],"width":10,"height":1,"words_per_row":1}"#;
+    let grid = serde_json::from_str::<BitGrid>(valid).unwrap();
+    assert_eq!(grid.get(Point::new(9, 0)), Some(true));
+}
+
+#[test]
+fn test_quadtree_deserialize_invalid() {
+    let mut tree = QuadTree::with_thresholds(
+        Rect::with_points(Point::new(0, 0), Point::new(8, 8)),
+        2,
+        1,
+        0,
+    );
+    tree.insert(0u32, Point::new(1, 1)).unwrap();
+    tree.insert(1, Point::new(6, 6)).unwrap();
+    let valid = serde_json::to_value(&tree).unwrap();
+    assert!(serde_json::from_value::<QuadTree<u32>>(valid.clone()).is_ok());
+
+    let corrupt = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut json = valid.clone();
+        edit(&mut json);
+        serde_json::from_value::<QuadTree<u32>>(json).is_err()
+    };
+    // merge threshold above the split threshold
+    assert!(corrupt(&|json| json["merge_threshold"] = 5.into()));
+    // deeper than the max depth
+    assert!(corrupt(&|json| json["max_depth"] = 0.into()));
+    // a child that doesn't cover its quadrant
+    assert!(corrupt(&|json| {
+        json["root"]["payload"]["Children"][0]["bounds"]["min"]["x"] = 1.into()
+    }));
+    // an item outside its leaf
+    assert!(corrupt(&|json| {
+        let children = &mut json["root"]["payload"]["Children"];
+        let leaf = children
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|child| child["payload"]["Contents"].as_array().unwrap().len() == 1)
+            .unwrap();
+        leaf["payload"]["Contents"][0][1]["x"] = 100.into();
+    }));
+}
+
+#[test]
+fn test_region_quadtree_roundtrip() {
+    let bounds = Rect::with_points(Point::new(0, 0), Point::new(32, 32));
+    let mut tree = RegionQuadTree::new(bounds, 3);
+    tree.insert(0u32, Rect::with_points(Point::new(1, 1), Point::new(3, 3)));
+    tree.insert(1, Rect::with_points(Point::new(10, 20), Point::new(22, 24)));
+    tree.insert(2, Rect::with_points(Point::new(-4, -4), Point::new(40, 3)));
+
+    let json = serde_json::to_value(&tree).unwrap();
+    let restored: RegionQuadTree<u32> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(restored.len(), 3);
+    let mut found = vec![];
+    restored.query_point(Point::new(2, 2), |(id, _)| found.push(*id));
+    found.sort();
+    assert_eq!(found, vec![0, 2]);
+
+    // the tree has children, so it is deeper than a max depth of zero allows
+    let mut shallow = json.clone();
+    shallow["max_depth"] = 0.into();
+    assert!(serde_json::from_value::<RegionQuadTree<u32>>(shallow).is_err());
+
+    let mut misplaced = json;
+    misplaced["root"]["children"][2]["bounds"]["max"]["x"] = 1.into();
+    assert!(serde_json::from_value::<RegionQuadTree<u32>>(misplaced).is_err());
+}
@@ -156,8 +156,6 @@ mod tests {
     // Test serde if the feature is enabled
     #[cfg(feature = "serde_json")]
     mod serde_tests {
-        use serde_json;
-
         use super::*;
 
         #[test]
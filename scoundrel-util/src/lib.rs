@@ -1,10 +1,12 @@
 mod ascii_glyph;
 mod min_heap_entry;
 pub mod numeric;
+mod rng;
 
 pub use ascii_glyph::AsciiGlyph;
 pub use min_heap_entry::MinHeapEntry;
 pub use numeric::NonNaN32;
+pub use rng::SeededRng;
 
 /// Macro that ignores the first identifier and returns the tail of the pattern.
 ///
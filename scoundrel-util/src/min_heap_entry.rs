@@ -32,8 +32,7 @@ impl<T, P: Ord> PartialOrd<Self> for MinHeapEntry<T, P> {
     /// Returns `Some(Ordering)` if the priorities are comparable, and `None`
     /// otherwise.
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        // flip the ordering to get a min-heap
-        other.priority.partial_cmp(&self.priority)
+        Some(self.cmp(other))
     }
 }
 
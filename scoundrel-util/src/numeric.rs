@@ -63,6 +63,26 @@ impl HasSqrt for f64 {
         self.sqrt()
     }
 }
+/// Integer square roots are rounded down.
+///
+/// # Panics
+///
+/// Panics if the value is negative, where the float implementations return NaN.
+impl HasSqrt for i32 {
+    fn _sqrt(&self) -> Self {
+        self.isqrt()
+    }
+}
+/// Integer square roots are rounded down.
+///
+/// # Panics
+///
+/// Panics if the value is negative, where the float implementations return NaN.
+impl HasSqrt for i64 {
+    fn _sqrt(&self) -> Self {
+        self.isqrt()
+    }
+}
 
 /// A trait for types that have an absolute value function.
 pub trait HasAbs {
@@ -148,7 +168,7 @@ impl Eq for NonNaN32 {}
 
 impl PartialOrd<Self> for NonNaN32 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.value.partial_cmp(&other.value)
+        Some(self.cmp(other))
     }
 }
 
@@ -264,6 +284,17 @@ mod tests {
 
         let x: f64 = 25.0;
         assert_eq!(x._sqrt(), 5.0);
+
+        // Integer square roots round down
+        assert_eq!(25i32._sqrt(), 5);
+        assert_eq!(26i32._sqrt(), 5);
+        assert_eq!(35i64._sqrt(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_has_sqrt_negative_integer() {
+        (-1i32)._sqrt();
     }
 
     #[test]
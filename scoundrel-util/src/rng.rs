@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+/// A small, fast pseudo-random number generator seeded from a single `u64`.
+///
+/// This is the SplitMix64 generator. It is not suitable for cryptography, but its output
+/// for a given seed is fixed, so procedural generation driven by it reproduces exactly
+/// from the same seed across platforms and versions.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a float uniformly distributed in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns an integer uniformly distributed in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn range(&mut self, range: Range<i32>) -> i32 {
+        assert!(range.start < range.end, "cannot sample from an empty range");
+        let span = (range.end as i64 - range.start as i64) as u64;
+        // multiply-shift maps 64 random bits onto the span without a modulo
+        let offset = ((self.next_u64() as u128 * span as u128) >> 64) as i64;
+        (range.start as i64 + offset) as i32
+    }
+
+    /// Returns true with probability `p`.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// Shuffles a slice in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            let other = self.range(0..idx as i32 + 1) as usize;
+            items.swap(idx, other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = SeededRng::new(42);
+        let mut b = SeededRng::new(42);
+        let mut c = SeededRng::new(43);
+        let xs: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        assert_eq!(xs, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(xs, (0..8).map(|_| c.next_u64()).collect::<Vec<_>>());
+        // reference output of SplitMix64 seeded with 0
+        assert_eq!(SeededRng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = SeededRng::new(7);
+        let mut seen = [false; 5];
+        for _ in 0..1000 {
+            let x = rng.range(-2..3);
+            assert!((-2..3).contains(&x));
+            seen[(x + 2) as usize] = true;
+            let f = rng.next_f32();
+            assert!((0.0..1.0).contains(&f));
+        }
+        assert!(seen.iter().all(|s| *s));
+        assert_eq!(rng.range(i32::MIN..i32::MIN + 1), i32::MIN);
+
+        let mut items: Vec<i32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+}